@@ -0,0 +1,33 @@
+#!/bin/sh
+# A stand-in for an implementation of the Machine component in samples/json/EcdarUniversity for `Reveaal test-run`.
+# It speaks the line protocol of src/Simulation/test_run.rs and serves coffee 4 time units after a coin is inserted,
+# or after the number of time units given as its first argument.
+serve=${1:-4}
+now=0
+paid=""
+
+while read -r command argument; do
+    case "$command" in
+        input)
+            # Coins inserted while waiting for the coffee are kept
+            if [ "$argument" = coin ] && [ -z "$paid" ]; then
+                paid=$now
+            fi
+            echo "ok $now"
+            ;;
+        wait)
+            end=$((now + argument))
+            if [ -n "$paid" ] && [ $((paid + serve)) -le "$end" ]; then
+                now=$((paid + serve))
+                paid=""
+                echo "output cof $now"
+            else
+                now=$end
+                echo "quiet $now"
+            fi
+            ;;
+        *)
+            echo "error unknown command $command"
+            ;;
+    esac
+done
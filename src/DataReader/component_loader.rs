@@ -5,7 +5,8 @@ use crate::xml_parser;
 use crate::DataReader::json_reader;
 use crate::DataReader::json_writer::component_to_json_file;
 use crate::DataReader::xml_parser::parse_xml_from_file;
-use crate::ModelObjects::{Component, Query, SystemDeclarations};
+use crate::ModelObjects::Expressions::SystemExpression;
+use crate::ModelObjects::{resolve_systems, Component, Query, SystemDeclarations, SystemDiagram};
 use crate::ProtobufServer::services;
 use crate::ProtobufServer::services::query_request::Settings;
 use crate::System::input_enabler;
@@ -112,6 +113,11 @@ pub trait ComponentLoader {
     fn save_component(&mut self, component: Component);
     fn get_settings(&self) -> &Settings;
     fn get_settings_mut(&mut self) -> &mut Settings;
+
+    /// Returns the system named `system_name`, or an error if the system is invalid, if the loader has such a system
+    fn get_system(&self, _system_name: &str) -> Option<Result<SystemExpression, String>> {
+        None
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -175,7 +181,7 @@ pub fn parse_components_if_some(
     if let Some(rep) = &proto_component.rep {
         match rep {
             services::component::Rep::Json(json) => parse_json_component(json),
-            services::component::Rep::Xml(xml) => Ok(parse_xml_components(xml)),
+            services::component::Rep::Xml(xml) => parse_xml_components(xml),
         }
     } else {
         Ok(vec![])
@@ -185,15 +191,21 @@ pub fn parse_components_if_some(
 fn parse_json_component(json: &str) -> Result<Vec<Component>, tonic::Status> {
     match json_reader::json_to_component(json) {
         Ok(comp) => Ok(vec![comp]),
-        Err(_) => Err(tonic::Status::invalid_argument(
-            "Failed to parse json component",
-        )),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse json component: {}",
+            err
+        ))),
     }
 }
 
-fn parse_xml_components(xml: &str) -> Vec<Component> {
-    let (comps, _, _) = xml_parser::parse_xml_from_str(xml);
-    comps
+fn parse_xml_components(xml: &str) -> Result<Vec<Component>, tonic::Status> {
+    match xml_parser::parse_xml_from_str(xml) {
+        Ok((comps, _, _)) => Ok(comps),
+        Err(err) => Err(tonic::Status::invalid_argument(format!(
+            "Failed to parse xml components: {}",
+            err
+        ))),
+    }
 }
 
 pub trait ProjectLoader: ComponentLoader {
@@ -206,6 +218,7 @@ pub trait ProjectLoader: ComponentLoader {
 pub struct JsonProjectLoader {
     project_path: PathBuf,
     loaded_components: ComponentsMap,
+    systems: HashMap<String, Result<SystemExpression, String>>,
     system_declarations: SystemDeclarations,
     queries: Vec<Query>,
     settings: Settings,
@@ -237,6 +250,10 @@ impl ComponentLoader for JsonProjectLoader {
     fn get_settings_mut(&mut self) -> &mut Settings {
         &mut self.settings
     }
+
+    fn get_system(&self, system_name: &str) -> Option<Result<SystemExpression, String>> {
+        self.systems.get(system_name).cloned()
+    }
 }
 
 impl ProjectLoader for JsonProjectLoader {
@@ -265,10 +282,27 @@ impl JsonProjectLoader {
     ) -> Box<dyn ProjectLoader> {
         let system_declarations = json_reader::read_system_declarations(&project_path).unwrap();
         let queries = json_reader::read_queries(&project_path).unwrap();
+        let components_path = project_path.as_ref().join("Components");
+        let is_component = |name: &str| components_path.join(format!("{}.json", name)).exists();
+        let read_systems = json_reader::read_systems(&project_path);
+        let diagrams: Vec<SystemDiagram> = read_systems
+            .iter()
+            .filter_map(|(_, system)| system.as_ref().ok().cloned())
+            .collect();
+        let mut systems = resolve_systems(&diagrams, is_component);
+        // Systems which could not be parsed are named by their files, and reported when they are used
+        for (name, system) in read_systems {
+            if let Err(err) = system {
+                if !is_component(&name) {
+                    systems.entry(name).or_insert(Err(err));
+                }
+            }
+        }
 
         Box::new(JsonProjectLoader {
             project_path: project_path.as_ref().to_path_buf(),
             loaded_components: HashMap::new(),
+            systems,
             system_declarations,
             queries,
             settings,
@@ -348,7 +382,14 @@ impl XmlProjectLoader {
         project_path: P,
         settings: Settings,
     ) -> Box<dyn ProjectLoader> {
-        let (comps, system_declarations, queries) = parse_xml_from_file(&project_path);
+        let (comps, system_declarations, queries) = parse_xml_from_file(&project_path)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Could not read xml project {}: {}",
+                    project_path.as_ref().display(),
+                    err
+                )
+            });
 
         let mut map = HashMap::<String, Component>::new();
         for mut component in comps {
@@ -1,4 +1,4 @@
-use crate::ModelObjects::{Component, Query, SystemDeclarations};
+use crate::ModelObjects::{Component, Query, SystemDeclarations, SystemDiagram};
 use serde::de::DeserializeOwned;
 use std::fs::File;
 use std::io::Read;
@@ -27,7 +27,7 @@ pub fn read_json_component<P: AsRef<Path>>(project_path: P, component_name: &str
         .join("Components")
         .join(format!("{}.json", component_name));
 
-    let component: Component = match read_json(&component_path) {
+    let mut component: Component = match read_json(&component_path) {
         Ok(json) => json,
         Err(error) => panic!(
             "We got error {}, and could not parse json file {} to component",
@@ -35,6 +35,13 @@ pub fn read_json_component<P: AsRef<Path>>(project_path: P, component_name: &str
             component_path.display()
         ),
     };
+    if let Err(error) = component.substitute_constants() {
+        panic!(
+            "We got error {}, and could not read component {}",
+            error,
+            component_path.display()
+        );
+    }
 
     component
 }
@@ -48,18 +55,52 @@ pub fn read_json<T: DeserializeOwned, P: AsRef<Path>>(filename: P) -> serde_json
     let mut data = String::new();
     file.read_to_string(&mut data).unwrap();
 
-    let json_file = serde_json::from_str(&data).unwrap_or_else(|_| {
-        panic!(
-            "{}: Json format is not as expected",
-            filename.as_ref().display()
-        )
-    });
-
-    Ok(json_file)
+    serde_json::from_str(&data)
 }
 
 pub fn json_to_component(json_str: &str) -> Result<Component, serde_json::Error> {
-    serde_json::from_str(json_str)
+    let mut component: Component = serde_json::from_str(json_str)?;
+    component
+        .substitute_constants()
+        .map_err(serde::de::Error::custom)?;
+    Ok(component)
+}
+
+/// Reads the systems in the `Systems` folder of the project, which is optional, along with the names of their files.
+/// A file which cannot be parsed as a system is returned as an error, so the rest of the project can still be used.
+pub fn read_systems<P: AsRef<Path>>(
+    project_path: P,
+) -> Vec<(String, Result<SystemDiagram, String>)> {
+    let systems_path = project_path.as_ref().join("Systems");
+
+    let entries = match std::fs::read_dir(&systems_path) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let system = read_json(&path).map_err(|error| {
+                format!(
+                    "Could not parse json file {} to system: {}",
+                    path.display(),
+                    error
+                )
+            });
+            (name, system)
+        })
+        .collect()
 }
 
 //Input:Filename
@@ -1,4 +1,6 @@
 use crate::ModelObjects::Component;
+use crate::System::specifics::SpecificRelation;
+use serde::Serialize;
 use std::{fs::File, path::Path};
 
 pub fn component_to_json_file<P: AsRef<Path>>(project_path: P, component: &Component) {
@@ -15,3 +17,24 @@ pub fn component_to_json_file<P: AsRef<Path>>(project_path: P, component: &Compo
 pub fn component_to_json(component: &Component) -> String {
     serde_json::to_string(component).unwrap()
 }
+
+#[derive(Serialize)]
+struct SavedRelation<'a> {
+    query: &'a str,
+    relation: &'a SpecificRelation,
+}
+
+/// Writes the refinement `relations` of the given queries to a json file at `path`.
+pub fn refinement_relations_to_json_file<P: AsRef<Path>>(
+    path: P,
+    relations: &[(String, &SpecificRelation)],
+) {
+    let relations: Vec<SavedRelation> = relations
+        .iter()
+        .map(|(query, relation)| SavedRelation { query, relation })
+        .collect();
+
+    let file = File::create(path).expect("Couldnt open file");
+
+    serde_json::to_writer_pretty(&file, &relations).expect("Failed to serialize relations");
+}
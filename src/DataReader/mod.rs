@@ -1,6 +1,7 @@
 pub mod component_loader;
 pub mod json_reader;
 pub mod json_writer;
+pub mod parse_declarations;
 pub mod parse_edge;
 pub mod parse_queries;
 pub mod proto_reader;
@@ -0,0 +1,148 @@
+extern crate pest;
+
+use crate::ModelObjects::Declarations;
+use edbm::util::constraints::ClockIndex;
+use pest::error::{Error, ErrorVariant};
+use pest::iterators::Pair;
+use pest::Parser;
+
+///This file handles parsing the declarations of components based on the abstract syntax described in the .pest files in the grammar folder
+///For clarification see documentation on pest crate
+#[derive(Parser)]
+#[grammar = "DataReader/grammars/declaration_grammar.pest"]
+pub struct DeclarationParser;
+
+type ParseResult<T> = Result<T, Box<Error<Rule>>>;
+
+/// Parses the declarations `input` of a component, e.g. `clock x, y; const int MAX = 10; int[0,MAX] n = 2;`.
+/// The clocks are given indices starting from 1 in the order they are declared.
+///
+/// Errors contain the line and column of the failing declaration.
+pub fn parse_declarations(input: &str) -> Result<Declarations, String> {
+    parse_declarations_helper(input)
+        .map_err(|e| format!("Could not parse declarations with error: {}", e))
+}
+
+fn parse_declarations_helper(input: &str) -> ParseResult<Declarations> {
+    let pairs = DeclarationParser::parse(Rule::declarations, input).map_err(Box::new)?;
+
+    let mut decls = Declarations::empty();
+    let mut counter: ClockIndex = 1;
+    for pair in pairs.flat_map(|pair| pair.into_inner()) {
+        match pair.as_rule() {
+            Rule::clock_decl => {
+                for var in pair.into_inner().filter(|p| p.as_rule() == Rule::variable) {
+                    let name = declare_name(&decls, &var)?;
+                    decls.clocks.insert(name, counter);
+                    counter += 1;
+                }
+            }
+            Rule::const_decl => {
+                for const_var in pair.into_inner().filter(|p| p.as_rule() == Rule::const_var) {
+                    let mut inner = const_var.into_inner();
+                    let var = inner.next().unwrap();
+                    let name = declare_name(&decls, &var)?;
+                    let value = parse_value(&decls, inner.next().unwrap())?;
+                    decls.consts.insert(name, value);
+                }
+            }
+            Rule::int_decl => parse_int_decl(&mut decls, pair)?,
+            Rule::EOI => {}
+            _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
+        }
+    }
+
+    Ok(decls)
+}
+
+fn parse_int_decl(decls: &mut Declarations, pair: Pair<Rule>) -> ParseResult<()> {
+    let mut range = None;
+    for inner in pair.into_inner() {
+        match inner.as_rule() {
+            Rule::int_kw => {}
+            Rule::range => {
+                let span = inner.as_span();
+                let mut bounds = inner.into_inner();
+                let lower = parse_value(decls, bounds.next().unwrap())?;
+                let upper = parse_value(decls, bounds.next().unwrap())?;
+                if lower > upper {
+                    return Err(custom_error(
+                        format!("The range [{}, {}] is empty", lower, upper),
+                        span,
+                    ));
+                }
+                range = Some((lower, upper));
+            }
+            Rule::int_var => {
+                let span = inner.as_span();
+                let mut var = inner.into_inner();
+                let name = declare_name(decls, &var.next().unwrap())?;
+                let (lower, upper) = range.unwrap_or_else(|| decls.get_int_range(&name));
+
+                // Without an initial value the variable starts at 0, or the lower bound if 0 is outside its range
+                let value = match var.next() {
+                    Some(value) => parse_value(decls, value)?,
+                    None if lower > 0 => lower,
+                    None => 0,
+                };
+                if value < lower || value > upper {
+                    return Err(custom_error(
+                        format!(
+                            "The initial value {} of \"{}\" is outside its range [{}, {}]",
+                            value, name, lower, upper
+                        ),
+                        span,
+                    ));
+                }
+
+                if let Some(range) = range {
+                    decls.int_ranges.insert(name.clone(), range);
+                }
+                decls.ints.insert(name, value);
+            }
+            _ => unreachable!("Unexpected rule: {:?}", inner.as_rule()),
+        }
+    }
+    Ok(())
+}
+
+/// Parses a number or the name of a previously declared constant
+fn parse_value(decls: &Declarations, pair: Pair<Rule>) -> ParseResult<i32> {
+    match pair.as_rule() {
+        Rule::int => pair.as_str().parse().map_err(|_| {
+            custom_error(
+                format!("The number {} is too large", pair.as_str()),
+                pair.as_span(),
+            )
+        }),
+        Rule::variable => decls.consts.get(pair.as_str()).copied().ok_or_else(|| {
+            custom_error(
+                format!("\"{}\" is not a declared constant", pair.as_str()),
+                pair.as_span(),
+            )
+        }),
+        _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
+    }
+}
+
+/// Returns the name of the variable in `pair`, failing if the name is already declared
+fn declare_name(decls: &Declarations, pair: &Pair<Rule>) -> ParseResult<String> {
+    let name = pair.as_str();
+    if decls.clocks.contains_key(name)
+        || decls.ints.contains_key(name)
+        || decls.consts.contains_key(name)
+    {
+        return Err(custom_error(
+            format!("\"{}\" is already declared", name),
+            pair.as_span(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn custom_error(message: String, span: pest::Span) -> Box<Error<Rule>> {
+    Box::new(Error::new_from_span(
+        ErrorVariant::CustomError { message },
+        span,
+    ))
+}
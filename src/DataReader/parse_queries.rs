@@ -2,12 +2,18 @@ extern crate pest;
 
 use crate::ModelObjects::Expressions::{
     ComponentVariable, OperandExpression, QueryExpression, SaveExpression, StateExpression,
-    SystemExpression,
+    SystemExpression, TemporalOperator, TimedAction,
 };
 use crate::ModelObjects::Query;
+use crate::Simulation::automatic::AutomaticSimulationOptions;
+use crate::Simulation::concrete::parse_delay;
+use crate::Simulation::test_generation::TestGenerationOptions;
 
+use pest::error::ErrorVariant;
 use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
+use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Parser)]
 #[grammar = "DataReader/grammars/query_grammar.pest"]
@@ -17,7 +23,8 @@ lazy_static! {
     static ref PRATT: PrattParser<Rule> = PrattParser::new()
         .op(Op::infix(Rule::qoutient_op, Assoc::Left))
         .op(Op::infix(Rule::composition_op, Assoc::Left))
-        .op(Op::infix(Rule::conjunction_op, Assoc::Left));
+        .op(Op::infix(Rule::conjunction_op, Assoc::Left))
+        .op(Op::postfix(Rule::hiding_op) | Op::postfix(Rule::renaming_op));
 }
 
 ///This file handles parsing the queries based on the abstract syntax described in the .pest files in the grammar folder
@@ -46,6 +53,27 @@ pub fn parse_system(pair: pest::iterators::Pair<Rule>) -> SystemExpression {
                 _ => unreachable!(),
             }
         })
+        .map_postfix(|system, op| {
+            let system = Box::new(system);
+            match op.as_rule() {
+                Rule::hiding_op => SystemExpression::Hiding(
+                    system,
+                    op.into_inner().map(|it| it.as_str().to_string()).collect(),
+                ),
+                Rule::renaming_op => SystemExpression::Renaming(
+                    system,
+                    op.into_inner()
+                        .map(|renamed| {
+                            let mut names = renamed.into_inner();
+                            let new = names.next().unwrap().as_str().to_string();
+                            let old = names.next().unwrap().as_str().to_string();
+                            (new, old)
+                        })
+                        .collect(),
+                ),
+                _ => unreachable!(),
+            }
+        })
         .parse(pair.into_inner())
 }
 
@@ -140,7 +168,7 @@ fn comp_var_from_variable_pair(pair: pest::iterators::Pair<Rule>) -> ComponentVa
     }
 }
 
-fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
+fn parse_query(pair: pest::iterators::Pair<Rule>) -> Result<QueryExpression, String> {
     PRATT
         .map_primary(|pair| {
             let query = match pair.as_rule() {
@@ -150,6 +178,12 @@ fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
                     let system2 = parse_system(pairs.next().unwrap());
                     QueryExpression::Refinement(system1, system2)
                 }
+                Rule::strategy => {
+                    let mut pairs = pair.into_inner();
+                    let system1 = parse_system(pairs.next().unwrap());
+                    let system2 = parse_system(pairs.next().unwrap());
+                    QueryExpression::Strategy(system1, system2)
+                }
                 Rule::consistency => {
                     let mut pairs = pair.into_inner();
                     let system = parse_system(pairs.next().unwrap());
@@ -196,14 +230,143 @@ fn parse_query(pair: pest::iterators::Pair<Rule>) -> QueryExpression {
                     let name = pairs.next().map(|it| it.as_str().to_string());
                     QueryExpression::BisimMinim(SaveExpression { system, name })
                 }
+                Rule::concreteTrace => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let steps = pairs.map(parse_timed_action).collect();
+                    QueryExpression::ConcreteTrace { system, steps }
+                }
+                Rule::traceCheck => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let path = pairs.next().unwrap().into_inner().next().unwrap();
+                    QueryExpression::TraceCheck {
+                        system,
+                        trace_file: path.as_str().to_string(),
+                    }
+                }
+                Rule::simulate => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let mut options = AutomaticSimulationOptions::default();
+                    let mut target = None;
+                    for pair in pairs {
+                        match pair.as_rule() {
+                            Rule::simulationOptions => {
+                                parse_simulation_options(pair, &mut options)?
+                            }
+                            Rule::state => target = Some(parse_state(pair)),
+                            _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
+                        }
+                    }
+                    QueryExpression::Simulate {
+                        system,
+                        options,
+                        target,
+                    }
+                }
+                Rule::testGeneration => {
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let mut options = TestGenerationOptions::default();
+                    if let Some(pair) = pairs.next() {
+                        parse_test_options(pair, &mut options)?;
+                    }
+                    QueryExpression::TestGeneration { system, options }
+                }
+                Rule::possibly | Rule::invariantly | Rule::eventuallyAlways | Rule::potentially => {
+                    let operator = match pair.as_rule() {
+                        Rule::possibly => TemporalOperator::Possibly,
+                        Rule::invariantly => TemporalOperator::Invariantly,
+                        Rule::eventuallyAlways => TemporalOperator::EventuallyAlways,
+                        Rule::potentially => TemporalOperator::Potentially,
+                        _ => unreachable!(),
+                    };
+                    let mut pairs = pair.into_inner();
+                    let system = parse_system(pairs.next().unwrap());
+                    let state = parse_state(pairs.next().unwrap());
+                    QueryExpression::Temporal {
+                        operator,
+                        system,
+                        state,
+                    }
+                }
                 _ => unreachable!("Unexpected rule: {:?}", pair.as_rule()),
             };
-            query
+            Ok(query)
         })
         .parse(pair.into_inner())
 }
 
-fn parse_queries(pair: pest::iterators::Pair<Rule>) -> Vec<QueryExpression> {
+fn parse_timed_action(pair: pest::iterators::Pair<Rule>) -> TimedAction {
+    let mut pairs = pair.into_inner();
+    let delay = pairs.next().unwrap().as_str();
+    let action = pairs.next().unwrap().as_str().to_string();
+    TimedAction {
+        // The grammar only accepts delays which can be parsed
+        delay: parse_delay(delay).unwrap(),
+        action,
+    }
+}
+
+fn parse_simulation_options(
+    pair: pest::iterators::Pair<Rule>,
+    options: &mut AutomaticSimulationOptions,
+) -> Result<(), String> {
+    for option in pair.into_inner() {
+        let rule = option.as_rule();
+        let mut pairs = option.into_inner();
+        match rule {
+            Rule::runsOption => options.runs = parse_number(pairs.next().unwrap())?,
+            Rule::stepsOption => options.max_steps = parse_number(pairs.next().unwrap())?,
+            Rule::seedOption => options.seed = parse_number(pairs.next().unwrap())?,
+            Rule::weightOption => {
+                let action = pairs.next().unwrap().as_str().to_string();
+                let weight = parse_number(pairs.next().unwrap())?;
+                options.action_weights.insert(action, weight);
+            }
+            _ => unreachable!("Unexpected rule: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+/// Parses a number the grammar accepts but which may not fit in `T`, e.g. `runs=99999999999999999999`
+fn parse_number<T>(pair: pest::iterators::Pair<Rule>) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    pair.as_str().parse().map_err(|err| {
+        let error = pest::error::Error::<Rule>::new_from_span(
+            ErrorVariant::CustomError {
+                message: format!("Invalid number {}: {}", pair.as_str(), err),
+            },
+            pair.as_span(),
+        );
+        format!("Could not parse as rule with error: {}", error)
+    })
+}
+
+fn parse_test_options(
+    pair: pest::iterators::Pair<Rule>,
+    options: &mut TestGenerationOptions,
+) -> Result<(), String> {
+    for option in pair.into_inner() {
+        let rule = option.as_rule();
+        let mut pairs = option.into_inner();
+        match rule {
+            Rule::depthOption => options.depth = parse_number(pairs.next().unwrap())?,
+            Rule::testsOption => options.tests = parse_number(pairs.next().unwrap())?,
+            Rule::waitOption => options.wait = parse_number(pairs.next().unwrap())?,
+            Rule::coverageOption => options.coverage = true,
+            _ => unreachable!("Unexpected rule: {:?}", rule),
+        }
+    }
+    Ok(())
+}
+
+fn parse_queries(pair: pest::iterators::Pair<Rule>) -> Result<Vec<QueryExpression>, String> {
     match pair.as_rule() {
         Rule::queryList => {
             let inner = pair.into_inner();
@@ -226,6 +389,7 @@ pub fn parse_to_query(input: &str) -> Vec<Query> {
         .map(|q| Query {
             query: Option::from(q),
             comment: "".to_string(),
+            ..Default::default()
         })
         .collect()
 }
@@ -236,9 +400,7 @@ pub fn parse_to_expression_tree(input: &str) -> Result<Vec<QueryExpression>, Str
         Err(e) => return Err(format!("Could not parse as rule with error: {}", e)),
     };
 
-    let result = parse_queries(pairs.next().unwrap());
-
-    Ok(result)
+    parse_queries(pairs.next().unwrap())
 }
 
 pub fn parse_to_system_expr(input: &str) -> Result<SystemExpression, String> {
@@ -1,10 +1,10 @@
+use crate::DataReader::parse_declarations::parse_declarations;
 use crate::DataReader::parse_edge;
 use crate::ModelObjects::Expressions;
 use crate::ModelObjects::{Component, Declarations, Edge, Location, LocationType, SyncType};
 use crate::Simulation::graph_layout::layout_dummy_component;
 use edbm::util::constraints::ClockIndex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::HashMap;
 use std::ops::Add;
 
 #[derive(Serialize)]
@@ -181,50 +181,7 @@ where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    //Split string into vector of strings
-    let decls: Vec<String> = s.split('\n').map(|s| s.into()).collect();
-    let mut ints: HashMap<String, i32> = HashMap::new();
-    let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
-    let mut counter: ClockIndex = 1;
-    for string in decls {
-        //skip comments
-        if string.starts_with("//") || string.is_empty() {
-            continue;
-        }
-        let sub_decls: Vec<String> = string.split(';').map(|s| s.into()).collect();
-
-        for sub_decl in sub_decls {
-            if !sub_decl.is_empty() {
-                let split_string: Vec<String> = sub_decl.split(' ').map(|s| s.into()).collect();
-                let variable_type = split_string[0].as_str();
-
-                if variable_type == "clock" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            if !var.is_empty() {
-                                clocks.insert(var, counter);
-                                counter += 1;
-                            }
-                        }
-                    }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
-                        }
-                    }
-                } else {
-                    panic!("Not implemented read for type: \"{}\"", variable_type);
-                }
-            }
-        }
-    }
-
-    Ok(Declarations { ints, clocks })
+    parse_declarations(&s).map_err(serde::de::Error::custom)
 }
 
 /// Function used for deserializing guards
@@ -350,19 +307,33 @@ pub fn encode_declarations<S>(decls: &Declarations, serializer: S) -> Result<S::
 where
     S: Serializer,
 {
-    let mut output = String::from("clock ");
-    let mut it = decls.clocks.iter();
-    if let Some((first_clock, _)) = it.next() {
-        output = output.add(first_clock);
+    let mut output: Vec<String> = vec![];
 
-        for (clock, _) in it {
-            output = output.add(&format!(", {}", clock));
-        }
-        output = output.add(";");
+    let mut clocks: Vec<(&String, &ClockIndex)> = decls.clocks.iter().collect();
+    clocks.sort_by_key(|(_, index)| **index);
+    if !clocks.is_empty() {
+        let names: Vec<&str> = clocks.iter().map(|(name, _)| name.as_str()).collect();
+        output.push(format!("clock {};", names.join(", ")));
+    }
+
+    let mut consts: Vec<(&String, &i32)> = decls.consts.iter().collect();
+    consts.sort();
+    for (name, value) in consts {
+        output.push(format!("const int {} = {};", name, value));
+    }
 
-        return serializer.serialize_str(&output);
+    let mut ints: Vec<(&String, &i32)> = decls.ints.iter().collect();
+    ints.sort();
+    for (name, value) in ints {
+        match decls.int_ranges.get(name) {
+            Some((lower, upper)) => {
+                output.push(format!("int[{},{}] {} = {};", lower, upper, name, value))
+            }
+            None => output.push(format!("int {} = {};", name, value)),
+        }
     }
-    serializer.serialize_str("")
+
+    serializer.serialize_str(&output.join("\n"))
 }
 
 pub fn encode_opt_boolexpr<S>(
@@ -1,10 +1,10 @@
+use crate::DataReader::parse_declarations::parse_declarations;
 use crate::DataReader::parse_edge;
 use crate::DataReader::parse_edge::Update;
 use crate::ModelObjects::{
-    Component, Declarations, Edge, Location, LocationType, Query, SyncType, SystemDeclarations,
+    Component, Edge, Location, LocationType, Query, SyncType, SystemDeclarations,
     SystemSpecification,
 };
-use edbm::util::constraints::ClockIndex;
 use elementtree::{Element, FindChildren};
 use std::collections::HashMap;
 use std::fs::File;
@@ -16,10 +16,11 @@ pub fn is_xml_project<P: AsRef<Path>>(project_path: P) -> bool {
     project_path.as_ref().ends_with(".xml")
 }
 
+type ParsedXml = (Vec<Component>, SystemDeclarations, Vec<Query>);
+
 ///Used to parse systems described in xml
-pub(crate) fn parse_xml_from_file<P: AsRef<Path>>(
-    fileName: P,
-) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
+///Fails with the position of the error if a declaration or label cannot be parsed
+pub(crate) fn parse_xml_from_file<P: AsRef<Path>>(fileName: P) -> Result<ParsedXml, String> {
     //Open file and read xml
     let file = File::open(fileName).unwrap();
     let reader = BufReader::new(file);
@@ -27,38 +28,44 @@ pub(crate) fn parse_xml_from_file<P: AsRef<Path>>(
     parse_xml(reader)
 }
 
-pub(crate) fn parse_xml_from_str(xml: &str) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
+pub(crate) fn parse_xml_from_str(xml: &str) -> Result<ParsedXml, String> {
     let reader = BufReader::new(xml.as_bytes());
 
     parse_xml(reader)
 }
 
-fn parse_xml<R: Read>(xml_data: R) -> (Vec<Component>, SystemDeclarations, Vec<Query>) {
+fn parse_xml<R: Read>(xml_data: R) -> Result<ParsedXml, String> {
     let root = Element::from_reader(xml_data).unwrap();
 
     //storage of components
     let mut xml_components: Vec<Component> = vec![];
 
     for xml_comp in root.find_all("template") {
+        let name: String = xml_comp.find("name").unwrap().text().parse().unwrap();
         let declarations = match xml_comp.find("declaration") {
             Some(e) => parse_declarations(e.text()),
             None => parse_declarations(""),
-        };
-        let edges = collect_edges(xml_comp.find_all("transition"));
-        let comp = Component {
-            name: xml_comp.find("name").unwrap().text().parse().unwrap(),
+        }
+        .map_err(|err| format!("Could not read component {}: {}", name, err))?;
+        let edges = collect_edges(xml_comp.find_all("transition"))
+            .map_err(|err| format!("Could not read component {}: {}", name, err))?;
+        let locations = collect_locations(
+            xml_comp.find_all("location"),
+            xml_comp
+                .find("init")
+                .expect("No initial location")
+                .get_attr("ref")
+                .unwrap(),
+        )
+        .map_err(|err| format!("Could not read component {}: {}", name, err))?;
+        let mut comp = Component {
+            name,
             declarations,
-            locations: collect_locations(
-                xml_comp.find_all("location"),
-                xml_comp
-                    .find("init")
-                    .expect("No initial location")
-                    .get_attr("ref")
-                    .unwrap(),
-            ),
+            locations,
             edges,
             special_id: None,
         };
+        comp.substitute_constants()?;
         xml_components.push(comp);
     }
 
@@ -67,19 +74,21 @@ fn parse_xml<R: Read>(xml_data: R) -> (Vec<Component>, SystemDeclarations, Vec<Q
         declarations: decode_sync_type(root.find("system").unwrap().text()),
     };
 
-    (xml_components, system_declarations, vec![])
+    Ok((xml_components, system_declarations, vec![]))
 }
 
-fn collect_locations(xml_locations: FindChildren, initial_id: &str) -> Vec<Location> {
+fn collect_locations(
+    xml_locations: FindChildren,
+    initial_id: &str,
+) -> Result<Vec<Location>, String> {
     let mut locations: Vec<Location> = vec![];
     for loc in xml_locations {
         let location = Location {
             id: loc.get_attr("id").unwrap().parse().unwrap(),
             invariant: match loc.find("label") {
-                Some(x) => match parse_edge::parse_guard(x.text()) {
-                    Ok(edgeAttribute) => Some(edgeAttribute),
-                    Err(e) => panic!("Could not parse invariant {} got error: {:?}", x.text(), e),
-                },
+                Some(x) => Some(parse_edge::parse_guard(x.text()).map_err(|e| {
+                    format!("Could not parse invariant {} got error: {}", x.text(), e)
+                })?),
                 _ => None,
             },
             location_type: match loc.get_attr("id").unwrap().eq(initial_id) {
@@ -91,10 +100,10 @@ fn collect_locations(xml_locations: FindChildren, initial_id: &str) -> Vec<Locat
         locations.push(location);
     }
 
-    locations
+    Ok(locations)
 }
 
-fn collect_edges(xml_edges: FindChildren) -> Vec<Edge> {
+fn collect_edges(xml_edges: FindChildren) -> Result<Vec<Edge>, String> {
     let mut edges: Vec<Edge> = vec![];
     for e in xml_edges {
         let mut guard: Option<crate::ModelObjects::Expressions::BoolExpression> = None;
@@ -102,19 +111,19 @@ fn collect_edges(xml_edges: FindChildren) -> Vec<Edge> {
         let mut sync: String = "".to_string();
         for label in e.find_all("label") {
             match label.get_attr("kind").unwrap() {
-                "guard" => match parse_edge::parse_guard(label.text()) {
-                    Ok(guard_res) => {
-                        guard = Some(guard_res);
-                    }
-                    Err(e) => panic!("Could not parse {} got error: {:?}", label.text(), e),
-                },
+                "guard" => {
+                    guard = Some(parse_edge::parse_guard(label.text()).map_err(|e| {
+                        format!("Could not parse {} got error: {}", label.text(), e)
+                    })?);
+                }
                 "synchronisation" => {
                     sync = label.text().to_string();
                 }
-                "assignment" => match parse_edge::parse_updates(label.text()) {
-                    Ok(updates_res) => updates = Some(updates_res),
-                    Err(e) => panic!("Could not parse {} got error: {:?}", label.text(), e),
-                },
+                "assignment" => {
+                    updates = Some(parse_edge::parse_updates(label.text()).map_err(|e| {
+                        format!("Could not parse {} got error: {}", label.text(), e)
+                    })?);
+                }
                 _ => {}
             }
         }
@@ -143,56 +152,7 @@ fn collect_edges(xml_edges: FindChildren) -> Vec<Edge> {
         edges.push(edge);
     }
 
-    edges
-}
-
-fn parse_declarations(variables: &str) -> Declarations {
-    //Split string into vector of strings
-    let decls: Vec<String> = variables.split('\n').map(|s| s.into()).collect();
-    let mut ints: HashMap<String, i32> = HashMap::new();
-    let mut clocks: HashMap<String, ClockIndex> = HashMap::new();
-    let mut counter: ClockIndex = 1;
-    for string in decls {
-        //skip comments
-        if string.starts_with("//") || string.is_empty() {
-            continue;
-        }
-        let sub_decls: Vec<String> = string.split(';').map(|s| s.into()).collect();
-
-        for mut sub_decl in sub_decls {
-            sub_decl = sub_decl.replace('\r', "");
-
-            if !sub_decl.is_empty() {
-                let split_string: Vec<String> = sub_decl.split(' ').map(|s| s.into()).collect();
-                let variable_type = split_string[0].as_str();
-
-                if variable_type == "clock" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            if !var.is_empty() {
-                                clocks.insert(var, counter);
-                                counter += 1;
-                            }
-                        }
-                    }
-                } else if variable_type == "int" {
-                    for split_str in split_string.iter().skip(1) {
-                        let comma_split: Vec<String> =
-                            split_str.split(',').map(|s| s.into()).collect();
-                        for var in comma_split {
-                            ints.insert(var, 0);
-                        }
-                    }
-                } else {
-                    panic!("not implemented read for type: {}", variable_type);
-                }
-            }
-        }
-    }
-
-    Declarations { ints, clocks }
+    Ok(edges)
 }
 
 fn decode_sync_type(global_decl: &str) -> SystemSpecification {
@@ -2,7 +2,11 @@ use std::collections::{HashMap, VecDeque};
 
 use edbm::zones::OwnedFederation;
 
-use crate::{ModelObjects::StatePair, TransitionSystems::LocationID};
+use crate::{
+    ModelObjects::StatePair,
+    System::{cancellation::Interrupted, limits},
+    TransitionSystems::LocationID,
+};
 
 pub type PassedStateList = PassedStateListFed;
 type PassedStateListFed = HashMap<(LocationID, LocationID), OwnedFederation>;
@@ -15,21 +19,25 @@ pub struct DepthFirstWaitingStateList {
 }
 
 pub trait PassedStateListExt {
-    fn put(&mut self, pair: StatePair);
+    /// Stores `pair`, returning an error if the query exceeds its [limits]
+    fn put(&mut self, pair: StatePair) -> Result<(), Interrupted>;
     fn has(&self, pair: &StatePair) -> bool;
     fn zones(&self, key: &(LocationID, LocationID)) -> Vec<&OwnedFederation>;
 }
 
 impl PassedStateListExt for PassedStateListVec {
-    fn put(&mut self, mut pair: StatePair) {
+    fn put(&mut self, mut pair: StatePair) -> Result<(), Interrupted> {
         let fed = pair.take_zone();
+        limits::record_state(&fed)?;
         let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
         let key = (loc1, loc2);
         if let Some(vec) = self.get_mut(&key) {
             vec.push(fed);
+            limits::check_zones_per_location(vec.len())?;
         } else {
             self.insert(key, vec![fed]);
         };
+        Ok(())
     }
 
     fn has(&self, pair: &StatePair) -> bool {
@@ -54,16 +62,19 @@ impl PassedStateListExt for PassedStateListVec {
 }
 
 impl PassedStateListExt for DepthFirstWaitingStateList {
-    fn put(&mut self, mut pair: StatePair) {
+    fn put(&mut self, mut pair: StatePair) -> Result<(), Interrupted> {
         self.queue.push_front(pair.clone());
         let fed = pair.take_zone();
+        limits::record_state(&fed)?;
         let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
         let key = (loc1, loc2);
         if let Some(vec) = self.map.get_mut(&key) {
             vec.push_front(fed);
+            limits::check_zones_per_location(vec.len())?;
         } else {
             self.map.insert(key, vec![fed].into());
         };
+        Ok(())
     }
     fn has(&self, pair: &StatePair) -> bool {
         let (loc1, loc2, fed) = (
@@ -101,6 +112,7 @@ impl DepthFirstWaitingStateList {
 
     pub fn pop(&mut self) -> Option<StatePair> {
         let pair = self.queue.pop_front()?;
+        limits::release_state(pair.ref_zone());
         let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
 
         if let Some(vec) = self.map.get_mut(&key) {
@@ -115,8 +127,10 @@ impl DepthFirstWaitingStateList {
     }
 }
 impl PassedStateListExt for PassedStateListFed {
-    fn put(&mut self, mut pair: StatePair) {
+    fn put(&mut self, mut pair: StatePair) -> Result<(), Interrupted> {
         let mut fed = pair.take_zone();
+        // The zones of a location are merged into one federation, so only the number of states is limited
+        limits::record_state(&fed)?;
         let (loc1, loc2) = (pair.locations1.id, pair.locations2.id);
         let key = (loc1, loc2);
 
@@ -124,6 +138,7 @@ impl PassedStateListExt for PassedStateListFed {
             fed = fed.union(f).expensive_reduce();
         }
         self.insert(key, fed);
+        Ok(())
     }
 
     fn has(&self, pair: &StatePair) -> bool {
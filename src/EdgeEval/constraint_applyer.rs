@@ -9,7 +9,57 @@ pub fn apply_constraints_to_state(
     decls: &Declarations,
     fed: OwnedFederation,
 ) -> Result<OwnedFederation, String> {
-    apply_constraints_to_state_helper(guard, decls, fed)
+    if decls.ints.is_empty() {
+        return apply_constraints_to_state_helper(guard, decls, fed);
+    }
+
+    // Constraints only on integer variables are evaluated to booleans before applying the clock constraints
+    let mut guard = replace_int_vars(guard, decls)?;
+    guard.simplify();
+    apply_constraints_to_state_helper(&guard, decls, fed)
+}
+
+fn replace_int_vars(
+    guard: &BoolExpression,
+    decls: &Declarations,
+) -> Result<BoolExpression, String> {
+    let replace = |left: &ArithExpression, right: &ArithExpression| {
+        Ok::<_, String>((
+            Box::new(replace_vars(left, decls)?),
+            Box::new(replace_vars(right, decls)?),
+        ))
+    };
+    Ok(match guard {
+        BoolExpression::AndOp(left, right) => BoolExpression::AndOp(
+            Box::new(replace_int_vars(left, decls)?),
+            Box::new(replace_int_vars(right, decls)?),
+        ),
+        BoolExpression::OrOp(left, right) => BoolExpression::OrOp(
+            Box::new(replace_int_vars(left, decls)?),
+            Box::new(replace_int_vars(right, decls)?),
+        ),
+        BoolExpression::LessEQ(left, right) => {
+            let (left, right) = replace(left, right)?;
+            BoolExpression::LessEQ(left, right)
+        }
+        BoolExpression::GreatEQ(left, right) => {
+            let (left, right) = replace(left, right)?;
+            BoolExpression::GreatEQ(left, right)
+        }
+        BoolExpression::LessT(left, right) => {
+            let (left, right) = replace(left, right)?;
+            BoolExpression::LessT(left, right)
+        }
+        BoolExpression::GreatT(left, right) => {
+            let (left, right) = replace(left, right)?;
+            BoolExpression::GreatT(left, right)
+        }
+        BoolExpression::EQ(left, right) => {
+            let (left, right) = replace(left, right)?;
+            BoolExpression::EQ(left, right)
+        }
+        BoolExpression::Bool(val) => BoolExpression::Bool(*val),
+    })
 }
 
 fn apply_constraints_to_state_helper(
@@ -256,6 +306,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Int(3);
@@ -270,6 +321,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Clock(1);
@@ -284,6 +336,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         // i-j <= 0 -> i can at most be the value of j
@@ -299,6 +352,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 < 3
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Clock(2));
@@ -322,6 +376,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::Int(3);
@@ -336,6 +391,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Addition(
             Box::new(ArithExpression::Clock(1)),
@@ -352,6 +408,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::ADif(ArithExpression::Clock(2), ArithExpression::Int(3));
@@ -371,6 +428,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
 
         let left = ArithExpression::Addition(
@@ -419,6 +477,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         // i-j < c -> c1-c2 > 3 -> c2-c1 < -3
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Int(2));
@@ -437,6 +496,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Clock(1);
         let right = ArithExpression::Addition(
@@ -458,6 +518,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Int(3);
         let right = ArithExpression::Addition(
@@ -474,6 +535,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Multiplication(
             Box::new(ArithExpression::Clock(2)),
@@ -537,6 +599,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(ArithExpression::Clock(1), ArithExpression::Clock(2));
         let right = Box::new(ArithExpression::Clock(3));
@@ -550,6 +613,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::Int(1);
         let right = ArithExpression::Int(2);
@@ -582,6 +646,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(
             // = 4
@@ -619,6 +684,7 @@ mod test {
         let decl = Declarations {
             clocks: HashMap::new(),
             ints: HashMap::new(),
+            ..Declarations::empty()
         };
         let left = ArithExpression::ADif(
             ArithExpression::Multiplication(
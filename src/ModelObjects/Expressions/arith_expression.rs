@@ -429,6 +429,26 @@ impl ArithExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName(name)` where `name` is in `values` with its value
+    pub fn substitute_constants(&mut self, values: &HashMap<String, i32>) {
+        match self {
+            ArithExpression::Difference(a1, a2)
+            | ArithExpression::Addition(a1, a2)
+            | ArithExpression::Multiplication(a1, a2)
+            | ArithExpression::Division(a1, a2)
+            | ArithExpression::Modulo(a1, a2) => {
+                a1.substitute_constants(values);
+                a2.substitute_constants(values);
+            }
+            ArithExpression::Clock(_) | ArithExpression::Int(_) => (),
+            ArithExpression::VarName(name) => {
+                if let Some(value) = values.get(name) {
+                    *self = ArithExpression::Int(*value);
+                }
+            }
+        }
+    }
+
     pub fn clock_var_count(&self) -> u32 {
         match self {
             ArithExpression::Clock(_) => 1,
@@ -434,6 +434,25 @@ impl BoolExpression {
         }
     }
 
+    /// Replaces all occurrences of `ArithExpression::VarName(name)` where `name` is in `values` with its value
+    pub fn substitute_constants(&mut self, values: &HashMap<String, i32>) {
+        match self {
+            BoolExpression::AndOp(e1, e2) | BoolExpression::OrOp(e1, e2) => {
+                e1.substitute_constants(values);
+                e2.substitute_constants(values);
+            }
+            BoolExpression::LessEQ(e1, e2)
+            | BoolExpression::GreatEQ(e1, e2)
+            | BoolExpression::LessT(e1, e2)
+            | BoolExpression::GreatT(e1, e2)
+            | BoolExpression::EQ(e1, e2) => {
+                e1.substitute_constants(values);
+                e2.substitute_constants(values);
+            }
+            BoolExpression::Bool(_) => (),
+        }
+    }
+
     pub fn BLessEQ(left: ArithExpression, right: ArithExpression) -> BoolExpression {
         BoolExpression::LessEQ(Box::new(left), Box::new(right))
     }
@@ -1,10 +1,15 @@
 use std::fmt::{Display, Formatter};
 
 use super::StateExpression;
+use crate::Simulation::automatic::AutomaticSimulationOptions;
+use crate::Simulation::concrete::Rational;
+use crate::Simulation::test_generation::TestGenerationOptions;
 
 #[derive(Debug, Clone)]
 pub enum QueryExpression {
     Refinement(SystemExpression, SystemExpression),
+    /// Fx. `"strategy: A <= B"` -> `Strategy(A, B)`, the winning strategy of the environment if A does not refine B
+    Strategy(SystemExpression, SystemExpression),
     Consistency(SystemExpression),
     Reachability {
         system: SystemExpression,
@@ -17,6 +22,76 @@ pub enum QueryExpression {
     GetComponent(SaveExpression),
     Prune(SaveExpression),
     BisimMinim(SaveExpression),
+    /// Fx. `"E<> A || B: A.L1"` -> `Temporal { operator: Possibly, system: (A || B), state: A.L1 }`
+    Temporal {
+        operator: TemporalOperator,
+        system: SystemExpression,
+        state: StateExpression,
+    },
+    /// Fx. `"leadsto: A @ A.Req --> A.Ack"` -> `LeadsTo { system: A, premise: A.Req, response: A.Ack }`
+    LeadsTo {
+        system: SystemExpression,
+        premise: StateExpression,
+        response: StateExpression,
+    },
+    /// Fx. `"concrete-trace: A: 2.5 coin, 1 tea"` -> `ConcreteTrace { system: A, steps: [2.5 coin, 1 tea] }`
+    ConcreteTrace {
+        system: SystemExpression,
+        steps: Vec<TimedAction>,
+    },
+    /// Fx. `"trace-check: A: \"run.csv\""` -> `TraceCheck { system: A, trace_file: run.csv }`
+    TraceCheck {
+        system: SystemExpression,
+        trace_file: String,
+    },
+    /// Fx. `"simulate: A {runs=10, steps=50} -> A.L2"` -> `Simulate { system: A, options: {runs=10, steps=50, ..}, target: Some(A.L2) }`
+    Simulate {
+        system: SystemExpression,
+        options: AutomaticSimulationOptions,
+        target: Option<StateExpression>,
+    },
+    /// Fx. `"test-gen: A {depth=8, coverage}"` -> `TestGeneration { system: A, options: {depth=8, coverage, ..} }`
+    TestGeneration {
+        system: SystemExpression,
+        options: TestGenerationOptions,
+    },
+}
+
+/// A step of a concrete trace: Delaying `delay` time units and then taking a transition with `action`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimedAction {
+    pub delay: Rational,
+    pub action: String,
+}
+
+impl Display for TimedAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.delay, self.action)
+    }
+}
+
+/// The temporal operators of TCTL, each combining a path quantifier with a temporal modality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemporalOperator {
+    /// `E<>`: Some path eventually reaches a state satisfying the predicate
+    Possibly,
+    /// `A[]`: Every reachable state satisfies the predicate
+    Invariantly,
+    /// `E[]`: Some maximal path only visits states satisfying the predicate
+    EventuallyAlways,
+    /// `A<>`: Every maximal path eventually reaches a state satisfying the predicate
+    Potentially,
+}
+
+impl Display for TemporalOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemporalOperator::Possibly => write!(f, "E<>"),
+            TemporalOperator::Invariantly => write!(f, "A[]"),
+            TemporalOperator::EventuallyAlways => write!(f, "E[]"),
+            TemporalOperator::Potentially => write!(f, "A<>"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +108,10 @@ pub enum SystemExpression {
     Quotient(Box<SystemExpression>, Box<SystemExpression>),
     Composition(Box<SystemExpression>, Box<SystemExpression>),
     Conjunction(Box<SystemExpression>, Box<SystemExpression>),
+    /// Fx. `"A \ {a, b}"` -> `Hiding(A, vec!["a", "b"])`
+    Hiding(Box<SystemExpression>, Vec<String>),
+    /// Fx. `"A[b/a, d/c]"` -> `Renaming(A, vec![("b", "a"), ("d", "c")])`, renaming `a` to `b` and `c` to `d`
+    Renaming(Box<SystemExpression>, Vec<(String, String)>),
 }
 
 impl Display for SystemExpression {
@@ -53,6 +132,16 @@ impl Display for SystemExpression {
             SystemExpression::Conjunction(left, right) => {
                 write!(f, "({} && {})", left, right)?;
             }
+            SystemExpression::Hiding(system, actions) => {
+                write!(f, "{} \\ {{{}}}", system, actions.join(", "))?;
+            }
+            SystemExpression::Renaming(system, renamings) => {
+                let renamings = renamings
+                    .iter()
+                    .map(|(new, old)| format!("{}/{}", new, old))
+                    .collect::<Vec<_>>();
+                write!(f, "{}[{}]", system, renamings.join(", "))?;
+            }
         }
         Ok(())
     }
@@ -73,6 +162,9 @@ impl Display for QueryExpression {
             QueryExpression::Refinement(left, right) => {
                 write!(f, "refinement: {} <= {}", left, right)
             }
+            QueryExpression::Strategy(left, right) => {
+                write!(f, "strategy: {} <= {}", left, right)
+            }
             QueryExpression::Reachability { system, from, to } => {
                 write!(
                     f,
@@ -106,6 +198,45 @@ impl Display for QueryExpression {
             QueryExpression::Specification(system) => {
                 write!(f, "specification: {}", system)
             }
+            QueryExpression::Temporal {
+                operator,
+                system,
+                state,
+            } => {
+                write!(f, "{} {}: {}", operator, system, state)
+            }
+            QueryExpression::LeadsTo {
+                system,
+                premise,
+                response,
+            } => {
+                write!(f, "leadsto: {} @ {} --> {}", system, premise, response)
+            }
+            QueryExpression::ConcreteTrace { system, steps } => {
+                write!(f, "concrete-trace: {}", system)?;
+                if !steps.is_empty() {
+                    let steps: Vec<String> = steps.iter().map(|step| step.to_string()).collect();
+                    write!(f, ": {}", steps.join(", "))?;
+                }
+                Ok(())
+            }
+            QueryExpression::TraceCheck { system, trace_file } => {
+                write!(f, "trace-check: {}: \"{}\"", system, trace_file)
+            }
+            QueryExpression::Simulate {
+                system,
+                options,
+                target,
+            } => {
+                write!(f, "simulate: {} {}", system, options)?;
+                if let Some(target) = target {
+                    write!(f, " -> {}", target)?;
+                }
+                Ok(())
+            }
+            QueryExpression::TestGeneration { system, options } => {
+                write!(f, "test-gen: {} {}", system, options)
+            }
         }
     }
 }
@@ -4,7 +4,7 @@ use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
 
 use crate::ModelObjects::Expressions::BoolExpression;
-use crate::ModelObjects::{Edge, Location, SyncType};
+use crate::ModelObjects::{Edge, Location, SyncType, INT_RANGE};
 use itertools::Itertools;
 use log::info;
 use serde::{Deserialize, Serialize};
@@ -99,6 +99,38 @@ impl Component {
         max_bounds
     }
 
+    /// Replaces the constants declared in the component with their values in all guards, invariants and updates.
+    /// Fails if an update cannot be simplified after the substitution.
+    pub fn substitute_constants(&mut self) -> Result<(), String> {
+        let consts = &self.declarations.consts;
+        if consts.is_empty() {
+            return Ok(());
+        }
+
+        for location in &mut self.locations {
+            if let Some(invariant) = &mut location.invariant {
+                invariant.substitute_constants(consts);
+                invariant.simplify();
+            }
+        }
+        for edge in &mut self.edges {
+            if let Some(guard) = &mut edge.guard {
+                guard.substitute_constants(consts);
+                guard.simplify();
+            }
+            for update in edge.update.iter_mut().flatten() {
+                update.expression.substitute_constants(consts);
+                update.expression = update.expression.simplify().map_err(|err| {
+                    format!(
+                        "Could not simplify the update of {} on edge {} in {}: {}",
+                        update.variable, edge.id, self.name, err
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     /// Redoes the components Edge IDs by giving them new unique IDs based on their index.
     pub fn remake_edge_ids(&mut self) {
         // Give all edges a name
@@ -181,11 +213,17 @@ pub trait DeclarationProvider {
     fn get_declarations(&self) -> &Declarations;
 }
 
-/// The declaration struct is used to hold the indices for each clock, and is meant to be the owner of int variables once implemented
+/// The declaration struct is used to hold the indices for each clock, and the initial values of the int variables
 #[derive(Debug, Deserialize, Clone, PartialEq, Eq, Serialize)]
 pub struct Declarations {
     pub ints: HashMap<String, i32>,
     pub clocks: HashMap<String, ClockIndex>,
+    /// The constants, which are substituted into the guards, invariants and updates when the component is read
+    #[serde(default)]
+    pub consts: HashMap<String, i32>,
+    /// The ranges of the bounded int variables, e.g. `[0, 5]` for `int[0,5] n;`
+    #[serde(default)]
+    pub int_ranges: HashMap<String, (i32, i32)>,
 }
 
 impl Declarations {
@@ -193,9 +231,16 @@ impl Declarations {
         Declarations {
             ints: HashMap::new(),
             clocks: HashMap::new(),
+            consts: HashMap::new(),
+            int_ranges: HashMap::new(),
         }
     }
 
+    /// Gets the range of the int variable `name`, which is [INT_RANGE] unless a range is declared.
+    pub fn get_int_range(&self, name: &str) -> (i32, i32) {
+        self.int_ranges.get(name).copied().unwrap_or(INT_RANGE)
+    }
+
     pub fn get_clock_count(&self) -> usize {
         self.clocks.len()
     }
@@ -6,9 +6,11 @@ mod queries;
 mod state;
 mod statepair;
 mod system_declarations;
+mod system_diagram;
 mod transition;
+mod valuation;
 
 pub use self::{
     component::*, edge::*, location::*, queries::*, state::*, statepair::*, system_declarations::*,
-    transition::*,
+    system_diagram::*, transition::*, valuation::*,
 };
@@ -1,19 +1,47 @@
 use crate::DataReader::parse_queries;
 use crate::ModelObjects::Expressions;
 use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
 
 /// The struct containing a single query
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct Query {
     #[serde(deserialize_with = "decode_query")]
     pub query: Option<Expressions::QueryExpression>,
     pub comment: String,
+    /// The inputs to ignore in a refinement, mapped to whether they are ignored
+    #[serde(default, rename = "ignoredInputs")]
+    pub ignored_inputs: HashMap<String, bool>,
+    /// The outputs to ignore in a refinement, mapped to whether they are ignored
+    #[serde(default, rename = "ignoredOutputs")]
+    pub ignored_outputs: HashMap<String, bool>,
+    /// The contents of the trace file of a trace check, which is read from the file when they are not given
+    #[serde(skip)]
+    pub trace: Option<String>,
 }
 
 impl Query {
     pub fn get_query(&self) -> &Option<Expressions::QueryExpression> {
         &self.query
     }
+
+    /// Returns the inputs which are ignored in a refinement
+    pub fn get_ignored_inputs(&self) -> HashSet<String> {
+        enabled_actions(&self.ignored_inputs)
+    }
+
+    /// Returns the outputs which are ignored in a refinement
+    pub fn get_ignored_outputs(&self) -> HashSet<String> {
+        enabled_actions(&self.ignored_outputs)
+    }
+}
+
+fn enabled_actions(actions: &HashMap<String, bool>) -> HashSet<String> {
+    actions
+        .iter()
+        .filter(|(_, ignored)| **ignored)
+        .map(|(action, _)| action.clone())
+        .collect()
 }
 
 /// Function used for deserializing queries
@@ -1,7 +1,8 @@
 use edbm::zones::OwnedFederation;
 
-use crate::TransitionSystems::{LocationTree, TransitionSystemPtr};
+use crate::TransitionSystems::{LocationID, LocationTree, TransitionID, TransitionSystem};
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct StatePair {
@@ -9,6 +10,23 @@ pub struct StatePair {
     pub locations2: LocationTree,
     /// The sentinel (Option) allows us to take ownership of the internal fed from a mutable reference
     zone_sentinel: Option<OwnedFederation>,
+    /// How this state pair was reached, which is `None` for the initial state pair
+    pub step: Option<Arc<StatePairStep>>,
+}
+
+/// The step of a refinement check following the `previous` steps, taking the transition with id `transition1` to `target1`
+/// in the left system and the transition with id `transition2` to `target2` in the right system.
+///
+/// A step does not keep the state pair it was taken from, so the zones of explored state pairs are freed as in a search without steps.
+/// The state pairs along the steps are rebuilt when they are needed, see [replay_steps](crate::System::refine::replay_steps).
+#[derive(Clone, Debug)]
+pub struct StatePairStep {
+    pub previous: Option<Arc<StatePairStep>>,
+    pub action: String,
+    pub transition1: TransitionID,
+    pub target1: LocationID,
+    pub transition2: TransitionID,
+    pub target2: LocationID,
 }
 
 impl StatePair {
@@ -23,7 +41,20 @@ impl StatePair {
             locations1,
             locations2,
             zone_sentinel: Some(zone),
+            step: None,
+        }
+    }
+
+    /// The steps from the initial state pair to this state pair
+    pub fn steps(&self) -> Vec<&StatePairStep> {
+        let mut steps = vec![];
+        let mut current = self.step.as_deref();
+        while let Some(step) = current {
+            steps.push(step);
+            current = step.previous.as_deref();
         }
+        steps.reverse();
+        steps
     }
 
     pub fn get_locations1(&self) -> &LocationTree {
@@ -69,8 +100,8 @@ impl StatePair {
 
     pub fn extrapolate_max_bounds(
         &mut self,
-        sys1: &TransitionSystemPtr,
-        sys2: &TransitionSystemPtr,
+        sys1: &dyn TransitionSystem,
+        sys2: &dyn TransitionSystem,
     ) {
         let mut bounds = sys1.get_local_max_bounds(&self.locations1);
         bounds.add_bounds(&sys2.get_local_max_bounds(&self.locations2));
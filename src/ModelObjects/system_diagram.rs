@@ -0,0 +1,253 @@
+use crate::ModelObjects::Expressions::SystemExpression;
+use log::warn;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The id of the root of a [SystemDiagram], which is connected to the node making up the system.
+pub const SYSTEM_ROOT_ID: u32 = 0;
+
+/// A system of an ECDAR project, as read from `Systems/*.json`,
+/// describing component instances combined by operators into a named system.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemDiagram {
+    pub name: String,
+    pub component_instances: Vec<ComponentInstance>,
+    pub operators: Vec<SystemOperator>,
+    pub edges: Vec<SystemEdge>,
+}
+
+/// An instance of a component, or of another system, in a [SystemDiagram].
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentInstance {
+    pub id: u32,
+    pub component_name: String,
+    #[serde(default)]
+    pub x: f64,
+}
+
+/// An operator combining its children in a [SystemDiagram].
+#[derive(Debug, Deserialize, Clone)]
+pub struct SystemOperator {
+    pub id: u32,
+    #[serde(rename = "type")]
+    pub operator_type: OperatorType,
+    #[serde(default)]
+    pub x: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatorType {
+    Composition,
+    Conjunction,
+    Quotient,
+}
+
+/// Connects the node with id `child` to the node with id `parent` in a [SystemDiagram].
+#[derive(Debug, Deserialize, Clone)]
+pub struct SystemEdge {
+    pub child: u32,
+    pub parent: u32,
+}
+
+impl SystemDiagram {
+    /// Converts the system into a [SystemExpression], where instances of other systems are left as components.
+    /// The children of an operator are combined from left to right as they are drawn.
+    pub fn to_expression(&self) -> Result<SystemExpression, String> {
+        match self.children(SYSTEM_ROOT_ID).as_slice() {
+            [root] => self.node_to_expression(*root, &mut vec![]),
+            [] => Err(format!(
+                "System {} has nothing connected to its root",
+                self.name
+            )),
+            _ => Err(format!(
+                "System {} has more than one node connected to its root",
+                self.name
+            )),
+        }
+    }
+
+    fn node_to_expression(
+        &self,
+        id: u32,
+        visiting: &mut Vec<u32>,
+    ) -> Result<SystemExpression, String> {
+        if let Some(instance) = self.component_instances.iter().find(|i| i.id == id) {
+            return Ok(SystemExpression::Component(
+                instance.component_name.clone(),
+                self.special_id(instance),
+            ));
+        }
+
+        let operator = match self.operators.iter().find(|o| o.id == id) {
+            Some(operator) => operator,
+            None => {
+                return Err(format!(
+                    "System {} has no component instance or operator with id {}",
+                    self.name, id
+                ))
+            }
+        };
+        if visiting.contains(&id) {
+            return Err(format!(
+                "System {} has a cycle through the operator with id {}",
+                self.name, id
+            ));
+        }
+
+        let children = self.children(id);
+        match (operator.operator_type, children.len()) {
+            (_, 0 | 1) => {
+                return Err(format!(
+                    "The {:?} operator with id {} in system {} must have at least two children",
+                    operator.operator_type, id, self.name
+                ))
+            }
+            (OperatorType::Quotient, count) if count > 2 => {
+                return Err(format!(
+                    "The quotient operator with id {} in system {} must have exactly two children",
+                    id, self.name
+                ))
+            }
+            _ => {}
+        }
+
+        visiting.push(id);
+        let mut operands = children
+            .into_iter()
+            .map(|child| self.node_to_expression(child, visiting))
+            .collect::<Result<Vec<_>, _>>()?;
+        visiting.pop();
+
+        let first = operands.remove(0);
+        Ok(operands.into_iter().fold(first, |left, right| {
+            let (left, right) = (Box::new(left), Box::new(right));
+            match operator.operator_type {
+                OperatorType::Composition => SystemExpression::Composition(left, right),
+                OperatorType::Conjunction => SystemExpression::Conjunction(left, right),
+                OperatorType::Quotient => SystemExpression::Quotient(left, right),
+            }
+        }))
+    }
+
+    /// The ids of the children of the node with `id`, ordered from left to right
+    fn children(&self, id: u32) -> Vec<u32> {
+        let mut children: Vec<(f64, u32)> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.parent == id)
+            .map(|edge| (self.x_of(edge.child), edge.child))
+            .collect();
+        children.sort_by(|(x1, id1), (x2, id2)| x1.total_cmp(x2).then(id1.cmp(id2)));
+        children.into_iter().map(|(_, child)| child).collect()
+    }
+
+    fn x_of(&self, id: u32) -> f64 {
+        self.component_instances
+            .iter()
+            .find(|i| i.id == id)
+            .map(|i| i.x)
+            .or_else(|| self.operators.iter().find(|o| o.id == id).map(|o| o.x))
+            .unwrap_or_default()
+    }
+
+    /// Instances of a component occurring more than once are told apart by their id
+    fn special_id(&self, instance: &ComponentInstance) -> Option<String> {
+        let occurrences = self
+            .component_instances
+            .iter()
+            .filter(|i| i.component_name == instance.component_name)
+            .count();
+        (occurrences > 1).then(|| instance.id.to_string())
+    }
+}
+
+/// Converts the `systems` into [SystemExpression]s by their names, where instances of other systems are replaced by their expressions.
+/// Every instance must be of one of the `systems` or of a component for which `is_component` holds.
+/// Components take precedence over systems with the same name, which are left out.
+///
+/// Each system is resolved on its own, so an invalid system does not prevent the others from being used.
+pub fn resolve_systems(
+    systems: &[SystemDiagram],
+    is_component: impl Fn(&str) -> bool,
+) -> HashMap<String, Result<SystemExpression, String>> {
+    let diagrams: HashMap<&str, &SystemDiagram> = systems
+        .iter()
+        .filter(|system| {
+            let shadowed = is_component(&system.name);
+            if shadowed {
+                warn!("System {} has the same name as a component", system.name);
+            }
+            !shadowed
+        })
+        .map(|system| (system.name.as_str(), system))
+        .collect();
+
+    diagrams
+        .keys()
+        .map(|name| {
+            let resolved = resolve_system(name, &diagrams, &is_component, &mut vec![]);
+            (name.to_string(), resolved)
+        })
+        .collect()
+}
+
+fn resolve_system(
+    name: &str,
+    diagrams: &HashMap<&str, &SystemDiagram>,
+    is_component: &impl Fn(&str) -> bool,
+    stack: &mut Vec<String>,
+) -> Result<SystemExpression, String> {
+    if stack.iter().any(|other| other == name) {
+        stack.push(name.to_string());
+        return Err(format!("Systems form a cycle: {}", stack.join(" -> ")));
+    }
+
+    stack.push(name.to_string());
+    let expression = diagrams[name].to_expression()?;
+    let resolved = replace_systems(expression, name, diagrams, is_component, stack)?;
+    stack.pop();
+    Ok(resolved)
+}
+
+fn replace_systems(
+    expression: SystemExpression,
+    system: &str,
+    diagrams: &HashMap<&str, &SystemDiagram>,
+    is_component: &impl Fn(&str) -> bool,
+    stack: &mut Vec<String>,
+) -> Result<SystemExpression, String> {
+    if let SystemExpression::Component(name, _) = &expression {
+        return if is_component(name) {
+            Ok(expression)
+        } else if diagrams.contains_key(name.as_str()) {
+            resolve_system(name, diagrams, is_component, stack)
+        } else {
+            Err(format!(
+                "System {} has an instance of {}, which is neither a component nor a system",
+                system, name
+            ))
+        };
+    }
+
+    let mut replace = |expr: Box<SystemExpression>| -> Result<Box<SystemExpression>, String> {
+        replace_systems(*expr, system, diagrams, is_component, stack).map(Box::new)
+    };
+
+    Ok(match expression {
+        SystemExpression::Quotient(l, r) => SystemExpression::Quotient(replace(l)?, replace(r)?),
+        SystemExpression::Composition(l, r) => {
+            SystemExpression::Composition(replace(l)?, replace(r)?)
+        }
+        SystemExpression::Conjunction(l, r) => {
+            SystemExpression::Conjunction(replace(l)?, replace(r)?)
+        }
+        SystemExpression::Hiding(s, actions) => SystemExpression::Hiding(replace(s)?, actions),
+        SystemExpression::Renaming(s, renamings) => {
+            SystemExpression::Renaming(replace(s)?, renamings)
+        }
+        SystemExpression::Component(..) => unreachable!(),
+    })
+}
@@ -1,7 +1,7 @@
 use crate::DataReader::parse_edge;
 use crate::EdgeEval::updater::CompiledUpdate;
 use crate::ModelObjects::Expressions::BoolExpression;
-use crate::ModelObjects::{Component, DeclarationProvider, Edge, State};
+use crate::ModelObjects::{Component, DeclarationProvider, Edge, State, Valuation};
 use crate::TransitionSystems::{CompositionType, LocationTree, TransitionID};
 use edbm::util::constraints::ClockIndex;
 use edbm::zones::OwnedFederation;
@@ -53,6 +53,52 @@ impl Transition {
         }
     }
 
+    /// Create the transition of `edge` taken while the integer variables of `comp` hold the values in `valuation`.
+    /// Returns the transition and the valuation after the updates, or `None` if the guard cannot be satisfied.
+    pub fn from_valued_edge(
+        comp: &Component,
+        edge: &Edge,
+        valuation: &Valuation,
+        dim: ClockIndex,
+    ) -> Result<Option<(Transition, Valuation)>, String> {
+        let decls = comp.get_declarations();
+        let guard_zone = edge.apply_guard(
+            &valuation.to_declarations(decls),
+            OwnedFederation::universe(dim),
+        );
+        if guard_zone.is_empty() {
+            return Ok(None);
+        }
+
+        // The updates are evaluated in order, so later updates see the values assigned by earlier ones
+        let mut target_valuation = valuation.clone();
+        let mut compiled_updates = vec![];
+        for update in edge.get_update().iter().flatten() {
+            let name = update.get_variable_name();
+            if let Some(&clock_index) = decls.get_clock_index_by_name(name) {
+                compiled_updates.push(CompiledUpdate {
+                    clock_index,
+                    value: target_valuation.evaluate(update.get_expression())?,
+                });
+            } else {
+                target_valuation.apply_update(update, decls)?;
+            }
+        }
+
+        let target_loc = comp.get_location_by_name(&edge.target_location);
+        let target_locations = LocationTree::valued(target_loc, &target_valuation, decls, dim)?;
+
+        Ok(Some((
+            Transition {
+                id: TransitionID::Simple(edge.id.clone()),
+                guard_zone,
+                target_locations,
+                updates: compiled_updates,
+            },
+            target_valuation,
+        )))
+    }
+
     pub fn use_transition(&self, state: &mut State) -> bool {
         let mut zone = state.take_zone();
         zone = self.apply_guards(zone);
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use crate::DataReader::parse_edge::Update;
+use crate::ModelObjects::Declarations;
+use crate::ModelObjects::Expressions::ArithExpression;
+
+/// The range of values an integer variable without a declared range can hold, equal to the default range of `int` in UPPAAL
+pub const INT_RANGE: (i32, i32) = (-32768, 32767);
+
+/// An assignment of values to the integer variables of a component.
+///
+/// The variables are kept in a sorted map so valuations can be hashed and printed deterministically.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Valuation {
+    values: BTreeMap<String, i32>,
+}
+
+impl Valuation {
+    /// Creates the valuation holding the initial values of the integer variables in `decls`
+    pub fn from_declarations(decls: &Declarations) -> Self {
+        Valuation {
+            values: decls
+                .ints
+                .iter()
+                .map(|(name, value)| (name.clone(), *value))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<i32> {
+        self.values.get(name).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &i32)> {
+        self.values.iter()
+    }
+
+    /// Returns a copy of `decls` where the integer variables hold the values of this valuation,
+    /// so guards and invariants can be applied with the existing constraint applier.
+    pub fn to_declarations(&self, decls: &Declarations) -> Declarations {
+        let mut decls = decls.clone();
+        decls.ints = self
+            .values
+            .iter()
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+        decls
+    }
+
+    /// Evaluates an integer expression in this valuation.
+    /// Fails if the expression refers to a clock or an undeclared variable, or if it overflows.
+    pub fn evaluate(&self, expr: &ArithExpression) -> Result<i32, String> {
+        let binary = |left: &ArithExpression, right: &ArithExpression| {
+            Ok::<_, String>((self.evaluate(left)?, self.evaluate(right)?))
+        };
+        let overflow = || format!("Integer overflow in \"{}\"", expr);
+        match expr {
+            ArithExpression::Int(value) => Ok(*value),
+            ArithExpression::VarName(name) => self
+                .get(name)
+                .ok_or_else(|| format!("\"{}\" is not an integer variable", name)),
+            ArithExpression::Clock(index) => Err(format!(
+                "Clock with index {} cannot be used in an integer expression",
+                index
+            )),
+            ArithExpression::Addition(left, right) => {
+                let (l, r) = binary(left, right)?;
+                l.checked_add(r).ok_or_else(overflow)
+            }
+            ArithExpression::Difference(left, right) => {
+                let (l, r) = binary(left, right)?;
+                l.checked_sub(r).ok_or_else(overflow)
+            }
+            ArithExpression::Multiplication(left, right) => {
+                let (l, r) = binary(left, right)?;
+                l.checked_mul(r).ok_or_else(overflow)
+            }
+            ArithExpression::Division(left, right) => match binary(left, right)? {
+                (_, 0) => Err(format!("Division by zero in \"{}\"", expr)),
+                (l, r) => l.checked_div(r).ok_or_else(overflow),
+            },
+            ArithExpression::Modulo(left, right) => match binary(left, right)? {
+                (_, 0) => Err(format!("Modulo by zero in \"{}\"", expr)),
+                (l, r) => l.checked_rem(r).ok_or_else(overflow),
+            },
+        }
+    }
+
+    /// Assigns the value of the expression in `update` to its integer variable.
+    /// Fails if the new value is outside of the range of the variable in `decls`.
+    pub fn apply_update(&mut self, update: &Update, decls: &Declarations) -> Result<(), String> {
+        let value = self.evaluate(update.get_expression())?;
+        let name = update.get_variable_name();
+        let (lower, upper) = decls.get_int_range(name);
+        if value < lower || value > upper {
+            return Err(format!(
+                "The value {} assigned to \"{}\" is outside the range [{}, {}]",
+                value, name, lower, upper
+            ));
+        }
+        match self.values.get_mut(name) {
+            Some(old) => {
+                *old = value;
+                Ok(())
+            }
+            None => Err(format!("\"{}\" is not an integer variable", name)),
+        }
+    }
+}
+
+impl Display for Valuation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")?;
+        for (index, (name, value)) in self.values.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        write!(f, "}}")
+    }
+}
@@ -1,10 +1,15 @@
 use crate::ProtobufServer::services::ecdar_backend_server::EcdarBackend;
 
 use crate::DataReader::component_loader::ModelCache;
+use crate::ProtobufServer::ecdar_requests::cancel_query::RunningQueries;
+use crate::ProtobufServer::ecdar_requests::simulation_sessions::SimulationSessions;
 use crate::ProtobufServer::services::{
-    QueryRequest, QueryResponse, SimulationStartRequest, SimulationStepRequest,
+    CancelQueryRequest, QueryRequest, QueryResponse, SessionDelayRequest, SessionJumpRequest,
+    SessionStepRequest, SimulationBatchRequest, SimulationReport, SimulationSessionRequest,
+    SimulationSessionResponse, SimulationStartRequest, SimulationStepRequest,
     SimulationStepResponse, UserTokenResponse,
 };
+use futures::channel::oneshot;
 use futures::executor::block_on;
 use futures::FutureExt;
 use std::panic::UnwindSafe;
@@ -17,6 +22,8 @@ use rayon::{ThreadPool, ThreadPoolBuilder};
 pub struct ConcreteEcdarBackend {
     thread_pool: ThreadPool,
     model_cache: ModelCache,
+    running_queries: RunningQueries,
+    simulation_sessions: SimulationSessions,
     num: AtomicI32,
 }
 
@@ -28,6 +35,8 @@ impl ConcreteEcdarBackend {
                 .build()
                 .unwrap(),
             model_cache: ModelCache::new(cache_size),
+            running_queries: RunningQueries::default(),
+            simulation_sessions: SimulationSessions::default(),
             num: AtomicI32::new(1),
         }
     }
@@ -42,6 +51,8 @@ impl Default for ConcreteEcdarBackend {
                 .unwrap(),
 
             model_cache: ModelCache::default(),
+            running_queries: RunningQueries::default(),
+            simulation_sessions: SimulationSessions::default(),
             num: AtomicI32::new(1),
         }
     }
@@ -91,18 +102,36 @@ impl EcdarBackend for ConcreteEcdarBackend {
         async fn async_query(
             request: QueryRequest,
             cache: ModelCache,
+            running_queries: RunningQueries,
         ) -> Result<QueryResponse, Status> {
-            ConcreteEcdarBackend::handle_send_query(request, cache)
+            ConcreteEcdarBackend::handle_send_query(request, cache, running_queries)
         }
         let cache = self.model_cache.clone();
+        let running_queries = self.running_queries.clone();
 
-        self.thread_pool
-            .install(|| block_on(catch_unwind(async_query(request.into_inner(), cache))))
+        // The query is run on the thread pool without blocking the server, so it can handle other requests, e.g. cancelling the query, meanwhile
+        let (sender, receiver) = oneshot::channel();
+        self.thread_pool.spawn(move || {
+            let response = block_on(catch_unwind(async_query(
+                request.into_inner(),
+                cache,
+                running_queries,
+            )));
+            // The receiver is gone if the client stopped waiting for the response
+            let _ = sender.send(response);
+        });
 
-        // TODO: Test whether there is a large performance difference between block_on and the non-catching commented out code below
-        // self.thread_pool.install(|| {
-        //     ConcreteEcdarBackend::handle_send_query(request.into_inner(), cache).map(Response::new)
-        // })
+        receiver
+            .await
+            .unwrap_or_else(|_| Err(Status::internal("The query stopped without a response")))
+    }
+
+    async fn cancel_query(
+        &self,
+        request: Request<CancelQueryRequest>,
+    ) -> Result<Response<()>, Status> {
+        ConcreteEcdarBackend::handle_cancel_query(request.into_inner(), &self.running_queries)
+            .map(Response::new)
     }
 
     async fn start_simulation(
@@ -140,4 +169,130 @@ impl EcdarBackend for ConcreteEcdarBackend {
         ))
         .await
     }
+
+    async fn simulate_batch(
+        &self,
+        request: Request<SimulationBatchRequest>,
+    ) -> Result<Response<SimulationReport>, Status> {
+        async fn async_simulate_batch(
+            request: SimulationBatchRequest,
+            cache: ModelCache,
+        ) -> Result<SimulationReport, Status> {
+            ConcreteEcdarBackend::handle_simulate_batch(request, cache)
+        }
+        let cache = self.model_cache.clone();
+
+        // The runs may take a while, so like queries they are taken on the thread pool without blocking the server
+        let (sender, receiver) = oneshot::channel();
+        self.thread_pool.spawn(move || {
+            let response = block_on(catch_unwind(async_simulate_batch(
+                request.into_inner(),
+                cache,
+            )));
+            let _ = sender.send(response);
+        });
+
+        receiver.await.unwrap_or_else(|_| {
+            Err(Status::internal(
+                "The simulation stopped without a response",
+            ))
+        })
+    }
+
+    async fn start_simulation_session(
+        &self,
+        request: Request<SimulationStartRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        async fn async_start_simulation_session(
+            request: SimulationStartRequest,
+            cache: ModelCache,
+            sessions: SimulationSessions,
+        ) -> Result<SimulationSessionResponse, Status> {
+            ConcreteEcdarBackend::handle_start_simulation_session(request, cache, &sessions)
+        }
+
+        catch_unwind(async_start_simulation_session(
+            request.into_inner(),
+            self.model_cache.clone(),
+            self.simulation_sessions.clone(),
+        ))
+        .await
+    }
+
+    async fn take_session_step(
+        &self,
+        request: Request<SessionStepRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        async fn async_take_session_step(
+            request: SessionStepRequest,
+            sessions: SimulationSessions,
+        ) -> Result<SimulationSessionResponse, Status> {
+            ConcreteEcdarBackend::handle_take_session_step(request, &sessions)
+        }
+
+        catch_unwind(async_take_session_step(
+            request.into_inner(),
+            self.simulation_sessions.clone(),
+        ))
+        .await
+    }
+
+    async fn delay_simulation_step(
+        &self,
+        request: Request<SessionDelayRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        async fn async_delay_simulation_step(
+            request: SessionDelayRequest,
+            sessions: SimulationSessions,
+        ) -> Result<SimulationSessionResponse, Status> {
+            ConcreteEcdarBackend::handle_delay_simulation_step(request, &sessions)
+        }
+
+        catch_unwind(async_delay_simulation_step(
+            request.into_inner(),
+            self.simulation_sessions.clone(),
+        ))
+        .await
+    }
+
+    async fn undo_simulation_step(
+        &self,
+        request: Request<SimulationSessionRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        ConcreteEcdarBackend::handle_undo_simulation_step(
+            request.into_inner(),
+            &self.simulation_sessions,
+        )
+        .map(Response::new)
+    }
+
+    async fn redo_simulation_step(
+        &self,
+        request: Request<SimulationSessionRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        ConcreteEcdarBackend::handle_redo_simulation_step(
+            request.into_inner(),
+            &self.simulation_sessions,
+        )
+        .map(Response::new)
+    }
+
+    async fn jump_to_simulation_step(
+        &self,
+        request: Request<SessionJumpRequest>,
+    ) -> Result<Response<SimulationSessionResponse>, Status> {
+        ConcreteEcdarBackend::handle_jump_to_simulation_step(
+            request.into_inner(),
+            &self.simulation_sessions,
+        )
+        .map(Response::new)
+    }
+
+    async fn end_simulation(
+        &self,
+        request: Request<SimulationSessionRequest>,
+    ) -> Result<Response<()>, Status> {
+        ConcreteEcdarBackend::handle_end_simulation(request.into_inner(), &self.simulation_sessions)
+            .map(Response::new)
+    }
 }
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use log::info;
+use tonic::Status;
+
+use crate::ProtobufServer::services::CancelQueryRequest;
+use crate::ProtobufServer::ConcreteEcdarBackend;
+use crate::System::cancellation::CancellationToken;
+
+/// The cancellation tokens of the queries currently running on the server, identified by their user and query id
+#[derive(Debug, Clone, Default)]
+pub struct RunningQueries {
+    tokens: Arc<Mutex<HashMap<(i32, i32), CancellationToken>>>,
+}
+
+impl RunningQueries {
+    /// Registers a running query, which can be cancelled until the returned [RunningQuery] is dropped.
+    /// A query registered with the same user and query id as a running query replaces it as the one cancelled by those ids.
+    pub fn register(&self, user_id: i32, query_id: i32, token: CancellationToken) -> RunningQuery {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert((user_id, query_id), token.clone());
+
+        RunningQuery {
+            queries: self.clone(),
+            key: (user_id, query_id),
+            token,
+        }
+    }
+
+    /// Cancels a running query, returning false if no such query is running.
+    pub fn cancel(&self, user_id: i32, query_id: i32) -> bool {
+        match self.tokens.lock().unwrap().get(&(user_id, query_id)) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Removes the query from the [RunningQueries] when dropped, unless another query has replaced it
+pub struct RunningQuery {
+    queries: RunningQueries,
+    key: (i32, i32),
+    token: CancellationToken,
+}
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        let mut tokens = self.queries.tokens.lock().unwrap();
+        if let Some(token) = tokens.get(&self.key) {
+            if token.is_same(&self.token) {
+                tokens.remove(&self.key);
+            }
+        }
+    }
+}
+
+impl ConcreteEcdarBackend {
+    /// Handles a cancel query request: Cancelling the running query with the user and query id given in the `request`.
+    pub fn handle_cancel_query(
+        request: CancelQueryRequest,
+        running_queries: &RunningQueries,
+    ) -> Result<(), Status> {
+        if running_queries.cancel(request.user_id, request.query_id) {
+            info!(
+                "Cancelled query {} of user {}",
+                request.query_id, request.user_id
+            );
+            Ok(())
+        } else {
+            Err(Status::not_found(format!(
+                "No running query {} for user {}",
+                request.query_id, request.user_id
+            )))
+        }
+    }
+}
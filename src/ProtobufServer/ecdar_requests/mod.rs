@@ -1,3 +1,5 @@
+pub mod cancel_query;
 mod request_util;
 mod send_query;
 mod simulation_requests;
+pub mod simulation_sessions;
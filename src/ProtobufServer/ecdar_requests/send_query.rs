@@ -2,22 +2,25 @@ use crate::extract_system_rep::ExecutableQueryError;
 use crate::DataReader::component_loader::ModelCache;
 use crate::DataReader::json_writer::component_to_json;
 use crate::DataReader::parse_queries;
-use crate::ModelObjects::Query;
+use crate::ModelObjects::{Expressions::QueryExpression, Query};
+use crate::ProtobufServer::ecdar_requests::cancel_query::RunningQueries;
 use crate::ProtobufServer::ecdar_requests::request_util::get_or_insert_model;
 use crate::ProtobufServer::services::component::Rep;
 use crate::ProtobufServer::services::query_response::{
-    Error as InnerError, Result as ProtobufResult, Success,
+    Cancelled, Error as InnerError, Result as ProtobufResult, Success, Timeout,
 };
 use crate::ProtobufServer::services::{
     Component as ProtobufComponent, QueryRequest, QueryResponse,
 };
 use crate::ProtobufServer::ConcreteEcdarBackend;
 use crate::System::query_failures::{
-    ConsistencyFailure, DeterminismFailure, PathFailure, QueryResult, RefinementFailure,
-    SystemRecipeFailure,
+    ConsistencyFailure, DeterminismFailure, ImplementationFailure, PathFailure, QueryResult,
+    RefinementFailure, SpecificationFailure, SystemRecipeFailure,
 };
 
+use crate::System::cancellation::{self, CancellationToken};
 use crate::System::extract_system_rep;
+use crate::System::limits::ExplorationLimits;
 
 use log::trace;
 use tonic::Status;
@@ -32,6 +35,7 @@ impl ConcreteEcdarBackend {
     pub fn handle_send_query(
         query_request: QueryRequest,
         mut model_cache: ModelCache,
+        running_queries: RunningQueries,
     ) -> Result<QueryResponse, Status> {
         trace!("Received query: {:?}", query_request);
         let components_info = query_request.components_info.as_ref().unwrap();
@@ -45,63 +49,114 @@ impl ConcreteEcdarBackend {
             components_info.components_hash,
             proto_components,
         );
-        component_container.set_settings(query_request.settings.unwrap_or(crate::DEFAULT_SETTINGS));
+        let settings = query_request.settings.unwrap_or(crate::DEFAULT_SETTINGS);
+        let token = CancellationToken::from_timeout_ms(settings.timeout_ms)
+            .with_limits(ExplorationLimits::from_settings(&settings));
+        component_container.set_settings(settings);
 
-        let out =
+        let executable_query =
             match extract_system_rep::create_executable_query(&query, &mut component_container) {
-                Ok(query) => {
-                    let result = query.execute();
-                    Ok(QueryResponse {
-                        query_id: query_request.query_id,
-                        info: vec![], // TODO: Should be logs
-                        result: Some(result.into()),
-                    })
+                Ok(executable_query) => executable_query,
+                Err(ExecutableQueryError::Custom(e)) => {
+                    return Err(Status::invalid_argument(format!(
+                        "Creation of query failed: {}",
+                        e
+                    )))
                 }
-                Err(ExecutableQueryError::Custom(e)) => Err(Status::invalid_argument(format!(
-                    "Creation of query failed: {}",
-                    e
-                ))),
                 Err(ExecutableQueryError::SystemRecipeFailure(failure)) => {
-                    Ok(QueryResponse {
+                    return Ok(QueryResponse {
                         query_id: query_request.query_id,
                         info: vec![], // TODO: Should be logs
                         result: Some(failure.into()),
-                    })
+                    });
                 }
             };
-        out
+
+        // The query can be cancelled while it is executed, but not while its system is built
+        let _running_query =
+            running_queries.register(user_id, query_request.query_id, token.clone());
+        let result = cancellation::run_cancellable(&token, || executable_query.execute())
+            .unwrap_or_else(QueryResult::from);
+        Ok(QueryResponse {
+            query_id: query_request.query_id,
+            info: vec![], // TODO: Should be logs
+            result: Some(result.into()),
+        })
     }
 }
 
 fn parse_query(query_request: &QueryRequest) -> Result<Query, Status> {
-    let mut queries = parse_queries::parse_to_query(&query_request.query);
+    let mut queries = parse_queries::parse_to_expression_tree(&query_request.query)
+        .map_err(Status::invalid_argument)?;
 
     if queries.len() != 1 {
-        Err(Status::invalid_argument(
+        return Err(Status::invalid_argument(
             "This procedure takes in exactly 1 query",
-        ))
-    } else {
-        Ok(queries.remove(0))
+        ));
     }
+    let query = queries.remove(0);
+
+    // The server must not read its own files, so the trace of a trace check is sent along with the query
+    let trace = match &query {
+        QueryExpression::TraceCheck { .. } if query_request.trace.is_empty() => {
+            return Err(Status::invalid_argument(
+                "A trace check must send the contents of its trace file in the request",
+            ))
+        }
+        QueryExpression::TraceCheck { .. } => Some(query_request.trace.clone()),
+        _ => None,
+    };
+    Ok(Query {
+        query: Some(query),
+        comment: String::new(),
+        trace,
+        ..Default::default()
+    })
 }
 
 impl From<QueryResult> for ProtobufResult {
     fn from(result: QueryResult) -> ProtobufResult {
         match result {
             QueryResult::Reachability(Ok(path)) => ProtobufResult::ReachabilityPath(path.into()),
-            QueryResult::Refinement(Ok(_))
+            QueryResult::Refinement(Ok(Some(relation))) => {
+                ProtobufResult::RefinementRelation(relation.into())
+            }
+            QueryResult::Temporal(Ok(Some(lasso))) => ProtobufResult::TemporalPath(lasso.into()),
+            QueryResult::Refinement(Ok(None))
             | QueryResult::Consistency(Ok(_))
-            | QueryResult::Determinism(Ok(_)) => ProtobufResult::Success(Success {}),
+            | QueryResult::Determinism(Ok(_))
+            | QueryResult::Implementation(Ok(_))
+            | QueryResult::Specification(Ok(_))
+            | QueryResult::Temporal(Ok(None)) => ProtobufResult::Success(Success {}),
             QueryResult::Refinement(Err(fail)) => fail.into(),
             QueryResult::Consistency(Err(fail)) => fail.into(),
             QueryResult::Determinism(Err(fail)) => fail.into(),
+            QueryResult::Implementation(Err(fail)) => fail.into(),
+            QueryResult::Specification(Err(fail)) => fail.into(),
             QueryResult::Reachability(Err(fail)) => fail.into(),
+            QueryResult::Temporal(Err(fail)) => ProtobufResult::TemporalFailure(fail.into()),
+            QueryResult::ConcreteTrace(Ok(trace)) => ProtobufResult::ConcreteTrace(trace.into()),
+            QueryResult::ConcreteTrace(Err(fail)) => string_error(fail.to_string()),
+            QueryResult::TraceCheck(Ok(_)) => ProtobufResult::Success(Success {}),
+            QueryResult::TraceCheck(Err(fail)) => ProtobufResult::TraceCheckFailure(fail.into()),
+            QueryResult::Simulation(report) => ProtobufResult::SimulationReport(report.into()),
+            QueryResult::TestCases(suite) => ProtobufResult::TestSuite(suite.into()),
+            QueryResult::Strategy(Ok(None)) => ProtobufResult::Success(Success {}),
+            QueryResult::Strategy(Ok(Some(strategy))) => {
+                ProtobufResult::RefinementStrategy(strategy.into())
+            }
+            QueryResult::Strategy(Err(precondition)) => {
+                RefinementFailure::Precondition(precondition).into()
+            }
 
             QueryResult::GetComponent(comp) => ProtobufResult::Component(ProtobufComponent {
                 rep: Some(Rep::Json(component_to_json(&comp))),
             }),
 
             QueryResult::RecipeFailure(recipe) => recipe.into(),
+            QueryResult::Timeout => ProtobufResult::Timeout(Timeout {}),
+            QueryResult::Cancelled => ProtobufResult::Cancelled(Cancelled {}),
+            QueryResult::Inconclusive(limit) => ProtobufResult::Inconclusive(limit.into()),
             QueryResult::CustomError(custom) => string_error(custom),
         }
     }
@@ -125,6 +180,18 @@ impl From<ConsistencyFailure> for ProtobufResult {
     }
 }
 
+impl From<SpecificationFailure> for ProtobufResult {
+    fn from(fail: SpecificationFailure) -> ProtobufResult {
+        ProtobufResult::Specification(fail.into())
+    }
+}
+
+impl From<ImplementationFailure> for ProtobufResult {
+    fn from(fail: ImplementationFailure) -> ProtobufResult {
+        ProtobufResult::Implementation(fail.into())
+    }
+}
+
 impl From<RefinementFailure> for ProtobufResult {
     fn from(fail: RefinementFailure) -> ProtobufResult {
         ProtobufResult::Refinement(fail.into())
@@ -1,15 +1,30 @@
 use tonic::Status;
 
 use crate::{
-    DataReader::{component_loader::ModelCache, proto_reader::proto_decision_to_decision},
+    DataReader::{
+        component_loader::ModelCache,
+        parse_queries::{parse_to_state_expr, parse_to_system_expr},
+        proto_reader::proto_decision_to_decision,
+    },
+    ModelObjects::{Expressions::QueryExpression, Query},
     ProtobufServer::{
-        services::{SimulationStartRequest, SimulationStepRequest, SimulationStepResponse},
+        services::{
+            SimulationBatchRequest, SimulationReport as ProtoSimulationReport,
+            SimulationStartRequest, SimulationStepRequest, SimulationStepResponse,
+        },
         ConcreteEcdarBackend,
     },
-    System::specifics::{SpecificDecision, SpecificState},
+    System::{
+        cancellation::{self, CancellationToken, Interrupted},
+        extract_system_rep::{self, ExecutableQueryError},
+        limits::ExplorationLimits,
+        query_failures::QueryResult,
+        specifics::{SpecificDecision, SpecificState},
+    },
 };
 
-use super::request_util::simulation_info_to_transition_system;
+use super::request_util::{get_or_insert_model, simulation_info_to_transition_system};
+use crate::Simulation::automatic::AutomaticSimulationOptions;
 use crate::Simulation::decision::Decision;
 
 impl ConcreteEcdarBackend {
@@ -68,4 +83,89 @@ impl ConcreteEcdarBackend {
             new_decision_points: decision_points,
         })
     }
+
+    /// Handles a simulate batch request: Taking the random or guided walks through the transition system given in the `request`
+    /// and responding with the coverage of its components.
+    /// The runs and steps default to those of [`AutomaticSimulationOptions`] when they are 0,
+    /// and the runs are stopped with an error once they exceed the timeout or limits of the settings.
+    pub fn handle_simulate_batch(
+        request: SimulationBatchRequest,
+        mut cache: ModelCache,
+    ) -> Result<ProtoSimulationReport, Status> {
+        let simulation_info = request.simulation_info.unwrap();
+        let info = simulation_info.components_info.as_ref().unwrap();
+
+        let system = parse_to_system_expr(&simulation_info.component_composition)
+            .map_err(Status::invalid_argument)?;
+        let target = match request.target.trim() {
+            "" => None,
+            target => Some(parse_to_state_expr(target).map_err(Status::invalid_argument)?),
+        };
+        let defaults = AutomaticSimulationOptions::default();
+        let options = AutomaticSimulationOptions {
+            runs: match request.runs {
+                0 => defaults.runs,
+                runs => runs as usize,
+            },
+            max_steps: match request.max_steps {
+                0 => defaults.max_steps,
+                max_steps => max_steps as usize,
+            },
+            seed: request.seed,
+            action_weights: request.action_weights.into_iter().collect(),
+        };
+
+        let mut component_container = get_or_insert_model(
+            &mut cache,
+            simulation_info.user_id,
+            info.components_hash,
+            &info.components,
+        );
+        let settings = request.settings.unwrap_or(crate::DEFAULT_SETTINGS);
+        let token = CancellationToken::from_timeout_ms(settings.timeout_ms)
+            .with_limits(ExplorationLimits::from_settings(&settings));
+        component_container.set_settings(settings);
+        let query = Query {
+            query: Some(QueryExpression::Simulate {
+                system,
+                options,
+                target,
+            }),
+            comment: String::new(),
+            ..Default::default()
+        };
+
+        let executable_query =
+            extract_system_rep::create_executable_query(&query, &mut component_container).map_err(
+                |err| match err {
+                    ExecutableQueryError::Custom(e) => Status::invalid_argument(e),
+                    ExecutableQueryError::SystemRecipeFailure(failure) => {
+                        Status::invalid_argument(failure.to_string())
+                    }
+                },
+            )?;
+        let result = cancellation::run_cancellable(&token, || executable_query.execute())
+            .map_err(interrupted_error)?;
+
+        match result {
+            QueryResult::Simulation(report) => Ok(report.into()),
+            QueryResult::Timeout => Err(interrupted_error(Interrupted::Timeout)),
+            QueryResult::Cancelled => Err(interrupted_error(Interrupted::Cancelled)),
+            QueryResult::Inconclusive(limit) => {
+                Err(interrupted_error(Interrupted::LimitReached(limit)))
+            }
+            result => Err(Status::internal(format!(
+                "Expected a simulation report, got {:?}",
+                result
+            ))),
+        }
+    }
+}
+
+fn interrupted_error(interrupted: Interrupted) -> Status {
+    match interrupted {
+        Interrupted::Timeout => Status::deadline_exceeded(interrupted.to_string()),
+        Interrupted::Cancelled => Status::cancelled(interrupted.to_string()),
+        Interrupted::LimitReached(_) => Status::resource_exhausted(interrupted.to_string()),
+    }
 }
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use log::info;
+use tonic::Status;
+
+use crate::DataReader::component_loader::ModelCache;
+use crate::ProtobufServer::services::{
+    SessionDelayRequest, SessionJumpRequest, SessionStepRequest, SimulationSessionRequest,
+    SimulationSessionResponse, SimulationStartRequest, SimulationStepResponse,
+};
+use crate::ProtobufServer::ConcreteEcdarBackend;
+use crate::Simulation::session::{SimulationError, SimulationSession, SimulationStep};
+use crate::System::specifics::{SpecificDecision, SpecificState};
+
+use super::request_util::simulation_info_to_transition_system;
+
+/// How long a session may go unused before it is closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The simulation sessions currently open on the server, identified by their session id
+#[derive(Clone)]
+pub struct SimulationSessions {
+    sessions: Arc<Mutex<HashMap<i32, OpenSession>>>,
+    next_id: Arc<AtomicI32>,
+    idle_timeout: Duration,
+}
+
+/// A session along with the user who opened it and when it was last used
+struct OpenSession {
+    user_id: i32,
+    last_used: Instant,
+    session: Arc<Mutex<SimulationSession>>,
+}
+
+impl Default for SimulationSessions {
+    fn default() -> Self {
+        SimulationSessions::with_idle_timeout(IDLE_TIMEOUT)
+    }
+}
+
+impl std::fmt::Debug for SimulationSessions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimulationSessions")
+            .field("sessions", &self.sessions.lock().unwrap().len())
+            .field("idle_timeout", &self.idle_timeout)
+            .finish()
+    }
+}
+
+impl SimulationSessions {
+    /// Creates an empty set of sessions where sessions unused for `idle_timeout` are closed
+    pub fn with_idle_timeout(idle_timeout: Duration) -> Self {
+        SimulationSessions {
+            sessions: Default::default(),
+            next_id: Default::default(),
+            idle_timeout,
+        }
+    }
+
+    /// Opens a new session owned by `user_id`, returning its id
+    pub fn open(&self, user_id: i32, session: SimulationSession) -> i32 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let mut sessions = self.sessions.lock().unwrap();
+        self.close_idle(&mut sessions);
+        sessions.insert(
+            id,
+            OpenSession {
+                user_id,
+                last_used: Instant::now(),
+                session: Arc::new(Mutex::new(session)),
+            },
+        );
+        id
+    }
+
+    /// Returns the session with `id` if it is owned by `user_id`.
+    /// The session is locked separately so other sessions can be used meanwhile
+    pub fn get(&self, id: i32, user_id: i32) -> Result<Arc<Mutex<SimulationSession>>, Status> {
+        let mut sessions = self.sessions.lock().unwrap();
+        self.close_idle(&mut sessions);
+        let open = owned_session(sessions.get_mut(&id), id, user_id)?;
+        open.last_used = Instant::now();
+        Ok(Arc::clone(&open.session))
+    }
+
+    /// Closes the session with `id` if it is owned by `user_id`
+    pub fn close(&self, id: i32, user_id: i32) -> Result<(), Status> {
+        let mut sessions = self.sessions.lock().unwrap();
+        self.close_idle(&mut sessions);
+        owned_session(sessions.get_mut(&id), id, user_id)?;
+        sessions.remove(&id);
+        Ok(())
+    }
+
+    fn close_idle(&self, sessions: &mut HashMap<i32, OpenSession>) {
+        sessions.retain(|id, open| {
+            let idle = open.last_used.elapsed() >= self.idle_timeout;
+            if idle {
+                info!("Closed idle simulation session {}", id);
+            }
+            !idle
+        });
+    }
+}
+
+fn owned_session(
+    open: Option<&mut OpenSession>,
+    id: i32,
+    user_id: i32,
+) -> Result<&mut OpenSession, Status> {
+    match open {
+        Some(open) if open.user_id == user_id => Ok(open),
+        Some(_) => Err(Status::permission_denied(format!(
+            "Simulation session {} belongs to another user",
+            id
+        ))),
+        None => Err(Status::not_found(format!("No simulation session {}", id))),
+    }
+}
+
+impl ConcreteEcdarBackend {
+    /// Handles a start simulation session request: Compiling the transition system given in the `request` once,
+    /// and responding with the id of the new session along with its initial decision points.
+    pub fn handle_start_simulation_session(
+        request: SimulationStartRequest,
+        mut cache: ModelCache,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        let simulation_info = match request.simulation_info {
+            Some(info) if info.components_info.is_some() => info,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "A simulation session must be started with the components to simulate",
+                ))
+            }
+        };
+
+        let system = simulation_info_to_transition_system(&simulation_info, &mut cache);
+        let session = SimulationSession::new(system).map_err(simulation_error)?;
+        let response = session_response(0, &session);
+
+        let session_id = sessions.open(simulation_info.user_id, session);
+        info!("Started simulation session {}", session_id);
+        Ok(SimulationSessionResponse {
+            session_id,
+            ..response
+        })
+    }
+
+    /// Handles a take session step request: Taking the decision with the index given in the `request` from the current step of the session.
+    pub fn handle_take_session_step(
+        request: SessionStepRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        with_session(sessions, request.session_id, request.user_id, |session| {
+            session
+                .take_decision(request.decision_index as usize)
+                .map(|_| ())
+        })
+    }
+
+    /// Handles a delay simulation step request: Letting the time given in the `request` pass in the current step of the session.
+    pub fn handle_delay_simulation_step(
+        request: SessionDelayRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        with_session(sessions, request.session_id, request.user_id, |session| {
+            session.delay(request.delay).map(|_| ())
+        })
+    }
+
+    /// Handles an undo simulation step request: Going back to the previous step of the session.
+    pub fn handle_undo_simulation_step(
+        request: SimulationSessionRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        with_session(sessions, request.session_id, request.user_id, |session| {
+            session.undo().map(|_| ())
+        })
+    }
+
+    /// Handles a redo simulation step request: Going forward to the step of the session which was last undone.
+    pub fn handle_redo_simulation_step(
+        request: SimulationSessionRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        with_session(sessions, request.session_id, request.user_id, |session| {
+            session.redo().map(|_| ())
+        })
+    }
+
+    /// Handles a jump to simulation step request: Going to the step of the session given in the `request`.
+    pub fn handle_jump_to_simulation_step(
+        request: SessionJumpRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<SimulationSessionResponse, Status> {
+        with_session(sessions, request.session_id, request.user_id, |session| {
+            session.jump_to(request.step as usize).map(|_| ())
+        })
+    }
+
+    /// Handles an end simulation request: Closing the session and freeing its system and trace.
+    pub fn handle_end_simulation(
+        request: SimulationSessionRequest,
+        sessions: &SimulationSessions,
+    ) -> Result<(), Status> {
+        sessions.close(request.session_id, request.user_id)?;
+        info!("Ended simulation session {}", request.session_id);
+        Ok(())
+    }
+}
+
+/// Applies `action` to the session with `session_id` and responds with its current step
+fn with_session(
+    sessions: &SimulationSessions,
+    session_id: i32,
+    user_id: i32,
+    action: impl FnOnce(&mut SimulationSession) -> Result<(), SimulationError>,
+) -> Result<SimulationSessionResponse, Status> {
+    let session = sessions.get(session_id, user_id)?;
+    // A session is only changed once its next step has been computed, so it is still valid if computing a step panicked
+    let mut session = session.lock().unwrap_or_else(PoisonError::into_inner);
+    action(&mut session).map_err(simulation_error)?;
+    Ok(session_response(session_id, &session))
+}
+
+fn session_response(session_id: i32, session: &SimulationSession) -> SimulationSessionResponse {
+    SimulationSessionResponse {
+        session_id,
+        step: session.position() as u32,
+        trace_length: session.trace().len() as u32,
+        current: Some(step_response(session.current(), session)),
+    }
+}
+
+fn step_response(step: &SimulationStep, session: &SimulationSession) -> SimulationStepResponse {
+    let system = &**session.system();
+    SimulationStepResponse {
+        full_state: Some(SpecificState::from_state(&step.state, system).into()),
+        new_decision_points: step
+            .decisions
+            .iter()
+            .map(|decision| SpecificDecision::from_decision(decision, system).into())
+            .collect(),
+    }
+}
+
+fn simulation_error(error: SimulationError) -> Status {
+    match error {
+        SimulationError::NoInitialState | SimulationError::InvalidDelay(_) => {
+            Status::invalid_argument(error.to_string())
+        }
+        _ => Status::failed_precondition(error.to_string()),
+    }
+}
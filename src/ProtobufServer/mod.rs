@@ -1,5 +1,5 @@
 mod ecdar_backend;
-mod ecdar_requests;
+pub(crate) mod ecdar_requests;
 mod proto_conversions;
 mod server;
 
@@ -1,7 +1,10 @@
 use crate::ProtobufServer::services::query_response::{
     ConsistencyFailure as ProtobufConsistencyFailure,
-    DeterminismFailure as ProtobufDeterminismFailure, ModelFailure, ReachabilityFailure,
-    ReachabilityPath, RefinementFailure as ProtobufRefinementFailure,
+    DeterminismFailure as ProtobufDeterminismFailure,
+    ImplementationFailure as ProtobufImplementationFailure, Inconclusive, ModelFailure,
+    ReachabilityFailure, ReachabilityPath, RefinementFailure as ProtobufRefinementFailure,
+    RefinementRelation, SpecificationFailure as ProtobufSpecificationFailure,
+    TemporalFailure as ProtobufTemporalFailure,
 };
 use crate::ProtobufServer::services::{
     self, clock::Clock as ProtoClockEnum, clock::ComponentClock as ProtoComponentClock,
@@ -10,11 +13,18 @@ use crate::ProtobufServer::services::{
     Conjunction as ProtoConjunction, Constraint as ProtoConstraint,
     Disjunction as ProtoDisjunction, LeafLocation, LocationTree, State as ProtoState,
 };
+use crate::Simulation::automatic::{ComponentCoverage, SimulationReport, SimulationRun};
+use crate::Simulation::concrete::{
+    Rational, SpecificConcreteState, SpecificTimedStep, SpecificTimedTrace,
+};
+use crate::Simulation::test_generation::TestSuite;
+use crate::System::limits::LimitReached;
 use crate::System::query_failures::*;
+use crate::System::refinement_strategy::RefinementStrategy;
 use crate::System::specifics::{
     SpecialLocation, SpecificClock, SpecificClockVar, SpecificComp, SpecificConjunction,
-    SpecificConstraint, SpecificDecision, SpecificDisjunction, SpecificEdge, SpecificLocation,
-    SpecificPath, SpecificState,
+    SpecificConstraint, SpecificDecision, SpecificDisjunction, SpecificEdge, SpecificLasso,
+    SpecificLocation, SpecificPath, SpecificRelation, SpecificState,
 };
 
 impl From<SpecificState> for ProtoState {
@@ -26,6 +36,147 @@ impl From<SpecificState> for ProtoState {
     }
 }
 
+impl From<SpecificTimedTrace> for services::ConcreteTrace {
+    fn from(trace: SpecificTimedTrace) -> Self {
+        services::ConcreteTrace {
+            initial: Some(trace.initial.into()),
+            steps: trace.steps.into_iter().map(|step| step.into()).collect(),
+        }
+    }
+}
+
+impl From<SpecificTimedStep> for services::TimedStep {
+    fn from(step: SpecificTimedStep) -> Self {
+        services::TimedStep {
+            delay: Some(step.delay.into()),
+            action: step.action,
+            state: Some(step.state.into()),
+        }
+    }
+}
+
+impl From<SpecificConcreteState> for services::ConcreteState {
+    fn from(state: SpecificConcreteState) -> Self {
+        services::ConcreteState {
+            location_tree: Some(state.locations.into()),
+            clocks: state
+                .clocks
+                .into_iter()
+                .map(|(clock, value)| services::ClockValue {
+                    clock: Some(clock.into()),
+                    value: Some(value.into()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<Rational> for services::Rational {
+    fn from(value: Rational) -> Self {
+        services::Rational {
+            numerator: *value.numer(),
+            denominator: *value.denom(),
+        }
+    }
+}
+
+impl From<TraceCheckFailure> for services::TraceCheckFailure {
+    fn from(failure: TraceCheckFailure) -> Self {
+        let (event_index, event) = match failure.event {
+            Some((index, event)) => (index as u32, Some(event)),
+            None => (0, None),
+        };
+        services::TraceCheckFailure {
+            event_index,
+            action: event.as_ref().map(|e| e.action.clone()).unwrap_or_default(),
+            timestamp: event.map(|e| e.timestamp.into()),
+            reason: failure.deviation.to_string(),
+            states: failure
+                .states
+                .into_iter()
+                .map(|state| state.into())
+                .collect(),
+        }
+    }
+}
+
+impl From<SimulationReport> for services::SimulationReport {
+    fn from(report: SimulationReport) -> Self {
+        services::SimulationReport {
+            runs: report.runs.into_iter().map(|run| run.into()).collect(),
+            no_initial_state: report.no_initial_state,
+            coverage: report
+                .coverage
+                .into_iter()
+                .map(|(component_name, coverage)| services::ComponentCoverage {
+                    component_name,
+                    ..coverage.into()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<TestSuite> for services::TestSuite {
+    fn from(suite: TestSuite) -> Self {
+        services::TestSuite {
+            // The test cases are trees of varying shape, so they are sent in the same JSON format as they are exported in
+            json: serde_json::to_string(&suite.tests).expect("Test cases can always be serialized"),
+            coverage: suite
+                .coverage
+                .into_iter()
+                .map(|(component_name, coverage)| services::ComponentCoverage {
+                    component_name,
+                    ..coverage.into()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<RefinementStrategy> for services::RefinementStrategy {
+    fn from(strategy: RefinementStrategy) -> Self {
+        services::RefinementStrategy {
+            system: strategy.system,
+            complete: strategy.complete,
+            // The strategy is a decision tree of varying shape, so it is sent in the same JSON format as it is exported in
+            json: serde_json::to_string(&strategy.root)
+                .expect("Strategies can always be serialized"),
+        }
+    }
+}
+
+impl From<SimulationRun> for services::SimulationRun {
+    fn from(run: SimulationRun) -> Self {
+        services::SimulationRun {
+            steps: run.steps as u32,
+            reached_target: run.reached_target,
+            deadlocked: run.deadlocked,
+            final_state: Some(run.final_state.into()),
+        }
+    }
+}
+
+impl From<ComponentCoverage> for services::ComponentCoverage {
+    fn from(coverage: ComponentCoverage) -> Self {
+        services::ComponentCoverage {
+            component_name: String::new(),
+            visited_locations: coverage.visited_locations.into_iter().collect(),
+            visited_edges: coverage.visited_edges.into_iter().collect(),
+            total_locations: coverage.total_locations as u32,
+            total_edges: coverage.total_edges as u32,
+        }
+    }
+}
+
+impl From<SpecificRelation> for RefinementRelation {
+    fn from(relation: SpecificRelation) -> Self {
+        RefinementRelation {
+            states: relation.pairs.into_iter().map(|pair| pair.into()).collect(),
+        }
+    }
+}
+
 impl From<SpecificLocation> for LocationTree {
     fn from(loc: SpecificLocation) -> Self {
         use services::location_tree::NodeType;
@@ -210,6 +361,7 @@ impl From<DeterminismFailure> for ProtobufDeterminismFailure {
         Self {
             system: df.system,
             failure_state: Some(state_action_to_proto(df.state, df.action)),
+            path: Some(df.path.into()),
         }
     }
 }
@@ -221,25 +373,79 @@ impl From<ConsistencyFailure> for ProtobufConsistencyFailure {
             ConsistencyFailure::NoInitialState { system } => ProtobufConsistencyFailure {
                 system,
                 failure: Some(Failure::NoInitialState(0)),
+                path: None,
             },
             ConsistencyFailure::NotDeterministic(det) => {
                 let df: ProtobufDeterminismFailure = det.into();
                 ProtobufConsistencyFailure {
                     system: df.system.clone(),
+                    path: df.path.clone(),
                     failure: Some(Failure::Determinism(df)),
                 }
             }
-            ConsistencyFailure::InconsistentLoc { system, state }
-            | ConsistencyFailure::InconsistentFrom { system, state } => {
-                ProtobufConsistencyFailure {
+            ConsistencyFailure::InconsistentLoc {
+                system,
+                state,
+                path,
+            }
+            | ConsistencyFailure::InconsistentFrom {
+                system,
+                state,
+                path,
+            } => ProtobufConsistencyFailure {
+                system,
+                failure: Some(Failure::FailureState(state.into())),
+                path: Some(path.into()),
+            },
+        }
+    }
+}
+
+impl From<ImplementationFailure> for ProtobufImplementationFailure {
+    fn from(imf: ImplementationFailure) -> Self {
+        use services::query_response::implementation_failure::Failure;
+        match imf {
+            ImplementationFailure::NotSpecification(cf) => {
+                let cf: ProtobufConsistencyFailure = cf.into();
+                ProtobufImplementationFailure {
+                    system: cf.system.clone(),
+                    failure: Some(Failure::NotSpecification(cf)),
+                }
+            }
+            ImplementationFailure::NotOutputUrgent {
+                system,
+                action,
+                state,
+            } => ProtobufImplementationFailure {
+                system,
+                failure: Some(Failure::NotOutputUrgent(state_action_to_proto(
+                    state, action,
+                ))),
+            },
+            ImplementationFailure::NoIndependentProgress { system, state } => {
+                ProtobufImplementationFailure {
                     system,
-                    failure: Some(Failure::FailureState(state.into())),
+                    failure: Some(Failure::NoIndependentProgress(state.into())),
                 }
             }
         }
     }
 }
 
+impl From<SpecificationFailure> for ProtobufSpecificationFailure {
+    fn from(sf: SpecificationFailure) -> Self {
+        let (consistency, determinism) = match sf {
+            SpecificationFailure::NotConsistent(cf) => (Some(cf), None),
+            SpecificationFailure::NotDeterministic(df) => (None, Some(df)),
+            SpecificationFailure::NotConsistentNorDeterministic(cf, df) => (Some(cf), Some(df)),
+        };
+        ProtobufSpecificationFailure {
+            consistency: consistency.map(|cf| cf.into()),
+            determinism: determinism.map(|df| df.into()),
+        }
+    }
+}
+
 impl From<RefinementFailure> for ProtobufRefinementFailure {
     fn from(rf: RefinementFailure) -> Self {
         use services::query_response::refinement_failure::Failure;
@@ -250,11 +456,13 @@ impl From<RefinementFailure> for ProtobufRefinementFailure {
                 action,
                 state,
                 system,
+                path,
             } => ProtobufRefinementFailure {
                 system: system.name,
                 failure: Some(Failure::RefinementState(RefinementStateFailure {
                     unmatched: 1,
                     state: Some(state_action_to_proto(state, action)),
+                    path: Some(path.into()),
                 })),
             },
 
@@ -262,11 +470,13 @@ impl From<RefinementFailure> for ProtobufRefinementFailure {
                 action,
                 state,
                 system,
+                path,
             } => ProtobufRefinementFailure {
                 system: system.name,
                 failure: Some(Failure::RefinementState(RefinementStateFailure {
                     unmatched: 0,
                     state: Some(state_action_to_proto(state, action)),
+                    path: Some(path.into()),
                 })),
             },
 
@@ -307,18 +517,67 @@ impl From<SystemRecipeFailure> for ModelFailure {
                 system: sys.name,
                 failure: Some(Failure::InconsistentConjunction(cf.into())),
             },
+            SystemRecipeFailure::Invalid(reason, sys) => ModelFailure {
+                system: sys.name,
+                failure: Some(Failure::Invalid(reason)),
+            },
         }
     }
 }
 
 impl From<SpecificPath> for ReachabilityPath {
     fn from(path: SpecificPath) -> Self {
-        use services::Path as ProtoPath;
+        Self {
+            path: Some(path.into()),
+        }
+    }
+}
+
+impl From<SpecificPath> for services::Path {
+    fn from(path: SpecificPath) -> Self {
+        Self {
+            decisions: path.path.into_iter().map(|d| d.into()).collect(),
+        }
+    }
+}
+
+impl From<TemporalFailure> for ProtobufTemporalFailure {
+    fn from(tf: TemporalFailure) -> Self {
+        match tf {
+            TemporalFailure::NoWitness(operator) => Self {
+                operator: operator.to_string(),
+                counterexample: None,
+            },
+            TemporalFailure::Counterexample {
+                operator,
+                counterexample,
+            } => Self {
+                operator: operator.to_string(),
+                counterexample: Some(counterexample.into()),
+            },
+            TemporalFailure::NotLeadsTo { counterexample } => Self {
+                operator: "-->".to_string(),
+                counterexample: Some(counterexample.into()),
+            },
+        }
+    }
+}
+
+impl From<LimitReached> for Inconclusive {
+    fn from(limit: LimitReached) -> Self {
+        Self {
+            limit: limit.limit.to_string(),
+            states: limit.statistics.states as u64,
+            approximate_bytes: limit.statistics.approximate_bytes as u64,
+        }
+    }
+}
 
+impl From<SpecificLasso> for services::Lasso {
+    fn from(lasso: SpecificLasso) -> Self {
         Self {
-            path: Some(ProtoPath {
-                decisions: path.path.into_iter().map(|d| d.into()).collect(),
-            }),
+            prefix: Some(lasso.prefix.into()),
+            cycle: Some(lasso.cycle.into()),
         }
     }
 }
@@ -0,0 +1,349 @@
+//! Automatic simulation: Random walks through a system, or walks guided towards a target state.
+//!
+//! The walks take the [Decision]s of the symbolic simulation and collect which locations and edges of each component they visit,
+//! which is useful for smoke-testing systems too large to be checked exhaustively.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+
+use crate::ModelObjects::State;
+use crate::Simulation::decision::Decision;
+use crate::System::cancellation::{self, Interrupted};
+use crate::System::reachability::reached_end_state;
+use crate::System::specifics::{SpecificDecision, SpecificLocation, SpecificState};
+use crate::TransitionSystems::{
+    CompositionType, LocationID, TransitionID, TransitionSystem, TransitionSystemPtr,
+};
+
+/// How many walks to take, how long they may be and how the actions are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomaticSimulationOptions {
+    pub runs: usize,
+    pub max_steps: usize,
+    pub seed: u64,
+    /// The relative weights of choosing each action, where actions without a weight have weight 1
+    pub action_weights: BTreeMap<String, f64>,
+}
+
+impl Default for AutomaticSimulationOptions {
+    fn default() -> Self {
+        AutomaticSimulationOptions {
+            runs: 1,
+            max_steps: 100,
+            seed: 0,
+            action_weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl AutomaticSimulationOptions {
+    fn weight(&self, action: &str) -> f64 {
+        self.action_weights.get(action).copied().unwrap_or(1.0)
+    }
+}
+
+impl fmt::Display for AutomaticSimulationOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{runs={}, steps={}, seed={}",
+            self.runs, self.max_steps, self.seed
+        )?;
+        for (action, weight) in &self.action_weights {
+            write!(f, ", weight({})={}", action, weight)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// How a single walk ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationRun {
+    /// The number of decisions taken
+    pub steps: usize,
+    /// Whether the walk reached the target state, which is always false without a target
+    pub reached_target: bool,
+    /// Whether the walk stopped before taking its maximal number of steps because no decision could be taken
+    pub deadlocked: bool,
+    pub final_state: SpecificState,
+}
+
+/// The locations and edges of a component visited by the walks, along with how many the component has
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ComponentCoverage {
+    pub visited_locations: BTreeSet<String>,
+    pub visited_edges: BTreeSet<String>,
+    pub total_locations: usize,
+    pub total_edges: usize,
+}
+
+/// The result of an automatic simulation with the coverage of each component by its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub runs: Vec<SimulationRun>,
+    /// Whether the system has no initial state, in which case no walks are taken
+    pub no_initial_state: bool,
+    pub coverage: BTreeMap<String, ComponentCoverage>,
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.no_initial_state {
+            return write!(f, "The system has no initial state, so no runs were taken");
+        }
+        let reached = self.runs.iter().filter(|run| run.reached_target).count();
+        let deadlocked = self.runs.iter().filter(|run| run.deadlocked).count();
+        write!(
+            f,
+            "{} runs, {} reached the target, {} deadlocked",
+            self.runs.len(),
+            reached,
+            deadlocked
+        )?;
+        for (component, coverage) in &self.coverage {
+            write!(
+                f,
+                "\n  {}: {}/{} locations, {}/{} edges",
+                component,
+                coverage.visited_locations.len(),
+                coverage.total_locations,
+                coverage.visited_edges.len(),
+                coverage.total_edges
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Takes `options.runs` walks of at most `options.max_steps` decisions through `system`.
+/// Without a `target` the decisions are chosen at random according to the action weights,
+/// otherwise the decisions leading closest to the `target` are preferred and a walk stops once it reaches the target.
+/// No walks are taken if the system has no initial state.
+/// Returns an error if the query is interrupted, see [cancellation].
+pub fn simulate(
+    system: &TransitionSystemPtr,
+    options: &AutomaticSimulationOptions,
+    target: Option<&State>,
+) -> Result<SimulationReport, Interrupted> {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut coverage = BTreeMap::new();
+    count_totals(system.as_ref(), &mut coverage);
+
+    let initial_state = match system.get_initial_state() {
+        Some(state) => state,
+        None => {
+            return Ok(SimulationReport {
+                runs: vec![],
+                no_initial_state: true,
+                coverage,
+            })
+        }
+    };
+    let runs = (0..options.runs)
+        .map(|_| {
+            walk(
+                system,
+                initial_state.clone(),
+                options,
+                target,
+                &mut rng,
+                &mut coverage,
+            )
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(SimulationReport {
+        runs,
+        no_initial_state: false,
+        coverage,
+    })
+}
+
+fn walk(
+    system: &TransitionSystemPtr,
+    mut state: State,
+    options: &AutomaticSimulationOptions,
+    target: Option<&State>,
+    rng: &mut StdRng,
+    coverage: &mut BTreeMap<String, ComponentCoverage>,
+) -> Result<SimulationRun, Interrupted> {
+    let mut visits: HashMap<LocationID, usize> = HashMap::new();
+    let mut steps = 0;
+    let mut deadlocked = false;
+
+    cover_state(&state, system.as_ref(), coverage);
+    *visits
+        .entry(state.decorated_locations.id.clone())
+        .or_default() += 1;
+
+    while target.map_or(true, |target| !reached_end_state(&state, target)) {
+        cancellation::checkpoint()?;
+        if steps == options.max_steps {
+            break;
+        }
+        let mut decisions = Decision::get_decisions_from_state(state.clone(), system);
+        // The actions of a system are not ordered, so the decisions are sorted to make the walks depend on the seed only
+        decisions.sort_by(|a, b| a.action.cmp(&b.action));
+        if let Some(target) = target {
+            decisions = closest_decisions(decisions, target, &visits);
+        }
+
+        let decision = match choose(decisions, options, rng) {
+            Some(decision) => decision,
+            None => {
+                deadlocked = true;
+                break;
+            }
+        };
+
+        cover_decision(&decision, system.as_ref(), coverage);
+        state = decision.next_state;
+        cover_state(&state, system.as_ref(), coverage);
+        *visits
+            .entry(state.decorated_locations.id.clone())
+            .or_default() += 1;
+        steps += 1;
+    }
+
+    Ok(SimulationRun {
+        steps,
+        reached_target: target.map_or(false, |target| reached_end_state(&state, target)),
+        deadlocked,
+        final_state: SpecificState::from_state(&state, system.as_ref()),
+    })
+}
+
+/// Chooses one of the `decisions` at random according to the weights of their actions
+fn choose(
+    mut decisions: Vec<Decision>,
+    options: &AutomaticSimulationOptions,
+    rng: &mut StdRng,
+) -> Option<Decision> {
+    let weights: Vec<f64> = decisions
+        .iter()
+        .map(|decision| options.weight(&decision.action))
+        .collect();
+    // Fails if there are no decisions or every decision has weight 0
+    let distribution = WeightedIndex::new(&weights).ok()?;
+    Some(decisions.swap_remove(distribution.sample(rng)))
+}
+
+/// Keeps the decisions leading to the locations closest to the `target`, preferring the locations visited the fewest times to avoid walking in circles
+fn closest_decisions(
+    decisions: Vec<Decision>,
+    target: &State,
+    visits: &HashMap<LocationID, usize>,
+) -> Vec<Decision> {
+    let score = |decision: &Decision| {
+        let location = &decision.next_state.decorated_locations.id;
+        (
+            !reached_end_state(&decision.next_state, target),
+            location_distance(location, &target.decorated_locations.id),
+            visits.get(location).copied().unwrap_or_default(),
+        )
+    };
+    let best = match decisions.iter().map(score).min() {
+        Some(best) => best,
+        None => return decisions,
+    };
+    decisions
+        .into_iter()
+        .filter(|decision| score(decision) == best)
+        .collect()
+}
+
+/// The number of component locations in `location` which differ from the `target` location
+fn location_distance(location: &LocationID, target: &LocationID) -> usize {
+    match (location, target) {
+        (LocationID::Composition(l1, r1), LocationID::Composition(l2, r2))
+        | (LocationID::Conjunction(l1, r1), LocationID::Conjunction(l2, r2))
+        | (LocationID::Quotient(l1, r1), LocationID::Quotient(l2, r2)) => {
+            location_distance(l1, l2) + location_distance(r1, r2)
+        }
+        (_, LocationID::AnyLocation) => 0,
+        (LocationID::Valued(location, _), LocationID::AnyValuation(target)) => {
+            usize::from(location != target)
+        }
+        (location, target) => usize::from(location != target),
+    }
+}
+
+/// Marks the edges taken by `decision` as visited
+pub(crate) fn cover_decision(
+    decision: &Decision,
+    system: &dyn TransitionSystem,
+    coverage: &mut BTreeMap<String, ComponentCoverage>,
+) {
+    for edge in SpecificDecision::from_decision(decision, system).edges {
+        coverage
+            .entry(edge.comp.name)
+            .or_default()
+            .visited_edges
+            .insert(edge.edge_id);
+    }
+}
+
+/// Marks the component locations of `state` as visited
+pub(crate) fn cover_state(
+    state: &State,
+    system: &dyn TransitionSystem,
+    coverage: &mut BTreeMap<String, ComponentCoverage>,
+) {
+    fn cover_location(
+        location: SpecificLocation,
+        coverage: &mut BTreeMap<String, ComponentCoverage>,
+    ) {
+        match location {
+            SpecificLocation::ComponentLocation { comp, location_id } => {
+                coverage
+                    .entry(comp.name)
+                    .or_default()
+                    .visited_locations
+                    .insert(location_id);
+            }
+            SpecificLocation::BranchLocation(left, right, _) => {
+                cover_location(*left, coverage);
+                cover_location(*right, coverage);
+            }
+            SpecificLocation::SpecialLocation(_) => {}
+        }
+    }
+
+    let state = SpecificState::from_state(state, system);
+    cover_location(state.locations, coverage);
+}
+
+/// Counts the locations and edges of every component in `system`
+pub(crate) fn count_totals(
+    system: &dyn TransitionSystem,
+    coverage: &mut BTreeMap<String, ComponentCoverage>,
+) {
+    if system.get_composition_type() != CompositionType::Simple {
+        let (left, right) = system.get_children();
+        count_totals(left.as_ref(), coverage);
+        count_totals(right.as_ref(), coverage);
+        return;
+    }
+
+    let locations = system.get_all_locations();
+    let mut edges = BTreeSet::new();
+    for location in &locations {
+        for action in system.get_actions() {
+            for transition in system.next_transitions(location, &action) {
+                if let TransitionID::Simple(edge_id) = transition.id {
+                    edges.insert(edge_id);
+                }
+            }
+        }
+    }
+
+    let component = coverage
+        .entry(system.comp_infos().info().name.clone())
+        .or_default();
+    component.total_locations = locations.len();
+    component.total_edges = edges.len();
+}
@@ -0,0 +1,667 @@
+//! Simulation with concrete clock valuations instead of zones.
+//!
+//! A [ConcreteState] is a location together with the exact value of every clock.
+//! Delays are rational numbers, so every point of a zone can be reached, not only the integer ones.
+//! The [Decision]s of the symbolic simulation tell which transitions can be taken from a location,
+//! and the [DelayInterval]s of a [ConcreteDecision] tell how long to delay from the current valuation before taking them.
+use std::fmt;
+use std::str::FromStr;
+
+use edbm::util::constraints::{ClockIndex, Conjunction, Constraint};
+use edbm::zones::OwnedFederation;
+use num_rational::Ratio;
+
+use crate::ModelObjects::{State, Transition};
+use crate::Simulation::decision::Decision;
+use crate::System::specifics::{
+    specific_clock_comp_map, specific_location, SpecificClockVar, SpecificLocation,
+};
+use crate::TransitionSystems::{LocationTree, TransitionSystem, TransitionSystemPtr};
+
+/// An exact rational number of time units
+pub type Rational = Ratio<i64>;
+
+/// Parses a delay written as an integer (`2`), a decimal number (`2.5`) or a fraction (`5/2`)
+pub fn parse_delay(delay: &str) -> Result<Rational, String> {
+    let invalid = || format!("Invalid delay '{}'", delay);
+    if delay.starts_with('-') {
+        return Err(format!("Delays cannot be negative, got {}", delay));
+    }
+    match delay.split_once('.') {
+        Some((integer, fraction)) => {
+            if fraction.is_empty() || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let denominator = 10i64
+                .checked_pow(fraction.len() as u32)
+                .ok_or_else(invalid)?;
+            let integer: i64 = integer.parse().map_err(|_| invalid())?;
+            let fraction: i64 = fraction.parse().map_err(|_| invalid())?;
+            Ok(Rational::from_integer(integer) + Rational::new(fraction, denominator))
+        }
+        None => Rational::from_str(delay).map_err(|_| invalid()),
+    }
+}
+
+/// The value of every clock in a system, where the clock with index 0 is the reference clock which is always 0
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClockValuation {
+    values: Vec<Rational>,
+}
+
+impl ClockValuation {
+    /// The valuation where every clock is 0
+    pub fn zero(dim: ClockIndex) -> Self {
+        ClockValuation {
+            values: vec![Rational::from_integer(0); dim],
+        }
+    }
+
+    pub fn get(&self, clock: ClockIndex) -> Rational {
+        self.values[clock]
+    }
+
+    pub fn dim(&self) -> ClockIndex {
+        self.values.len()
+    }
+
+    /// Returns the valuation after delaying `delay` time units
+    pub fn delayed(&self, delay: Rational) -> Self {
+        ClockValuation {
+            values: self
+                .values
+                .iter()
+                .enumerate()
+                .map(|(clock, value)| match clock {
+                    0 => *value,
+                    _ => value + delay,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn reset(&mut self, clock: ClockIndex, value: i32) {
+        self.values[clock] = Rational::from_integer(value as i64);
+    }
+
+    fn satisfies(&self, constraint: &Constraint) -> bool {
+        let difference = self.values[constraint.i] - self.values[constraint.j];
+        let bound = Rational::from_integer(constraint.ineq().bound() as i64);
+        match constraint.ineq().is_strict() {
+            true => difference < bound,
+            false => difference <= bound,
+        }
+    }
+
+    fn satisfies_conjunction(&self, conjunction: &Conjunction) -> bool {
+        conjunction.constraints.iter().all(|c| self.satisfies(c))
+    }
+
+    /// Returns whether the valuation is a point in `zone`
+    pub fn is_in(&self, zone: &OwnedFederation) -> bool {
+        zone.minimal_constraints()
+            .conjunctions
+            .iter()
+            .any(|conjunction| self.satisfies_conjunction(conjunction))
+    }
+
+    /// Returns the delays after which the valuation is in `zone`.
+    /// There is an interval for each zone of the federation, so the intervals may overlap.
+    pub fn delays_into(&self, zone: &OwnedFederation) -> Vec<DelayInterval> {
+        zone.minimal_constraints()
+            .conjunctions
+            .iter()
+            .filter_map(|conjunction| self.delays_into_conjunction(conjunction))
+            .collect()
+    }
+
+    /// Returns the delays after which the valuation satisfies the `conjunction`, which form an interval as the conjunction is convex
+    fn delays_into_conjunction(&self, conjunction: &Conjunction) -> Option<DelayInterval> {
+        let mut interval = DelayInterval::any();
+        for constraint in &conjunction.constraints {
+            let bound = Rational::from_integer(constraint.ineq().bound() as i64);
+            let strict = constraint.ineq().is_strict();
+            match (constraint.i, constraint.j) {
+                (0, 0) => {}
+                // x_i + d - 0 <= c, so d <= c - x_i
+                (i, 0) => interval.restrict_upper(DelayBound {
+                    value: bound - self.values[i],
+                    strict,
+                }),
+                // 0 - (x_j + d) <= c, so d >= -c - x_j
+                (0, j) => interval.restrict_lower(DelayBound {
+                    value: -bound - self.values[j],
+                    strict,
+                }),
+                // Differences between clocks do not change when delaying
+                _ => {
+                    if !self.satisfies(constraint) {
+                        return None;
+                    }
+                }
+            }
+        }
+        (!interval.is_empty()).then_some(interval)
+    }
+}
+
+/// A bound of a [DelayInterval]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DelayBound {
+    pub value: Rational,
+    pub strict: bool,
+}
+
+/// The delays between `lower` and `upper`, where no `upper` bound means the interval is unbounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DelayInterval {
+    pub lower: DelayBound,
+    pub upper: Option<DelayBound>,
+}
+
+impl DelayInterval {
+    /// Every delay, which is every non-negative number
+    pub fn any() -> Self {
+        DelayInterval {
+            lower: DelayBound {
+                value: Rational::from_integer(0),
+                strict: false,
+            },
+            upper: None,
+        }
+    }
+
+    fn restrict_lower(&mut self, bound: DelayBound) {
+        if bound.value > self.lower.value || (bound.value == self.lower.value && bound.strict) {
+            self.lower = bound;
+        }
+    }
+
+    fn restrict_upper(&mut self, bound: DelayBound) {
+        match self.upper {
+            Some(upper)
+                if upper.value < bound.value || (upper.value == bound.value && upper.strict) => {}
+            _ => self.upper = Some(bound),
+        }
+    }
+
+    pub fn intersection(&self, other: &DelayInterval) -> Option<DelayInterval> {
+        let mut interval = *self;
+        interval.restrict_lower(other.lower);
+        if let Some(upper) = other.upper {
+            interval.restrict_upper(upper);
+        }
+        (!interval.is_empty()).then_some(interval)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self.upper {
+            Some(upper) => {
+                upper.value < self.lower.value
+                    || (upper.value == self.lower.value && (upper.strict || self.lower.strict))
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, delay: Rational) -> bool {
+        let above_lower =
+            delay > self.lower.value || (delay == self.lower.value && !self.lower.strict);
+        let below_upper = match self.upper {
+            Some(upper) => delay < upper.value || (delay == upper.value && !upper.strict),
+            None => true,
+        };
+        above_lower && below_upper
+    }
+
+    /// Picks a delay in the interval: The smallest one if it is included, otherwise one strictly between the bounds
+    pub fn pick(&self) -> Rational {
+        if !self.lower.strict {
+            return self.lower.value;
+        }
+        match self.upper {
+            Some(upper) => (self.lower.value + upper.value) / Rational::from_integer(2),
+            None => self.lower.value + Rational::from_integer(1),
+        }
+    }
+}
+
+impl fmt::Display for DelayInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let open = if self.lower.strict { "(" } else { "[" };
+        match self.upper {
+            Some(upper) => {
+                let close = if upper.strict { ")" } else { "]" };
+                write!(f, "{}{}, {}{}", open, self.lower.value, upper.value, close)
+            }
+            None => write!(f, "{}{}, inf)", open, self.lower.value),
+        }
+    }
+}
+
+/// The reasons a concrete simulation step cannot be taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConcreteSimulationError {
+    NoInitialState,
+    /// The invariant of the location does not allow to delay `delay` time units
+    InvalidDelay {
+        delay: Rational,
+        allowed: Option<DelayInterval>,
+    },
+    /// No transition with `action` is enabled after delaying `delay` time units
+    NotEnabled {
+        action: String,
+        delay: Rational,
+        allowed: Vec<DelayInterval>,
+    },
+    /// Several transitions with `action` are enabled after delaying `delay` time units
+    Ambiguous {
+        action: String,
+        delay: Rational,
+    },
+    UnknownDecision(usize),
+}
+
+impl fmt::Display for ConcreteSimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcreteSimulationError::NoInitialState => {
+                write!(f, "The system has no initial state")
+            }
+            ConcreteSimulationError::InvalidDelay { delay, allowed } => match allowed {
+                Some(allowed) => write!(
+                    f,
+                    "Cannot delay {}, the invariant allows delays in {}",
+                    delay, allowed
+                ),
+                None => write!(f, "Cannot delay {}, the invariant is violated", delay),
+            },
+            ConcreteSimulationError::NotEnabled {
+                action,
+                delay,
+                allowed,
+            } => {
+                write!(
+                    f,
+                    "Action {} is not enabled after delaying {}",
+                    action, delay
+                )?;
+                if !allowed.is_empty() {
+                    let allowed: Vec<String> = allowed.iter().map(|i| i.to_string()).collect();
+                    write!(
+                        f,
+                        ", it is enabled after delays in {}",
+                        allowed.join(" and ")
+                    )?;
+                }
+                Ok(())
+            }
+            ConcreteSimulationError::Ambiguous { action, delay } => write!(
+                f,
+                "Several transitions with action {} are enabled after delaying {}",
+                action, delay
+            ),
+            ConcreteSimulationError::UnknownDecision(index) => {
+                write!(f, "There is no decision {} in the current state", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConcreteSimulationError {}
+
+/// A location of a system along with the exact values of its clocks
+#[derive(Debug, Clone)]
+pub struct ConcreteState {
+    pub locations: LocationTree,
+    pub valuation: ClockValuation,
+}
+
+impl ConcreteState {
+    /// The initial location of `system` with every clock at 0
+    pub fn initial(system: &TransitionSystemPtr) -> Result<Self, ConcreteSimulationError> {
+        let locations = system
+            .get_initial_location()
+            .ok_or(ConcreteSimulationError::NoInitialState)?;
+        let state = ConcreteState {
+            locations,
+            valuation: ClockValuation::zero(system.get_dim()),
+        };
+        match state.satisfies_invariant() {
+            true => Ok(state),
+            false => Err(ConcreteSimulationError::NoInitialState),
+        }
+    }
+
+    fn satisfies_invariant(&self) -> bool {
+        match self.locations.get_invariants() {
+            Some(invariant) => self.valuation.is_in(invariant),
+            None => true,
+        }
+    }
+
+    /// The delays allowed by the invariant of the location, which all start at 0
+    pub fn allowed_delays(&self) -> Option<DelayInterval> {
+        let invariant = match self.locations.get_invariants() {
+            Some(invariant) => invariant,
+            None => return Some(DelayInterval::any()),
+        };
+        // As each zone of the invariant is convex, the valuation stays inside a zone until its upper bound if the zone contains it now
+        self.valuation
+            .delays_into(invariant)
+            .into_iter()
+            .filter(|interval| interval.contains(Rational::from_integer(0)))
+            .reduce(|longest, interval| match (longest.upper, interval.upper) {
+                (None, _) => longest,
+                (_, None) => interval,
+                (Some(a), Some(b)) if b.value > a.value || (b.value == a.value && !b.strict) => {
+                    interval
+                }
+                _ => longest,
+            })
+    }
+
+    /// Returns the state after delaying `delay` time units, if the invariant of the location allows it
+    pub fn delay(&self, delay: Rational) -> Result<ConcreteState, ConcreteSimulationError> {
+        let allowed = self.allowed_delays();
+        match allowed {
+            Some(interval) if interval.contains(delay) => Ok(ConcreteState {
+                locations: self.locations.clone(),
+                valuation: self.valuation.delayed(delay),
+            }),
+            _ => Err(ConcreteSimulationError::InvalidDelay { delay, allowed }),
+        }
+    }
+
+    /// Returns the state after taking `transition`, or `None` if its guard or the invariant of its target is not satisfied
+    pub fn take_transition(&self, transition: &Transition) -> Option<ConcreteState> {
+        if !self.valuation.is_in(&transition.guard_zone) {
+            return None;
+        }
+        let mut valuation = self.valuation.clone();
+        for update in &transition.updates {
+            valuation.reset(update.clock_index, update.value);
+        }
+        let state = ConcreteState {
+            locations: transition.target_locations.clone(),
+            valuation,
+        };
+        state.satisfies_invariant().then_some(state)
+    }
+
+    /// The symbolic state of the location containing every valuation allowed by its invariant
+    fn symbolic_state(&self) -> State {
+        let mut state = State::new(
+            self.locations.clone(),
+            OwnedFederation::universe(self.valuation.dim()),
+        );
+        state.apply_invariants();
+        state
+    }
+}
+
+/// A [Decision] which can be taken from a [ConcreteState] after any of the `delays`
+#[derive(Debug, Clone)]
+pub struct ConcreteDecision {
+    pub decision: Decision,
+    pub delays: Vec<DelayInterval>,
+}
+
+impl ConcreteDecision {
+    /// Returns the decisions which can be taken from `state` after some delay, ordered by their action
+    pub fn from_state(state: &ConcreteState, system: &TransitionSystemPtr) -> Vec<Self> {
+        let allowed = match state.allowed_delays() {
+            Some(allowed) => allowed,
+            None => return vec![],
+        };
+        let mut decisions = Decision::get_decisions_from_state(state.symbolic_state(), system);
+        // The actions of a system are not ordered, but the decisions are chosen by their index
+        decisions.sort_by(|a, b| a.action.cmp(&b.action));
+        decisions
+            .into_iter()
+            .filter_map(|decision| {
+                let delays: Vec<DelayInterval> = state
+                    .valuation
+                    .delays_into(decision.state.zone_ref())
+                    .iter()
+                    .filter_map(|interval| interval.intersection(&allowed))
+                    .collect();
+                (!delays.is_empty()).then_some(ConcreteDecision { decision, delays })
+            })
+            .collect()
+    }
+
+    pub fn allows(&self, delay: Rational) -> bool {
+        self.delays.iter().any(|interval| interval.contains(delay))
+    }
+
+    /// Picks a delay after which the decision can be taken
+    pub fn pick_delay(&self) -> Rational {
+        self.delays
+            .iter()
+            .map(DelayInterval::pick)
+            .min()
+            .expect("A concrete decision has at least one delay")
+    }
+}
+
+/// A step of a concrete simulation: Delaying `delay` time units and then taking `transition` with `action` to reach `state`
+#[derive(Debug, Clone)]
+pub struct TimedStep {
+    pub delay: Rational,
+    pub action: String,
+    pub transition: Transition,
+    pub state: ConcreteState,
+}
+
+/// A simulation of a transition system with concrete clock valuations, which keeps the timed trace of the steps taken
+pub struct ConcreteSimulator {
+    system: TransitionSystemPtr,
+    initial: ConcreteState,
+    steps: Vec<TimedStep>,
+}
+
+impl ConcreteSimulator {
+    /// Starts a simulation in the initial location of `system` with every clock at 0
+    pub fn new(system: TransitionSystemPtr) -> Result<Self, ConcreteSimulationError> {
+        let initial = ConcreteState::initial(&system)?;
+        Ok(ConcreteSimulator {
+            system,
+            initial,
+            steps: vec![],
+        })
+    }
+
+    pub fn system(&self) -> &TransitionSystemPtr {
+        &self.system
+    }
+
+    pub fn current(&self) -> &ConcreteState {
+        self.steps
+            .last()
+            .map(|step| &step.state)
+            .unwrap_or(&self.initial)
+    }
+
+    pub fn steps(&self) -> &[TimedStep] {
+        &self.steps
+    }
+
+    /// The decisions which can be taken from the current state after some delay
+    pub fn decisions(&self) -> Vec<ConcreteDecision> {
+        ConcreteDecision::from_state(self.current(), &self.system)
+    }
+
+    /// Takes the decision at `index` in [ConcreteSimulator::decisions] after delaying `delay` time units,
+    /// or after a delay picked from the allowed ones if no `delay` is given.
+    pub fn take_decision(
+        &mut self,
+        index: usize,
+        delay: Option<Rational>,
+    ) -> Result<&TimedStep, ConcreteSimulationError> {
+        let decision = self
+            .decisions()
+            .into_iter()
+            .nth(index)
+            .ok_or(ConcreteSimulationError::UnknownDecision(index))?;
+        let delay = delay.unwrap_or_else(|| decision.pick_delay());
+        if !decision.allows(delay) {
+            return Err(ConcreteSimulationError::NotEnabled {
+                action: decision.decision.action,
+                delay,
+                allowed: decision.delays,
+            });
+        }
+        let transition = decision
+            .decision
+            .transition
+            .expect("Decisions from a state have a transition");
+        self.push_step(delay, decision.decision.action, transition)
+    }
+
+    /// Delays `delay` time units and then takes the transition with `action` enabled in the resulting valuation
+    pub fn step(
+        &mut self,
+        delay: Rational,
+        action: &str,
+    ) -> Result<&TimedStep, ConcreteSimulationError> {
+        let delayed = self.current().delay(delay)?;
+
+        let mut enabled: Vec<Transition> = self
+            .system
+            .next_transitions(&delayed.locations, action)
+            .into_iter()
+            .filter(|transition| delayed.take_transition(transition).is_some())
+            .collect();
+
+        match enabled.len() {
+            0 => Err(ConcreteSimulationError::NotEnabled {
+                action: action.to_string(),
+                delay,
+                allowed: self
+                    .decisions()
+                    .into_iter()
+                    .filter(|decision| decision.decision.action == action)
+                    .flat_map(|decision| decision.delays)
+                    .collect(),
+            }),
+            1 => self.push_step(delay, action.to_string(), enabled.remove(0)),
+            _ => Err(ConcreteSimulationError::Ambiguous {
+                action: action.to_string(),
+                delay,
+            }),
+        }
+    }
+
+    fn push_step(
+        &mut self,
+        delay: Rational,
+        action: String,
+        transition: Transition,
+    ) -> Result<&TimedStep, ConcreteSimulationError> {
+        let delayed = self.current().delay(delay)?;
+        let state = delayed.take_transition(&transition).ok_or_else(|| {
+            ConcreteSimulationError::NotEnabled {
+                action: action.clone(),
+                delay,
+                allowed: vec![],
+            }
+        })?;
+        self.steps.push(TimedStep {
+            delay,
+            action,
+            transition,
+            state,
+        });
+        Ok(self.steps.last().unwrap())
+    }
+
+    /// Removes the last step, returning false if there is none
+    pub fn undo(&mut self) -> bool {
+        self.steps.pop().is_some()
+    }
+
+    /// The trace of the simulation with the names of the locations and clocks of the system
+    pub fn timed_trace(&self) -> SpecificTimedTrace {
+        let system = &*self.system;
+        SpecificTimedTrace {
+            initial: SpecificConcreteState::from_state(&self.initial, system),
+            steps: self
+                .steps
+                .iter()
+                .map(|step| SpecificTimedStep {
+                    delay: step.delay,
+                    action: step.action.clone(),
+                    state: SpecificConcreteState::from_state(&step.state, system),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Intermediate representation of a [ConcreteState] with the names of its locations and clocks
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificConcreteState {
+    pub locations: SpecificLocation,
+    pub clocks: Vec<(SpecificClockVar, Rational)>,
+}
+
+impl SpecificConcreteState {
+    pub fn from_state(state: &ConcreteState, system: &dyn TransitionSystem) -> Self {
+        let clock_map = specific_clock_comp_map(system);
+        let clocks = (1..state.valuation.dim())
+            .map(|clock| {
+                let var = match clock_map.get(&clock) {
+                    Some(clock) => SpecificClockVar::ComponentClock(clock.clone()),
+                    None => SpecificClockVar::SystemClock(clock),
+                };
+                (var, state.valuation.get(clock))
+            })
+            .collect();
+        SpecificConcreteState {
+            locations: specific_location(&state.locations.id, system),
+            clocks,
+        }
+    }
+}
+
+impl fmt::Display for SpecificConcreteState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({})", self.locations)?;
+        for (clock, value) in &self.clocks {
+            if *clock != SpecificClockVar::Zero {
+                write!(f, " {}={}", clock, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Intermediate representation of a [TimedStep]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificTimedStep {
+    pub delay: Rational,
+    pub action: String,
+    pub state: SpecificConcreteState,
+}
+
+/// Intermediate representation of the timed trace of a [ConcreteSimulator]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificTimedTrace {
+    pub initial: SpecificConcreteState,
+    pub steps: Vec<SpecificTimedStep>,
+}
+
+impl fmt::Display for SpecificTimedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.initial)?;
+        for step in &self.steps {
+            write!(
+                f,
+                "\n  delay {}, {} -> {}",
+                step.delay, step.action, step.state
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -59,6 +59,21 @@ impl Decision {
         }
     }
 
+    /// Records that `transition` was taken for `action` from `state`, leading to `next_state`
+    pub fn taking(
+        state: &State,
+        action: impl Into<String>,
+        transition: &Transition,
+        next_state: &State,
+    ) -> Decision {
+        Decision {
+            state: state.clone(),
+            action: action.into(),
+            transition: Some(transition.to_owned()),
+            next_state: next_state.clone(),
+        }
+    }
+
     /// Get all possible [`Decision`]s from a [`State`]
     pub fn get_decisions_from_state(state: State, system: &TransitionSystemPtr) -> Vec<Decision> {
         let mut next_decisions = vec![];
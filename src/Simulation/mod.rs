@@ -1,2 +1,9 @@
+pub mod automatic;
+pub mod concrete;
 pub mod decision;
 pub mod graph_layout;
+pub mod repl;
+pub mod session;
+pub mod test_generation;
+pub mod test_run;
+pub mod trace_check;
@@ -0,0 +1,160 @@
+//! An interactive prompt for simulating a system from the command line.
+//!
+//! The prompt shows the current [SpecificState] and the [SpecificDecision]s which can be taken from it,
+//! and reads commands to take a decision, delay, go back or print the zone of the current state.
+use std::error::Error;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::Simulation::session::SimulationSession;
+use crate::System::specifics::{SpecificDecision, SpecificState};
+use crate::TransitionSystems::TransitionSystemPtr;
+
+const HELP: &str = "Commands:
+  <n>, take <n>  take decision n
+  delay <d>      let at least d time units pass
+  back           go back to the previous step
+  zone           print the zone of the current state
+  help           print this message
+  quit           stop the simulation";
+
+/// A command given to the prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplCommand {
+    Take(usize),
+    Delay(u32),
+    Back,
+    Zone,
+    Help,
+    Quit,
+}
+
+impl FromStr for ReplCommand {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            [index] if index.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(ReplCommand::Take(number(index)?))
+            }
+            ["take", index] => Ok(ReplCommand::Take(number(index)?)),
+            ["delay", delay] => Ok(ReplCommand::Delay(number(delay)?)),
+            ["back"] => Ok(ReplCommand::Back),
+            ["zone"] => Ok(ReplCommand::Zone),
+            ["help"] => Ok(ReplCommand::Help),
+            ["quit"] | ["exit"] => Ok(ReplCommand::Quit),
+            _ => Err(format!(
+                "Unknown command '{}', type 'help' for the commands",
+                line.trim()
+            )),
+        }
+    }
+}
+
+fn number<T: FromStr>(word: &str) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("Expected a non-negative integer, got '{}'", word))
+}
+
+/// A [SpecificDecision] as listed by the prompt, fx. `coin [Machine.E3] -> (Machine.L4)`
+struct ListedDecision(SpecificDecision);
+
+impl fmt::Display for ListedDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edges: Vec<String> = self
+            .0
+            .edges
+            .iter()
+            .map(|edge| format!("{}.{}", edge.comp.name, edge.edge_id))
+            .collect();
+        write!(
+            f,
+            "{} [{}] -> {}",
+            self.0.action,
+            edges.join(", "),
+            self.0.destination_state
+        )
+    }
+}
+
+/// Runs the prompt on `system`, reading commands from `input` until it ends or the user quits
+pub fn run_repl(
+    system: TransitionSystemPtr,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut session = SimulationSession::new(system)?;
+    print_step(&session, &mut output)?;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            prompt(&mut output)?;
+            continue;
+        }
+
+        let result = match line.parse::<ReplCommand>() {
+            Ok(ReplCommand::Take(index)) => session.take_decision(index).map(|_| true),
+            Ok(ReplCommand::Delay(delay)) => session.delay(delay).map(|_| true),
+            Ok(ReplCommand::Back) => session.undo().map(|_| true),
+            Ok(ReplCommand::Zone) => {
+                let state = current_state(&session);
+                writeln!(output, "Zone: {}", state.constraints)?;
+                Ok(false)
+            }
+            Ok(ReplCommand::Help) => {
+                writeln!(output, "{}", HELP)?;
+                Ok(false)
+            }
+            Ok(ReplCommand::Quit) => return Ok(()),
+            Err(error) => {
+                writeln!(output, "{}", error)?;
+                Ok(false)
+            }
+        };
+
+        match result {
+            Ok(true) => print_step(&session, &mut output)?,
+            Ok(false) => prompt(&mut output)?,
+            Err(error) => {
+                writeln!(output, "{}", error)?;
+                prompt(&mut output)?;
+            }
+        }
+    }
+    writeln!(output)?;
+    Ok(())
+}
+
+fn current_state(session: &SimulationSession) -> SpecificState {
+    SpecificState::from_state(&session.current().state, session.system().as_ref())
+}
+
+/// Prints the current state and the decisions which can be taken from it, followed by the prompt
+fn print_step(session: &SimulationSession, output: &mut impl Write) -> std::io::Result<()> {
+    writeln!(
+        output,
+        "Step {}: {}",
+        session.position(),
+        current_state(session)
+    )?;
+
+    let decisions = &session.current().decisions;
+    if decisions.is_empty() {
+        writeln!(output, "No decisions can be taken")?;
+    } else {
+        writeln!(output, "Decisions:")?;
+        for (index, decision) in decisions.iter().enumerate() {
+            let decision = SpecificDecision::from_decision(decision, session.system().as_ref());
+            writeln!(output, "  {}: {}", index, ListedDecision(decision))?;
+        }
+    }
+    prompt(output)
+}
+
+fn prompt(output: &mut impl Write) -> std::io::Result<()> {
+    write!(output, "> ")?;
+    output.flush()
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+
+use edbm::util::constraints::{
+    ClockIndex, Conjunction, Constraint, Disjunction, Inequality, RawInequality,
+};
+use edbm::zones::OwnedFederation;
+
+use crate::ModelObjects::State;
+use crate::Simulation::decision::Decision;
+use crate::TransitionSystems::TransitionSystemPtr;
+
+/// A step of a [`SimulationSession`]: The `state` reached by taking the `decision` and the [`Decision`]s possible from it.
+#[derive(Debug, Clone)]
+pub struct SimulationStep {
+    /// The decision taken to reach this step, which is `None` for the initial step and for delays
+    pub decision: Option<Decision>,
+    pub state: State,
+    pub decisions: Vec<Decision>,
+}
+
+impl SimulationStep {
+    fn new(decision: Option<Decision>, state: State, system: &TransitionSystemPtr) -> Self {
+        let mut decisions = Decision::get_decisions_from_state(state.clone(), system);
+        // The actions of a system are not ordered, so the decisions are sorted to give them the same index every time
+        decisions.sort_by(|a, b| a.action.cmp(&b.action));
+        SimulationStep {
+            decision,
+            state,
+            decisions,
+        }
+    }
+}
+
+/// The reason a [`SimulationSession`] could not do what it was asked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+    NoInitialState,
+    UnknownDecision(usize),
+    UnknownStep(usize),
+    NothingToUndo,
+    NothingToRedo,
+    InvalidDelay(u32),
+}
+
+impl fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::NoInitialState => write!(f, "The system has no initial state"),
+            SimulationError::UnknownDecision(index) => {
+                write!(f, "There is no decision {} in the current state", index)
+            }
+            SimulationError::UnknownStep(step) => {
+                write!(f, "There is no step {} in the trace", step)
+            }
+            SimulationError::NothingToUndo => write!(f, "There is no step to undo"),
+            SimulationError::NothingToRedo => write!(f, "There is no step to redo"),
+            SimulationError::InvalidDelay(delay) => {
+                write!(f, "Cannot delay {} in the current state", delay)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+/// A simulation of a transition system, which keeps the compiled system and the trace of the steps taken.
+///
+/// Undoing a step keeps it in the trace so it can be redone, until a new decision is taken from an earlier step.
+pub struct SimulationSession {
+    system: TransitionSystemPtr,
+    trace: Vec<SimulationStep>,
+    position: usize,
+}
+
+impl SimulationSession {
+    /// Starts a simulation in the initial state of `system`
+    pub fn new(system: TransitionSystemPtr) -> Result<Self, SimulationError> {
+        let initial_state = system
+            .get_initial_state()
+            .ok_or(SimulationError::NoInitialState)?;
+        let initial_step = SimulationStep::new(None, initial_state, &system);
+        Ok(SimulationSession {
+            system,
+            trace: vec![initial_step],
+            position: 0,
+        })
+    }
+
+    pub fn system(&self) -> &TransitionSystemPtr {
+        &self.system
+    }
+
+    /// The steps of the trace, including the undone steps which can be redone
+    pub fn trace(&self) -> &[SimulationStep] {
+        &self.trace
+    }
+
+    /// The index of the current step in the trace
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> &SimulationStep {
+        &self.trace[self.position]
+    }
+
+    /// Takes the decision at `index` in the decisions of the current step, discarding the undone steps
+    pub fn take_decision(&mut self, index: usize) -> Result<&SimulationStep, SimulationError> {
+        let decision = self
+            .current()
+            .decisions
+            .get(index)
+            .cloned()
+            .ok_or(SimulationError::UnknownDecision(index))?;
+        Ok(self.push_decision(decision))
+    }
+
+    /// Takes `decision` from the current step, discarding the undone steps
+    pub fn push_decision(&mut self, decision: Decision) -> &SimulationStep {
+        let next_state = decision.next_state.clone();
+        let step = SimulationStep::new(Some(decision), next_state, &self.system);
+
+        self.trace.truncate(self.position + 1);
+        self.trace.push(step);
+        self.position += 1;
+        self.current()
+    }
+
+    /// Lets at least `delay` time units pass in the current step, discarding the undone steps
+    pub fn delay(&mut self, delay: u32) -> Result<&SimulationStep, SimulationError> {
+        let mut state = self.current().state.clone();
+        let dim = self.system.get_dim();
+        let zone = i32::try_from(delay)
+            .ok()
+            .and_then(|delay| delay_zone(state.zone_ref(), delay, dim))
+            .ok_or(SimulationError::InvalidDelay(delay))?;
+        state.set_zone(zone.up());
+        state.apply_invariants();
+        if state.zone_ref().is_empty() {
+            return Err(SimulationError::InvalidDelay(delay));
+        }
+        let step = SimulationStep::new(None, state, &self.system);
+
+        self.trace.truncate(self.position + 1);
+        self.trace.push(step);
+        self.position += 1;
+        Ok(self.current())
+    }
+
+    pub fn undo(&mut self) -> Result<&SimulationStep, SimulationError> {
+        if self.position == 0 {
+            return Err(SimulationError::NothingToUndo);
+        }
+        self.position -= 1;
+        Ok(self.current())
+    }
+
+    pub fn redo(&mut self) -> Result<&SimulationStep, SimulationError> {
+        if self.position + 1 >= self.trace.len() {
+            return Err(SimulationError::NothingToRedo);
+        }
+        self.position += 1;
+        Ok(self.current())
+    }
+
+    /// Jumps to `step` in the trace, keeping the later steps so they can be redone
+    pub fn jump_to(&mut self, step: usize) -> Result<&SimulationStep, SimulationError> {
+        if step >= self.trace.len() {
+            return Err(SimulationError::UnknownStep(step));
+        }
+        self.position = step;
+        Ok(self.current())
+    }
+}
+
+/// Bounds of this size or larger can not be stored in a DBM, which stores twice the bound and reserves its largest value for infinity
+const MAX_BOUND: i32 = i32::MAX >> 2;
+
+/// Returns the valuations of `zone` after delaying exactly `delay` time units,
+/// which raises the bounds on every clock and keeps the differences between clocks.
+/// Returns `None` if a bound becomes too large for a DBM
+fn delay_zone(zone: &OwnedFederation, delay: i32, dim: ClockIndex) -> Option<OwnedFederation> {
+    let conjunctions = zone
+        .minimal_constraints()
+        .conjunctions
+        .into_iter()
+        .map(|conjunction| {
+            let constraints = conjunction
+                .constraints
+                .into_iter()
+                .map(|constraint| {
+                    let bound = match (constraint.i, constraint.j) {
+                        // An upper bound x <= c becomes x <= c + delay
+                        (i, 0) if i != 0 => constraint.ineq().bound().checked_add(delay)?,
+                        // A lower bound -x <= c becomes -x <= c - delay
+                        (0, j) if j != 0 => constraint.ineq().bound().checked_sub(delay)?,
+                        _ => constraint.ineq().bound(),
+                    };
+                    if bound.checked_abs()? >= MAX_BOUND {
+                        return None;
+                    }
+                    let inequality = match constraint.ineq().is_strict() {
+                        true => Inequality::LS(bound),
+                        false => Inequality::LE(bound),
+                    };
+                    Some(Constraint::new(
+                        constraint.i,
+                        constraint.j,
+                        RawInequality::from_inequality(&inequality),
+                    ))
+                })
+                .collect::<Option<_>>()?;
+            Some(Conjunction::new(constraints))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(OwnedFederation::from_disjunction(
+        &Disjunction::new(conjunctions),
+        dim,
+    ))
+}
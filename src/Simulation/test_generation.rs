@@ -0,0 +1,440 @@
+//! Test case generation from specifications according to timed input-output conformance (tioco).
+//!
+//! A test case is a tree telling the tester which inputs to send and which outputs to expect, and when.
+//! The generator tracks every state the specification can be in symbolically, with an extra *test clock* which is reset
+//! whenever the tester sends an input or observes an output, so the expected outputs and timeouts are given in time since
+//! the last action of the test.
+//! The test clock is not used by the specification, so it must be compiled with one clock more than it needs, see
+//! [crate::System::extract_system_rep::create_executable_query].
+use std::collections::BTreeMap;
+use std::fmt;
+
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+use serde::Serialize;
+
+use crate::ModelObjects::State;
+use crate::Simulation::automatic::{count_totals, cover_decision, cover_state, ComponentCoverage};
+use crate::Simulation::decision::Decision;
+use crate::System::cancellation::{self, Interrupted};
+use crate::TransitionSystems::TransitionSystemPtr;
+
+/// How many test cases to generate, how deep they may be and how they are chosen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestGenerationOptions {
+    /// The maximal number of actions and timeouts on a path through a test case
+    pub depth: usize,
+    pub tests: usize,
+    /// How long to wait for an output when the specification allows the implementation to stay quiescent
+    pub wait: u32,
+    /// Whether to prefer the actions covering the most new locations and edges over taking turns
+    pub coverage: bool,
+}
+
+impl Default for TestGenerationOptions {
+    fn default() -> Self {
+        TestGenerationOptions {
+            depth: 5,
+            tests: 1,
+            wait: 10,
+            coverage: false,
+        }
+    }
+}
+
+impl fmt::Display for TestGenerationOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{{depth={}, tests={}, wait={}",
+            self.depth, self.tests, self.wait
+        )?;
+        if self.coverage {
+            write!(f, ", coverage")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// A node of a test case. The times in a node are relative to when the tester reached it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TestCase {
+    /// The test has ended without finding a deviation from the specification
+    Pass,
+    /// Send the input `action` right away and continue with `next`.
+    /// Outputs observed before the input is sent are handled by the node the test came from.
+    Input { action: String, next: Box<TestCase> },
+    /// Wait for an output. An output which is not expected, or is observed outside its windows, fails the test.
+    Observe {
+        outputs: Vec<ExpectedOutput>,
+        timeout: TestTimeout,
+    },
+}
+
+/// An output allowed by the specification in an [TestCase::Observe] node, and the test to continue with when it is observed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExpectedOutput {
+    pub action: String,
+    pub windows: Vec<TimeWindow>,
+    pub next: TestCase,
+}
+
+/// What happens when no output is observed in an [TestCase::Observe] node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum TestTimeout {
+    /// The specification requires an output, so the test fails if more than `after` time units pass without one
+    Fail { after: u32 },
+    /// The implementation may stay quiescent, so the test continues with `next` after waiting `after` time units
+    Continue { after: u32, next: Box<TestCase> },
+}
+
+/// An interval of time, where no `upper` bound means that the interval is unbounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimeWindow {
+    pub lower: i32,
+    pub lower_strict: bool,
+    pub upper: Option<i32>,
+    pub upper_strict: bool,
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let open = if self.lower_strict { '(' } else { '[' };
+        match self.upper {
+            Some(upper) => {
+                let close = if self.upper_strict { ')' } else { ']' };
+                write!(f, "{}{}, {}{}", open, self.lower, upper, close)
+            }
+            None => write!(f, "{}{}, inf)", open, self.lower),
+        }
+    }
+}
+
+/// The generated test cases with the locations and edges of each component they cover
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestSuite {
+    pub tests: Vec<TestCase>,
+    pub coverage: BTreeMap<String, ComponentCoverage>,
+}
+
+/// Generates at most `options.tests` test cases from the specification `system`, where `test_clock` is a clock not used by it.
+/// With `options.coverage`, the generation stops early once a test case covers no new locations or edges.
+/// Returns an error if the query is interrupted, see [cancellation].
+pub fn generate_tests(
+    system: &TransitionSystemPtr,
+    test_clock: ClockIndex,
+    options: &TestGenerationOptions,
+) -> Result<TestSuite, Interrupted> {
+    let mut generator = TestGenerator {
+        system,
+        test_clock,
+        options,
+        inputs: sorted(system.get_input_actions()),
+        outputs: sorted(system.get_output_actions()),
+        coverage: BTreeMap::new(),
+    };
+    count_totals(system.as_ref(), &mut generator.coverage);
+
+    let initial = match system.get_initial_state() {
+        Some(state) => state,
+        None => {
+            return Ok(TestSuite {
+                tests: vec![],
+                coverage: generator.coverage,
+            })
+        }
+    };
+    cover_state(&initial, system.as_ref(), &mut generator.coverage);
+
+    let mut tests = vec![];
+    for index in 0..options.tests {
+        let covered = covered_count(&generator.coverage);
+        let test = generator.node(vec![initial.clone()], options.depth, index)?;
+        if options.coverage && index > 0 && covered_count(&generator.coverage) == covered {
+            break;
+        }
+        tests.push(test);
+    }
+
+    Ok(TestSuite {
+        tests,
+        coverage: generator.coverage,
+    })
+}
+
+fn sorted(actions: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut actions: Vec<String> = actions.into_iter().collect();
+    actions.sort();
+    actions
+}
+
+fn covered_count(coverage: &BTreeMap<String, ComponentCoverage>) -> usize {
+    coverage
+        .values()
+        .map(|coverage| coverage.visited_locations.len() + coverage.visited_edges.len())
+        .sum()
+}
+
+struct TestGenerator<'a> {
+    system: &'a TransitionSystemPtr,
+    test_clock: ClockIndex,
+    options: &'a TestGenerationOptions,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    coverage: BTreeMap<String, ComponentCoverage>,
+}
+
+/// A way to continue a test from a node, along with the decisions it may take in the specification
+enum Candidate {
+    Input {
+        action: String,
+        decisions: Vec<Decision>,
+    },
+    Observe {
+        outputs: Vec<(String, Vec<TimeWindow>, Vec<Decision>)>,
+        deadline: Option<u32>,
+        /// The states after waiting for `options.wait` time units, if no output is required before that
+        quiescent: Vec<State>,
+    },
+}
+
+impl Candidate {
+    fn decisions(&self) -> Box<dyn Iterator<Item = &Decision> + '_> {
+        match self {
+            Candidate::Input { decisions, .. } => Box::new(decisions.iter()),
+            Candidate::Observe { outputs, .. } => Box::new(
+                outputs
+                    .iter()
+                    .flat_map(|(_, _, decisions)| decisions.iter()),
+            ),
+        }
+    }
+}
+
+impl<'a> TestGenerator<'a> {
+    /// Generates the node of a test case where the specification can be in any of the `states`,
+    /// which the tester has just reached so the test clock is 0 in all of them
+    fn node(
+        &mut self,
+        states: Vec<State>,
+        depth: usize,
+        index: usize,
+    ) -> Result<TestCase, Interrupted> {
+        cancellation::checkpoint()?;
+        if depth == 0 || states.is_empty() {
+            return Ok(TestCase::Pass);
+        }
+
+        let mut candidates = vec![self.observe(&states)];
+        candidates.extend(
+            self.inputs
+                .iter()
+                .map(|input| Candidate::Input {
+                    action: input.clone(),
+                    decisions: self.decisions(&states, input),
+                })
+                .filter(|candidate| candidate.decisions().next().is_some()),
+        );
+
+        // The candidates take turns between the test cases, unless one of them covers something new
+        let mut chosen = (index + depth) % candidates.len();
+        if self.options.coverage {
+            let scores: Vec<usize> = candidates.iter().map(|c| self.score(c)).collect();
+            let best = scores.iter().copied().max().unwrap_or_default();
+            if best > 0 {
+                // The first candidate with the best score, so observing is preferred on ties
+                chosen = scores.iter().position(|score| *score == best).unwrap();
+            }
+        }
+
+        Ok(match candidates.swap_remove(chosen) {
+            Candidate::Input { action, decisions } => TestCase::Input {
+                action,
+                next: Box::new(self.after(decisions, depth, index)?),
+            },
+            Candidate::Observe {
+                outputs,
+                deadline,
+                quiescent,
+            } => {
+                let outputs = outputs
+                    .into_iter()
+                    .map(|(action, windows, decisions)| {
+                        Ok(ExpectedOutput {
+                            action,
+                            windows,
+                            next: self.after(decisions, depth, index)?,
+                        })
+                    })
+                    .collect::<Result<_, Interrupted>>()?;
+                let timeout = match deadline {
+                    Some(after) => TestTimeout::Fail { after },
+                    None => TestTimeout::Continue {
+                        after: self.options.wait,
+                        next: Box::new(self.node(quiescent, depth - 1, index)?),
+                    },
+                };
+                TestCase::Observe { outputs, timeout }
+            }
+        })
+    }
+
+    /// Generates the node reached by taking one of the `decisions`, marking their locations and edges as covered
+    fn after(
+        &mut self,
+        decisions: Vec<Decision>,
+        depth: usize,
+        index: usize,
+    ) -> Result<TestCase, Interrupted> {
+        let states = decisions
+            .into_iter()
+            .map(|decision| {
+                cover_decision(&decision, self.system.as_ref(), &mut self.coverage);
+                cover_state(
+                    &decision.next_state,
+                    self.system.as_ref(),
+                    &mut self.coverage,
+                );
+                decision.next_state
+            })
+            .collect();
+        self.node(states, depth - 1, index)
+    }
+
+    /// The number of locations and edges not covered yet which the `candidate` may take
+    fn score(&self, candidate: &Candidate) -> usize {
+        let mut coverage = self.coverage.clone();
+        for decision in candidate.decisions() {
+            cover_decision(decision, self.system.as_ref(), &mut coverage);
+            cover_state(&decision.next_state, self.system.as_ref(), &mut coverage);
+        }
+        covered_count(&coverage) - covered_count(&self.coverage)
+    }
+
+    /// The decisions taking `action` from the `states` with the test clock reset in their next states
+    fn decisions(&self, states: &[State], action: &str) -> Vec<Decision> {
+        states
+            .iter()
+            .flat_map(|state| {
+                self.system
+                    .next_transitions(&state.decorated_locations, action)
+                    .into_iter()
+                    .filter_map(|transition| {
+                        let mut decision =
+                            Decision::from_state_transition(state.clone(), &transition, action)?;
+                        // Taking a transition lets time pass in the next state, which the next node does itself
+                        let mut next_state = decision.state.clone();
+                        next_state.update_zone(|zone| transition.apply_updates(zone));
+                        transition.move_locations(&mut next_state.decorated_locations);
+                        next_state.apply_invariants();
+                        next_state.update_zone(|zone| zone.update_clock_val(self.test_clock, 0));
+                        decision.next_state = next_state;
+                        Some(decision)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn observe(&self, states: &[State]) -> Candidate {
+        let delayed: Vec<State> = states
+            .iter()
+            .map(|state| {
+                let mut state = state.clone();
+                state.update_zone(|zone| zone.up());
+                state.apply_invariants();
+                state
+            })
+            .collect();
+
+        let outputs = self
+            .outputs
+            .iter()
+            .filter_map(|output| {
+                let decisions = self.decisions(&delayed, output);
+                let mut windows: Vec<TimeWindow> = decisions
+                    .iter()
+                    .flat_map(|decision| time_windows(decision.state.zone_ref(), self.test_clock))
+                    .collect();
+                windows.sort_by_key(|window| (window.lower, window.upper.is_none(), window.upper));
+                windows.dedup();
+                match decisions.is_empty() {
+                    true => None,
+                    false => Some((output.clone(), windows, decisions)),
+                }
+            })
+            .collect();
+
+        // The implementation may only stay quiescent for longer than the deadline if one of the states allows it.
+        // Clocks are never negative, so neither are their upper bounds
+        let deadline = delayed
+            .iter()
+            .flat_map(|state| time_windows(state.zone_ref(), self.test_clock))
+            .map(|window| window.upper)
+            .try_fold(0, |deadline, upper| {
+                upper.map(|upper| deadline.max(u32::try_from(upper).unwrap_or_default()))
+            });
+
+        let wait = self.options.wait as i32;
+        let quiescent = match deadline {
+            Some(_) => vec![],
+            None => delayed
+                .into_iter()
+                .filter_map(|mut state| {
+                    state.update_zone(|zone| {
+                        zone.constrain(self.test_clock, 0, Inequality::LE(wait))
+                            .constrain(0, self.test_clock, Inequality::LE(-wait))
+                            .update_clock_val(self.test_clock, 0)
+                    });
+                    (!state.zone_ref().is_empty()).then_some(state)
+                })
+                .collect(),
+        };
+
+        Candidate::Observe {
+            outputs,
+            deadline,
+            quiescent,
+        }
+    }
+}
+
+/// The values `clock` can have in each convex part of `zone`.
+/// Every other clock is freed first, so the canonical bounds on `clock` are the only constraints left in each part
+fn time_windows(zone: &OwnedFederation, clock: ClockIndex) -> Vec<TimeWindow> {
+    let projection = (1..zone.dim())
+        .filter(|other| *other != clock)
+        .fold(zone.clone(), |zone, other| zone.free_clock(other));
+
+    projection
+        .minimal_constraints()
+        .conjunctions
+        .into_iter()
+        .map(|conjunction| {
+            let mut window = TimeWindow {
+                lower: 0,
+                lower_strict: false,
+                upper: None,
+                upper_strict: false,
+            };
+            for constraint in conjunction.constraints {
+                let ineq = constraint.ineq();
+                match (constraint.i, constraint.j) {
+                    // A lower bound is stored as -clock <= -lower
+                    (0, j) if j == clock => {
+                        window.lower = -ineq.bound();
+                        window.lower_strict = ineq.is_strict();
+                    }
+                    (i, 0) if i == clock => {
+                        window.upper = Some(ineq.bound());
+                        window.upper_strict = ineq.is_strict();
+                    }
+                    _ => unreachable!("Only the bounds on the clock are left"),
+                }
+            }
+            window
+        })
+        .collect()
+}
@@ -0,0 +1,459 @@
+//! Online testing of a running implementation against a specification.
+//!
+//! The tester talks to the system under test (SUT) with a line protocol over its standard input and output.
+//! The SUT keeps the time and answers every command with the time since the start of the run:
+//!
+//! | Tester sends | SUT answers                                                                                        |
+//! |--------------|----------------------------------------------------------------------------------------------------|
+//! | `input <a>`  | `ok <t>` when it received the input `a` at time `t`                                                |
+//! | `wait <d>`   | `output <a> <t>` for its first output `a` within `d` time units, or `quiet <t>` at the end of the wait |
+//!
+//! The tester tracks every state the specification can be in with [Decision]s, using an extra clock which measures
+//! the time since the start of the run. As zones are integer bounded, a timestamp which is not an integer is tracked as
+//! the open interval between the integers around it, which may make the tester accept more, but never fail a conforming SUT.
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::DataReader::component_loader::ComponentLoader;
+use crate::DataReader::parse_queries::parse_to_system_expr;
+use crate::ModelObjects::State;
+use crate::Simulation::concrete::{parse_delay, Rational};
+use crate::Simulation::decision::Decision;
+use crate::Simulation::trace_check::TraceEvent;
+use crate::System::cancellation;
+use crate::System::extract_system_rep::get_system_recipe;
+use crate::System::specifics::SpecificState;
+use crate::TransitionSystems::TransitionSystemPtr;
+
+/// How many commands to send to the SUT, how they are chosen and how long to wait for outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestRunOptions {
+    pub steps: usize,
+    pub seed: u64,
+    pub wait: u32,
+    /// How many milliseconds of real time the SUT may take to answer a command before the run is inconclusive
+    pub answer_timeout_ms: u64,
+}
+
+impl Default for TestRunOptions {
+    fn default() -> Self {
+        TestRunOptions {
+            steps: 20,
+            seed: 0,
+            wait: 10,
+            answer_timeout_ms: 5000,
+        }
+    }
+}
+
+/// An input sent to the SUT, or an output or a period of quiescence observed from it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestEvent {
+    Input(TraceEvent),
+    Output(TraceEvent),
+    /// No output was observed until the time
+    Quiescence(Rational),
+}
+
+impl fmt::Display for TestEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestEvent::Input(event) => write!(f, "input {}", event),
+            TestEvent::Output(event) => write!(f, "output {}", event),
+            TestEvent::Quiescence(time) => write!(f, "quiet until {}", time),
+        }
+    }
+}
+
+/// The outcome of a test run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestVerdict {
+    /// The SUT behaved as the specification allows for the whole run
+    Pass,
+    /// The SUT did something the specification does not allow
+    Fail(String),
+    /// The run reached behaviour the specification does not specify, so nothing can be concluded from the rest of it
+    Inconclusive(String),
+}
+
+impl fmt::Display for TestVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestVerdict::Pass => write!(f, "PASS"),
+            TestVerdict::Fail(reason) => write!(f, "FAIL: {}", reason),
+            TestVerdict::Inconclusive(reason) => write!(f, "INCONCLUSIVE: {}", reason),
+        }
+    }
+}
+
+/// The verdict of a test run with the events until it was given, and the states the specification could be in when it was given
+#[derive(Debug, Clone)]
+pub struct TestRunReport {
+    pub verdict: TestVerdict,
+    pub trace: Vec<TestEvent>,
+    pub states: Vec<SpecificState>,
+}
+
+impl fmt::Display for TestRunReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\nTrace:", self.verdict)?;
+        for event in &self.trace {
+            write!(f, "\n  {}", event)?;
+        }
+        if self.verdict != TestVerdict::Pass {
+            write!(f, "\nPossible states of the specification:")?;
+            for state in &self.states {
+                write!(f, "\n  {}", state)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compiles the specification `composition` with an extra clock for the tester, returning it along with the index of the clock
+pub fn specification_with_clock(
+    loader: &mut dyn ComponentLoader,
+    composition: &str,
+) -> Result<(TransitionSystemPtr, ClockIndex), Box<dyn Error>> {
+    let mut dim = 0;
+    let system = parse_to_system_expr(composition)?;
+    let recipe = get_system_recipe(&system, loader, &mut dim, &mut None)?;
+    let clock = dim + 1;
+    Ok((recipe.compile(clock).map_err(|err| err.to_string())?, clock))
+}
+
+/// Starts the SUT with `command` and tests it against the specification `system`, where `clock` is a clock not used by it
+pub fn run_process(
+    system: &TransitionSystemPtr,
+    clock: ClockIndex,
+    options: &TestRunOptions,
+    command: &[String],
+) -> Result<TestRunReport, Box<dyn Error>> {
+    let (program, args) = command
+        .split_first()
+        .ok_or("Expected a command to start the system under test")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Could not start {}: {}", program, err))?;
+
+    let sut_input = child.stdin.take().unwrap();
+    let sut_output = BufReader::new(child.stdout.take().unwrap());
+    let report = run_test(system, clock, options, sut_input, sut_output);
+
+    // The SUT may keep waiting for commands, so it is stopped when the verdict is given
+    let _ = child.kill();
+    child.wait()?;
+    report
+}
+
+/// Tests the SUT against the specification `system` by sending commands to `sut_input` and reading the answers from `sut_output`.
+/// Every step either sends one of the inputs the specification accepts or waits for an output, chosen at random.
+/// The answers are read on a separate thread, so a SUT which stops answering makes the run inconclusive instead of blocking it.
+pub fn run_test(
+    system: &TransitionSystemPtr,
+    clock: ClockIndex,
+    options: &TestRunOptions,
+    mut sut_input: impl Write,
+    sut_output: impl BufRead + Send + 'static,
+) -> Result<TestRunReport, Box<dyn Error>> {
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let answers = read_lines(sut_output);
+    let timeout = Duration::from_millis(options.answer_timeout_ms);
+    let mut inputs: Vec<String> = system.get_input_actions().into_iter().collect();
+    // The actions of a system are not ordered, so the inputs are sorted to make the runs depend on the seed only
+    inputs.sort();
+
+    let mut tester = Tester::new(system, clock)?;
+    for _ in 0..options.steps {
+        cancellation::checkpoint()?;
+        let enabled: Vec<&String> = inputs
+            .iter()
+            .filter(|input| tester.accepts(input))
+            .collect();
+        // Waiting is chosen as often as each of the inputs
+        let choice = rng.gen_range(0..=enabled.len());
+
+        let outcome = match enabled.get(choice) {
+            Some(input) => {
+                writeln!(sut_input, "input {}", input)?;
+                sut_input.flush()?;
+                match read_answer(&answers, timeout)? {
+                    None => return Ok(tester.report(no_answer(timeout))),
+                    Some(Answer::Ok(time)) => {
+                        tester.advance(time)?;
+                        tester.input(input, time)
+                    }
+                    Some(answer) => return Err(unexpected(&answer, "ok").into()),
+                }
+            }
+            None => {
+                writeln!(sut_input, "wait {}", options.wait)?;
+                sut_input.flush()?;
+                match read_answer(&answers, timeout)? {
+                    None => return Ok(tester.report(no_answer(timeout))),
+                    Some(Answer::Output(action, time)) => {
+                        tester.advance(time)?;
+                        tester.output(&action, time)
+                    }
+                    Some(Answer::Quiet(time)) => {
+                        tester.advance(time)?;
+                        tester.quiet(time)
+                    }
+                    Some(answer) => return Err(unexpected(&answer, "output or quiet").into()),
+                }
+            }
+        };
+
+        if let Err(verdict) = outcome {
+            return Ok(tester.report(verdict));
+        }
+    }
+
+    Ok(tester.report(TestVerdict::Pass))
+}
+
+/// An answer from the SUT
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Answer {
+    Ok(Rational),
+    Output(String, Rational),
+    Quiet(Rational),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Ok(time) => write!(f, "ok {}", time),
+            Answer::Output(action, time) => write!(f, "output {} {}", action, time),
+            Answer::Quiet(time) => write!(f, "quiet {}", time),
+        }
+    }
+}
+
+/// Sends the lines of `output` to the returned receiver from a thread, which stops once `output` or the receiver is closed
+fn read_lines(output: impl BufRead + Send + 'static) -> Receiver<io::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in output.lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Reads the next answer, or `None` if the SUT does not answer within `timeout`
+fn read_answer(
+    answers: &Receiver<io::Result<String>>,
+    timeout: Duration,
+) -> Result<Option<Answer>, Box<dyn Error>> {
+    let line = match answers.recv_timeout(timeout) {
+        Ok(line) => line?,
+        Err(RecvTimeoutError::Timeout) => return Ok(None),
+        Err(RecvTimeoutError::Disconnected) => {
+            return Err("The system under test stopped answering".into())
+        }
+    };
+    let invalid = |reason: String| format!("Invalid answer '{}': {}", line.trim(), reason);
+    let time = |time: &str| parse_delay(time).map_err(invalid);
+
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let answer = match words.as_slice() {
+        ["ok", t] => Answer::Ok(time(t)?),
+        ["output", action, t] => Answer::Output(action.to_string(), time(t)?),
+        ["quiet", t] => Answer::Quiet(time(t)?),
+        _ => return Err(invalid("expected ok, output or quiet".to_string()).into()),
+    };
+    Ok(Some(answer))
+}
+
+fn no_answer(timeout: Duration) -> TestVerdict {
+    TestVerdict::Inconclusive(format!(
+        "the system under test did not answer within {} ms",
+        timeout.as_millis()
+    ))
+}
+
+fn unexpected(answer: &Answer, expected: &str) -> String {
+    format!("Unexpected answer '{}', expected {}", answer, expected)
+}
+
+/// The states the specification can be in, which are closed under delay, along with the events so far
+struct Tester<'a> {
+    system: &'a TransitionSystemPtr,
+    clock: ClockIndex,
+    states: Vec<State>,
+    time: Rational,
+    trace: Vec<TestEvent>,
+}
+
+impl<'a> Tester<'a> {
+    fn new(system: &'a TransitionSystemPtr, clock: ClockIndex) -> Result<Self, String> {
+        let mut state = system
+            .get_initial_state()
+            .ok_or("The specification has no initial state")?;
+        state.update_zone(|zone| zone.up());
+        state.apply_invariants();
+        Ok(Tester {
+            system,
+            clock,
+            states: vec![state],
+            time: Rational::from_integer(0),
+            trace: vec![],
+        })
+    }
+
+    /// Whether any of the states can take the `input` at some point
+    fn accepts(&self, input: &str) -> bool {
+        !self.take(&self.states, input).is_empty()
+    }
+
+    fn input(&mut self, action: &str, time: Rational) -> Result<(), TestVerdict> {
+        self.trace.push(TestEvent::Input(TraceEvent {
+            action: action.to_string(),
+            timestamp: time,
+        }));
+        let states = self.at(time)?;
+        let next = self.take(&states, action);
+        if next.is_empty() {
+            self.states = states;
+            return Err(TestVerdict::Inconclusive(format!(
+                "the specification does not specify input {} at {}",
+                action, time
+            )));
+        }
+        self.states = next;
+        Ok(())
+    }
+
+    fn output(&mut self, action: &str, time: Rational) -> Result<(), TestVerdict> {
+        self.trace.push(TestEvent::Output(TraceEvent {
+            action: action.to_string(),
+            timestamp: time,
+        }));
+        if !self.system.get_output_actions().contains(action) {
+            return Err(TestVerdict::Fail(format!(
+                "{} is not an output of the specification",
+                action
+            )));
+        }
+        let states = self.at(time)?;
+        let next = self.take(&states, action);
+        if next.is_empty() {
+            self.states = states;
+            return Err(TestVerdict::Fail(format!(
+                "the specification does not allow output {} at {}",
+                action, time
+            )));
+        }
+        self.states = next;
+        Ok(())
+    }
+
+    fn quiet(&mut self, time: Rational) -> Result<(), TestVerdict> {
+        self.trace.push(TestEvent::Quiescence(time));
+        self.states = self
+            .at(time)?
+            .into_iter()
+            .map(|mut state| {
+                state.update_zone(|zone| zone.up());
+                state.apply_invariants();
+                state
+            })
+            .collect();
+        Ok(())
+    }
+
+    /// Checks that the SUT does not go back in time from the previous event to `time`
+    fn advance(&mut self, time: Rational) -> Result<(), String> {
+        if time < self.time {
+            return Err(format!(
+                "The system under test went back in time from {} to {}",
+                self.time, time
+            ));
+        }
+        self.time = time;
+        Ok(())
+    }
+
+    /// The states at `time`, failing if the specification requires an output before it
+    fn at(&self, time: Rational) -> Result<Vec<State>, TestVerdict> {
+        let states: Vec<State> = self
+            .states
+            .iter()
+            .filter_map(|state| {
+                let mut state = state.clone();
+                state.update_zone(|zone| at_time(zone, self.clock, time));
+                (!state.zone_ref().is_empty()).then_some(state)
+            })
+            .collect();
+        match states.is_empty() {
+            true => Err(TestVerdict::Fail(format!(
+                "the specification requires an output before {}",
+                time
+            ))),
+            false => Ok(states),
+        }
+    }
+
+    /// The states reached by taking `action` from the `states`, the same way a [Decision] is taken
+    fn take(&self, states: &[State], action: &str) -> Vec<State> {
+        let mut next_states: Vec<State> = vec![];
+        for state in states {
+            for transition in self
+                .system
+                .next_transitions(&state.decorated_locations, action)
+            {
+                if let Some(decision) =
+                    Decision::from_state_transition(state.clone(), &transition, action)
+                {
+                    if !decision.next_state.is_contained_in_list(&next_states) {
+                        next_states.push(decision.next_state);
+                    }
+                }
+            }
+        }
+        next_states
+    }
+
+    fn report(self, verdict: TestVerdict) -> TestRunReport {
+        TestRunReport {
+            verdict,
+            trace: self.trace,
+            states: self
+                .states
+                .iter()
+                .map(|state| SpecificState::from_state(state, self.system.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+/// Restricts `zone` to the valuations where `clock` is `time`, or between the integers around it
+fn at_time(zone: OwnedFederation, clock: ClockIndex, time: Rational) -> OwnedFederation {
+    let floor = time.floor().to_integer() as i32;
+    match time.is_integer() {
+        true => zone.constrain(clock, 0, Inequality::LE(floor)).constrain(
+            0,
+            clock,
+            Inequality::LE(-floor),
+        ),
+        false => zone
+            .constrain(clock, 0, Inequality::LS(floor + 1))
+            .constrain(0, clock, Inequality::LS(-floor)),
+    }
+}
@@ -0,0 +1,173 @@
+//! Replaying traces recorded from a running system to check that they conform to a model.
+//!
+//! A trace is a sequence of [TraceEvent]s with the time since the start of the run at which each action happened.
+//! As the timestamps are exact, the trace is replayed with [ConcreteState]s, keeping every state the model could be in
+//! when several transitions with the same action can be taken.
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::Simulation::concrete::{parse_delay, ConcreteState, Rational, SpecificConcreteState};
+use crate::System::cancellation::{self, Interrupted};
+use crate::System::query_failures::{TraceCheckFailure, TraceCheckResult, TraceDeviation};
+use crate::TransitionSystems::TransitionSystemPtr;
+
+/// An `action` observed at `timestamp` time units after the start of a run
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceEvent {
+    pub action: String,
+    pub timestamp: Rational,
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.action, self.timestamp)
+    }
+}
+
+/// Reads a trace from a JSON file if its extension is `.json`, and from a CSV file otherwise
+pub fn read_trace_file(path: impl AsRef<Path>) -> Result<Vec<TraceEvent>, String> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Could not read trace {}: {}", path.display(), err))?;
+    parse_trace(path, &contents)
+}
+
+/// Parses the `contents` of the trace file `path` as JSON if its extension is `.json`, and as CSV otherwise
+pub fn parse_trace(path: impl AsRef<Path>, contents: &str) -> Result<Vec<TraceEvent>, String> {
+    match path
+        .as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("json") => parse_json_trace(contents),
+        _ => parse_csv_trace(contents),
+    }
+}
+
+/// Parses a trace written as a JSON array of events, fx. `[{"action": "coin", "timestamp": 2.5}]`.
+/// The timestamps may also be strings to write them as fractions, fx. `"5/2"`.
+pub fn parse_json_trace(contents: &str) -> Result<Vec<TraceEvent>, String> {
+    let events: Vec<Value> =
+        serde_json::from_str(contents).map_err(|err| format!("Invalid JSON trace: {}", err))?;
+    events
+        .iter()
+        .enumerate()
+        .map(|(index, event)| {
+            let invalid = |reason: &str| format!("Invalid event {}: {}", index + 1, reason);
+            let action = event["action"]
+                .as_str()
+                .ok_or_else(|| invalid("expected an action"))?;
+            let timestamp = match &event["timestamp"] {
+                Value::Number(number) => number.to_string(),
+                Value::String(timestamp) => timestamp.clone(),
+                _ => return Err(invalid("expected a timestamp")),
+            };
+            Ok(TraceEvent {
+                action: action.to_string(),
+                timestamp: parse_delay(&timestamp).map_err(|err| invalid(&err))?,
+            })
+        })
+        .collect()
+}
+
+/// Parses a trace written as CSV with an action and a timestamp on each line, fx. `coin,2.5`.
+/// A header line `action,timestamp`, empty lines and lines starting with `#` are skipped.
+pub fn parse_csv_trace(contents: &str) -> Result<Vec<TraceEvent>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .filter(|(index, line)| !(*index == 0 && line.replace(' ', "") == "action,timestamp"))
+        .map(|(index, line)| {
+            let invalid = |reason: &str| format!("Invalid line {}: {}", index + 1, reason);
+            let (action, timestamp) = line
+                .split_once(',')
+                .ok_or_else(|| invalid("expected an action and a timestamp"))?;
+            Ok(TraceEvent {
+                action: action.trim().to_string(),
+                timestamp: parse_delay(timestamp.trim()).map_err(|err| invalid(&err))?,
+            })
+        })
+        .collect()
+}
+
+/// Replays the `events` in `system` from its initial state, returning the states it can be in after the last event,
+/// or the first event which the system cannot take along with the states it could be in.
+/// Returns an error if the query is interrupted, see [cancellation].
+pub fn check_trace(
+    system: &TransitionSystemPtr,
+    events: &[TraceEvent],
+) -> Result<TraceCheckResult, Interrupted> {
+    let specific = |states: &[ConcreteState]| -> Vec<SpecificConcreteState> {
+        states
+            .iter()
+            .map(|state| SpecificConcreteState::from_state(state, system.as_ref()))
+            .collect()
+    };
+    let actions = system.get_actions();
+
+    let mut states = match ConcreteState::initial(system) {
+        Ok(state) => vec![state],
+        Err(_) => {
+            return Ok(Err(TraceCheckFailure {
+                event: None,
+                deviation: TraceDeviation::NoInitialState,
+                states: vec![],
+            }))
+        }
+    };
+    let mut time = Rational::from_integer(0);
+
+    for (index, event) in events.iter().enumerate() {
+        cancellation::checkpoint()?;
+        let fail = |deviation, states: &[ConcreteState]| TraceCheckFailure {
+            event: Some((index, event.clone())),
+            deviation,
+            states: specific(states),
+        };
+
+        if event.timestamp < time {
+            return Ok(Err(fail(
+                TraceDeviation::TimeDecreasing { previous: time },
+                &states,
+            )));
+        }
+        if !actions.contains(&event.action) {
+            return Ok(Err(fail(TraceDeviation::UnknownAction, &states)));
+        }
+
+        let delayed: Vec<ConcreteState> = states
+            .iter()
+            .filter_map(|state| state.delay(event.timestamp - time).ok())
+            .collect();
+        if delayed.is_empty() {
+            return Ok(Err(fail(TraceDeviation::InvariantViolated, &states)));
+        }
+
+        let mut next_states: Vec<ConcreteState> = vec![];
+        for state in &delayed {
+            for transition in system.next_transitions(&state.locations, &event.action) {
+                if let Some(next) = state.take_transition(&transition) {
+                    let known = next_states.iter().any(|known| {
+                        known.locations.id == next.locations.id && known.valuation == next.valuation
+                    });
+                    if !known {
+                        next_states.push(next);
+                    }
+                }
+            }
+        }
+        if next_states.is_empty() {
+            return Ok(Err(fail(TraceDeviation::NotEnabled, &delayed)));
+        }
+
+        states = next_states;
+        time = event.timestamp;
+    }
+
+    Ok(Ok(specific(&states)))
+}
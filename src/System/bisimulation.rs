@@ -0,0 +1,299 @@
+//! Minimisation of components by merging their bisimilar locations, used by the `bisim-minim` query.
+//!
+//! The partition is refined on whole locations only, not on zones, see [minimise_locations].
+
+use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
+use log::debug;
+
+use crate::DataReader::parse_edge::Update;
+use crate::EdgeEval::constraint_applyer::apply_constraints_to_state;
+use crate::ModelObjects::Expressions::{ArithExpression, BoolExpression};
+use crate::ModelObjects::{Component, DeclarationProvider, Edge, Location, LocationType, SyncType};
+use crate::System::query_failures::SystemRecipeFailure;
+use crate::System::save_component::combine_components;
+use crate::TransitionSystems::{CompiledComponent, TransitionSystemPtr};
+use std::collections::HashMap;
+
+use super::save_component::PruningStrategy;
+
+/// Identifies the outgoing edges of a location which can be merged when minimising.
+/// The key consists of the action, whether it is an input, the updates and the block of the target location.
+type EdgeKey = (String, bool, Vec<(ClockIndex, i32)>, usize);
+
+/// The outgoing behaviour of a location with respect to the current partition.
+type Signature = HashMap<EdgeKey, OwnedFederation>;
+
+/// Combines the system into a single component and merges its bisimilar locations, see [minimise_locations].
+pub fn minimise_system_locations(
+    ts: TransitionSystemPtr,
+    dim: ClockIndex,
+) -> Result<TransitionSystemPtr, Box<SystemRecipeFailure>> {
+    let inputs = ts.get_input_actions();
+    let outputs = ts.get_output_actions();
+    let comp = combine_components(&ts, PruningStrategy::Reachable);
+
+    let minimised = minimise_locations(&comp, dim)?;
+
+    Ok(CompiledComponent::compile_with_actions(
+        minimised, inputs, outputs, dim, 0,
+    )?)
+}
+
+/// An edge of the component with its guard and updates compiled.
+struct CompiledEdge<'a> {
+    edge: &'a Edge,
+    guard: OwnedFederation,
+    updates: Vec<(ClockIndex, i32)>,
+}
+
+struct BisimContext<'a> {
+    comp: &'a Component,
+    invariants: HashMap<&'a str, OwnedFederation>,
+    edges: HashMap<&'a str, Vec<CompiledEdge<'a>>>,
+    block_of: HashMap<&'a str, usize>,
+}
+
+impl<'a> BisimContext<'a> {
+    fn new(comp: &'a Component, dim: ClockIndex) -> Result<Self, Box<SystemRecipeFailure>> {
+        let invariants = comp
+            .locations
+            .iter()
+            .map(|loc| {
+                Ok((
+                    loc.id.as_str(),
+                    to_federation(loc.invariant.as_ref(), comp, dim)?,
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, Box<SystemRecipeFailure>>>()?;
+
+        let mut edges: HashMap<&str, Vec<CompiledEdge>> = HashMap::new();
+        for edge in &comp.edges {
+            let mut guard = to_federation(edge.guard.as_ref(), comp, dim)?;
+            // Only the part of the guard satisfying the source invariant can be taken
+            if let Some(invariant) = invariants.get(edge.source_location.as_str()) {
+                guard = guard.intersection(invariant);
+            }
+            if guard.is_empty() {
+                continue;
+            }
+
+            let mut updates = edge
+                .update
+                .iter()
+                .flatten()
+                .map(|update| compile_update(update, comp))
+                .collect::<Result<Vec<_>, _>>()?;
+            updates.sort_unstable();
+
+            edges
+                .entry(edge.source_location.as_str())
+                .or_default()
+                .push(CompiledEdge {
+                    edge,
+                    guard,
+                    updates,
+                });
+        }
+
+        Ok(BisimContext {
+            comp,
+            invariants,
+            edges,
+            block_of: HashMap::new(),
+        })
+    }
+
+    fn set_blocks(&mut self, blocks: &[Vec<&'a str>]) {
+        self.block_of = blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(index, block)| block.iter().map(move |loc| (*loc, index)))
+            .collect();
+    }
+
+    fn signature(&self, loc: &str) -> Signature {
+        let mut signature: Signature = HashMap::new();
+        for compiled in self.edges.get(loc).into_iter().flatten() {
+            let key = (
+                compiled.edge.sync.clone(),
+                compiled.edge.sync_type == SyncType::Input,
+                compiled.updates.clone(),
+                self.block_of[compiled.edge.target_location.as_str()],
+            );
+            match signature.remove(&key) {
+                Some(fed) => signature.insert(key, fed.union(&compiled.guard)),
+                None => signature.insert(key, compiled.guard.clone()),
+            };
+        }
+        signature
+    }
+}
+
+fn to_federation(
+    expr: Option<&BoolExpression>,
+    comp: &Component,
+    dim: ClockIndex,
+) -> Result<OwnedFederation, Box<SystemRecipeFailure>> {
+    match expr {
+        Some(expr) => apply_constraints_to_state(
+            expr,
+            comp.get_declarations(),
+            OwnedFederation::universe(dim),
+        )
+        .map_err(|reason| SystemRecipeFailure::invalid(reason, &comp.name)),
+        None => Ok(OwnedFederation::universe(dim)),
+    }
+}
+
+/// Compiles `update` into the clock it resets and the value it resets it to.
+/// Only clock resets can be compared when minimising, so other updates are returned as an error.
+fn compile_update(
+    update: &Update,
+    comp: &Component,
+) -> Result<(ClockIndex, i32), Box<SystemRecipeFailure>> {
+    let clock = comp
+        .get_declarations()
+        .get_clock_index_by_name(update.get_variable_name());
+    match (clock, update.get_expression()) {
+        (Some(&clock), ArithExpression::Int(value)) => Ok((clock, *value)),
+        _ => Err(SystemRecipeFailure::invalid(
+            format!(
+                "Cannot minimise the update {} = {}, as only clock resets to constants are supported",
+                update.get_variable_name(),
+                update.get_expression()
+            ),
+            &comp.name,
+        )),
+    }
+}
+
+fn signatures_equal(left: &Signature, right: &Signature) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .all(|(key, fed)| right.get(key).map_or(false, |other| fed.equals(other)))
+}
+
+/// Minimises a component by merging the locations which are timed bisimilar.
+///
+/// The locations are initially partitioned by their invariants, after which the partition is
+/// refined until every location in a block can take the same actions, with the same updates, in
+/// the same clock valuations to the same blocks.
+///
+/// Only whole locations are merged, as the partition is never split on zones. Two locations which
+/// only behave the same in some of their clock valuations are therefore kept apart, so the result
+/// is bisimilar to `comp`, but not necessarily the smallest such component.
+///
+/// Returns an error if a guard, invariant or update of `comp` cannot be compiled into clock constraints and resets.
+pub fn minimise_locations(
+    comp: &Component,
+    dim: ClockIndex,
+) -> Result<Component, Box<SystemRecipeFailure>> {
+    let mut context = BisimContext::new(comp, dim)?;
+
+    // Initial partition on the invariants
+    let mut blocks: Vec<Vec<&str>> = vec![];
+    for loc in &comp.locations {
+        let invariant = &context.invariants[loc.id.as_str()];
+        match blocks
+            .iter_mut()
+            .find(|block| context.invariants[block[0]].equals(invariant))
+        {
+            Some(block) => block.push(loc.id.as_str()),
+            None => blocks.push(vec![loc.id.as_str()]),
+        }
+    }
+    context.set_blocks(&blocks);
+
+    loop {
+        let mut refined: Vec<Vec<&str>> = vec![];
+        for block in &blocks {
+            let mut split: Vec<(Signature, Vec<&str>)> = vec![];
+            for loc in block {
+                let signature = context.signature(loc);
+                match split
+                    .iter_mut()
+                    .find(|(other, _)| signatures_equal(other, &signature))
+                {
+                    Some((_, locs)) => locs.push(*loc),
+                    None => split.push((signature, vec![*loc])),
+                }
+            }
+            refined.extend(split.into_iter().map(|(_, locs)| locs));
+        }
+
+        let stable = refined.len() == blocks.len();
+        blocks = refined;
+        context.set_blocks(&blocks);
+        if stable {
+            break;
+        }
+    }
+
+    debug!(
+        "Minimised component from {} locations to {} locations",
+        comp.locations.len(),
+        blocks.len()
+    );
+
+    Ok(build_component(&context, &blocks))
+}
+
+fn build_component(context: &BisimContext, blocks: &[Vec<&str>]) -> Component {
+    let comp = context.comp;
+    let decls = comp.get_declarations();
+
+    let mut locations = vec![];
+    let mut edges = vec![];
+    for block in blocks {
+        let representative = block[0];
+        let is_initial = block
+            .iter()
+            .any(|loc| comp.get_location_by_name(loc).location_type == LocationType::Initial);
+
+        let mut location: Location = comp.get_location_by_name(representative).clone();
+        if is_initial {
+            location.location_type = LocationType::Initial;
+        }
+        locations.push(location);
+
+        let mut merged: Vec<(EdgeKey, &Edge)> = vec![];
+        for compiled in context.edges.get(representative).into_iter().flatten() {
+            let key = (
+                compiled.edge.sync.clone(),
+                compiled.edge.sync_type == SyncType::Input,
+                compiled.updates.clone(),
+                context.block_of[compiled.edge.target_location.as_str()],
+            );
+            if !merged.iter().any(|(other, _)| *other == key) {
+                merged.push((key, compiled.edge));
+            }
+        }
+
+        let signature = context.signature(representative);
+        for (key, edge) in merged {
+            let guard = BoolExpression::from_disjunction(
+                &signature[&key].minimal_constraints(),
+                &decls.clocks,
+            );
+            edges.push(Edge {
+                id: edge.id.clone(),
+                source_location: representative.to_string(),
+                target_location: blocks[key.3][0].to_string(),
+                sync_type: edge.sync_type,
+                guard,
+                update: edge.update.clone(),
+                sync: edge.sync.clone(),
+            });
+        }
+    }
+
+    Component {
+        name: comp.name.clone(),
+        declarations: decls.clone(),
+        locations,
+        edges,
+        special_id: comp.special_id.clone(),
+    }
+}
@@ -0,0 +1,186 @@
+//! Cooperative cancellation of long running queries.
+//!
+//! A query is run with [run_cancellable], which makes its [CancellationToken] the token of the current thread.
+//! Long running loops call [checkpoint], which returns an error once the token is cancelled or its timeout has passed.
+//! The searches pass the error on, and their [ExecutableQuery](super::executable_query::ExecutableQuery) reports it as the result of the query.
+//! The consistency and determinism checks are also used while the systems are built, where no query can be interrupted,
+//! so they stop exploring at the error instead, and [run_cancellable] returns the error in place of their result.
+//! The same mechanism stops queries which exceed their [limits](super::limits).
+//! Code which moves work to other threads, e.g. with rayon, must pass the token on with [with_token].
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::limits::{ExplorationBudget, ExplorationLimits, ExplorationStatistics, LimitReached};
+
+/// The reason a query was stopped before it finished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interrupted {
+    /// The query ran for longer than its timeout
+    Timeout,
+    /// The query was cancelled by the user
+    Cancelled,
+    /// The query stored more states than its limits allow
+    LimitReached(LimitReached),
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Interrupted::Timeout => write!(f, "The query timed out"),
+            Interrupted::Cancelled => write!(f, "The query was cancelled"),
+            Interrupted::LimitReached(limit) => write!(f, "{}", limit),
+        }
+    }
+}
+
+impl std::error::Error for Interrupted {}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    deadline: Option<Instant>,
+    budget: ExplorationBudget,
+    /// The first interruption returned by a checkpoint of the query
+    interrupted: Mutex<Option<Interrupted>>,
+}
+
+/// A token shared between a running query and whoever may cancel it
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<TokenState>,
+}
+
+impl CancellationToken {
+    /// Creates a token without a timeout, which is only interrupted by [CancellationToken::cancel]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token which times out after `timeout`
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken {
+            state: Arc::new(TokenState {
+                deadline: Some(Instant::now() + timeout),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Creates a token from the `timeout_ms` of the query settings, where 0 means no timeout
+    pub fn from_timeout_ms(timeout_ms: u64) -> Self {
+        match timeout_ms {
+            0 => Self::new(),
+            ms => Self::with_timeout(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Limits the states stored by the query, see [limits](super::limits)
+    pub fn with_limits(mut self, limits: ExplorationLimits) -> Self {
+        Arc::get_mut(&mut self.state)
+            .expect("The limits must be set before the token is shared")
+            .budget = ExplorationBudget::new(limits);
+        self
+    }
+
+    pub(super) fn budget(&self) -> &ExplorationBudget {
+        &self.state.budget
+    }
+
+    /// Returns how much the query stores, see [limits](super::limits)
+    pub fn statistics(&self) -> ExplorationStatistics {
+        self.state.budget.statistics()
+    }
+
+    /// Returns true if `other` is a clone of this token, i.e. the token of the same query
+    pub fn is_same(&self, other: &CancellationToken) -> bool {
+        Arc::ptr_eq(&self.state, &other.state)
+    }
+
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns why the query should stop, if it should
+    pub fn interruption(&self) -> Option<Interrupted> {
+        if let Some(interrupted) = self.interrupted() {
+            return Some(interrupted);
+        }
+        if self.state.cancelled.load(Ordering::Relaxed) {
+            return Some(Interrupted::Cancelled);
+        }
+        match self.state.deadline {
+            Some(deadline) if Instant::now() >= deadline => Some(Interrupted::Timeout),
+            _ => None,
+        }
+    }
+
+    /// Returns an error if the query should stop, which the query must pass on
+    pub fn checkpoint(&self) -> Result<(), Interrupted> {
+        match self.interruption() {
+            Some(interrupted) => Err(self.interrupt(interrupted)),
+            None => Ok(()),
+        }
+    }
+
+    /// Stops the query because of `interrupted`, unless it was already stopped, and returns why it is stopped
+    pub(super) fn interrupt(&self, interrupted: Interrupted) -> Interrupted {
+        self.state
+            .interrupted
+            .lock()
+            .unwrap()
+            .get_or_insert(interrupted)
+            .clone()
+    }
+
+    /// Returns why the query was stopped, if a checkpoint of the query has returned an error
+    pub fn interrupted(&self) -> Option<Interrupted> {
+        self.state.interrupted.lock().unwrap().clone()
+    }
+}
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = RefCell::new(None);
+}
+
+/// Returns the token of the query running on the current thread
+pub fn current_token() -> Option<CancellationToken> {
+    CURRENT_TOKEN.with(|token| token.borrow().clone())
+}
+
+/// Returns an error if the query running on the current thread should stop
+pub fn checkpoint() -> Result<(), Interrupted> {
+    match current_token() {
+        Some(token) => token.checkpoint(),
+        None => Ok(()),
+    }
+}
+
+/// Runs `f` with `token` as the token of the current thread, restoring the previous token afterwards
+pub fn with_token<R>(token: Option<CancellationToken>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<CancellationToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_TOKEN.with(|token| *token.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(CURRENT_TOKEN.with(|current| current.replace(token)));
+    f()
+}
+
+/// Runs `f` with `token` as the token of the current thread.
+///
+/// Returns why `f` was stopped instead of its result, if a checkpoint of `f` returned an error.
+pub fn run_cancellable<R>(
+    token: &CancellationToken,
+    f: impl FnOnce() -> R,
+) -> Result<R, Interrupted> {
+    let result = with_token(Some(token.clone()), f);
+    match token.interrupted() {
+        Some(interrupted) => Err(interrupted),
+        None => Ok(result),
+    }
+}
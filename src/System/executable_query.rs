@@ -1,14 +1,29 @@
 use crate::DataReader::component_loader::ComponentLoader;
+use crate::ModelObjects::Expressions::{TemporalOperator, TimedAction};
 use crate::ModelObjects::State;
+use crate::Simulation::automatic::{self, AutomaticSimulationOptions};
+use crate::Simulation::concrete::ConcreteSimulator;
+use crate::Simulation::test_generation::{self, TestGenerationOptions};
+use crate::Simulation::trace_check::{self, TraceEvent};
+use crate::System::local_consistency;
+use crate::System::parallel_reachability;
+use crate::System::parallel_refine;
 use crate::System::reachability;
 use crate::System::refine;
+use crate::System::refinement_strategy;
 use crate::System::save_component::combine_components;
+use crate::System::temporal;
 use crate::TransitionSystems::TransitionSystemPtr;
+use edbm::util::constraints::ClockIndex;
 
+use super::query_failures::ConcreteTraceFailure;
 use super::query_failures::PathFailure;
 use super::query_failures::QueryResult;
+use super::query_failures::RefinementFailure;
+use super::query_failures::SpecificationFailure;
+use super::query_failures::TemporalFailure;
 use super::save_component::PruningStrategy;
-use super::specifics::SpecificDecision;
+use super::specifics::{SpecificDecision, SpecificLasso};
 
 impl QueryResult {
     pub fn print_result(&self, query_str: &str) {
@@ -17,6 +32,13 @@ impl QueryResult {
             QueryResult::Refinement(Err(failure)) => {
                 not_satisfied(query_str);
                 println!("\nGot failure: {}", failure);
+                if let RefinementFailure::CannotMatch { path, .. }
+                | RefinementFailure::CutsDelaySolutions { path, .. } = failure
+                {
+                    if !path.path.is_empty() {
+                        print_path(&path.path);
+                    }
+                }
             }
 
             QueryResult::Reachability(path) => match path {
@@ -30,15 +52,133 @@ impl QueryResult {
             },
 
             QueryResult::Consistency(Ok(_)) => satisfied(query_str),
-            QueryResult::Consistency(Err(_)) => not_satisfied(query_str),
+            QueryResult::Consistency(Err(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+                if let Some(path) = failure.path() {
+                    if !path.path.is_empty() {
+                        print_path(&path.path);
+                    }
+                }
+            }
 
             QueryResult::Determinism(Ok(_)) => satisfied(query_str),
-            QueryResult::Determinism(Err(_)) => not_satisfied(query_str),
+            QueryResult::Determinism(Err(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+                if !failure.path.path.is_empty() {
+                    print_path(&failure.path.path);
+                }
+            }
+
+            QueryResult::Implementation(Ok(_)) => satisfied(query_str),
+            QueryResult::Implementation(Err(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
+
+            QueryResult::Specification(Ok(_)) => satisfied(query_str),
+            QueryResult::Specification(Err(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+            }
+
+            QueryResult::Temporal(Ok(witness)) => {
+                satisfied(query_str);
+                if let Some(witness) = witness {
+                    print_lasso(witness);
+                }
+            }
+            QueryResult::Temporal(Err(failure)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", failure);
+                match failure {
+                    TemporalFailure::Counterexample { counterexample, .. }
+                    | TemporalFailure::NotLeadsTo { counterexample } => print_lasso(counterexample),
+                    TemporalFailure::NoWitness(_) => {}
+                }
+            }
+
+            QueryResult::ConcreteTrace(Ok(trace)) => {
+                println!("{} -- Trace is feasible", query_str);
+                println!("{}", trace);
+            }
+            QueryResult::ConcreteTrace(Err(failure)) => {
+                println!("{} -- Trace is NOT feasible", query_str);
+                println!("\nGot failure: {}", failure);
+                if let Some(trace) = &failure.trace {
+                    println!("Trace until the failure:\n{}", trace);
+                }
+            }
+
+            QueryResult::TraceCheck(Ok(states)) => {
+                println!("{} -- Trace conforms to the system", query_str);
+                println!("Possible states after the trace:");
+                for state in states {
+                    println!("  {}", state);
+                }
+            }
+            QueryResult::TraceCheck(Err(failure)) => {
+                println!("{} -- Trace does NOT conform to the system", query_str);
+                println!("\nGot failure: {}", failure);
+                if !failure.states.is_empty() {
+                    println!("Possible states when the trace left the system:");
+                    for state in &failure.states {
+                        println!("  {}", state);
+                    }
+                }
+            }
+
+            QueryResult::Simulation(report) => {
+                println!("{} -- Simulation finished", query_str);
+                println!("{}", report);
+            }
+
+            QueryResult::TestCases(suite) => {
+                println!(
+                    "{} -- Generated {} test cases",
+                    query_str,
+                    suite.tests.len()
+                );
+                match serde_json::to_string_pretty(suite) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => println!("Could not export the test cases: {}", err),
+                }
+            }
+
+            QueryResult::Strategy(Ok(None)) => {
+                println!(
+                    "{} -- The refinement holds, so the environment has no winning strategy",
+                    query_str
+                )
+            }
+            QueryResult::Strategy(Ok(Some(strategy))) => {
+                println!(
+                    "{} -- Found a {} winning strategy for the environment",
+                    query_str,
+                    if strategy.complete {
+                        "complete"
+                    } else {
+                        "partial"
+                    }
+                );
+                match serde_json::to_string_pretty(strategy) {
+                    Ok(json) => println!("{}", json),
+                    Err(err) => println!("Could not export the strategy: {}", err),
+                }
+            }
+            QueryResult::Strategy(Err(precondition)) => {
+                not_satisfied(query_str);
+                println!("\nGot failure: {}", precondition);
+            }
 
             QueryResult::GetComponent(_) => {
                 println!("{} -- Component succesfully created", query_str)
             }
-            QueryResult::CustomError(_) => println!("{} -- Failed", query_str),
+            QueryResult::Timeout => println!("{} -- Timed out", query_str),
+            QueryResult::Cancelled => println!("{} -- Cancelled", query_str),
+            QueryResult::Inconclusive(limit) => println!("{} -- {}", query_str, limit),
+            QueryResult::CustomError(err) => println!("{} -- Failed: {}", query_str, err),
             QueryResult::RecipeFailure(_) => not_satisfied(query_str),
         };
     }
@@ -64,6 +204,14 @@ fn print_path(path: &Vec<SpecificDecision>) {
     }
 }
 
+fn print_lasso(lasso: &SpecificLasso) {
+    print_path(&lasso.prefix.path);
+    if !lasso.cycle.path.is_empty() {
+        println!("Repeated forever:");
+        print_path(&lasso.cycle.path);
+    }
+}
+
 pub trait ExecutableQuery {
     fn execute(self: Box<Self>) -> QueryResult;
 }
@@ -71,13 +219,38 @@ pub trait ExecutableQuery {
 pub struct RefinementExecutor {
     pub sys1: TransitionSystemPtr,
     pub sys2: TransitionSystemPtr,
+
+    // parallel is whether to explore the state pairs on every thread of the current thread pool
+    pub parallel: bool,
+    // build_relation is whether to return the refinement relation when the refinement holds
+    pub build_relation: bool,
 }
 
 impl ExecutableQuery for RefinementExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
         let (sys1, sys2) = (self.sys1, self.sys2);
 
-        refine::check_refinement(sys1, sys2).into()
+        if self.parallel {
+            parallel_refine::check_refinement_parallel(sys1, sys2, self.build_relation).into()
+        } else {
+            refine::check_refinement(sys1, sys2, self.build_relation).into()
+        }
+    }
+}
+
+/// Used to store input for synthesizing the winning strategy of the environment in a refinement
+pub struct StrategyExecutor {
+    pub sys1: TransitionSystemPtr,
+    pub sys2: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for StrategyExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        match refinement_strategy::synthesize_strategy(&self.sys1, &self.sys2) {
+            Ok(Ok(result)) => QueryResult::Strategy(result),
+            Ok(Err(err)) => QueryResult::CustomError(err),
+            Err(interrupted) => interrupted.into(),
+        }
     }
 }
 
@@ -91,10 +264,142 @@ pub struct ReachabilityExecutor {
 
     // e_state is the end state, where we want to see whether end state is reachable from start state
     pub end_state: State,
+
+    // parallel is whether to explore the states on every thread of the current thread pool
+    pub parallel: bool,
 }
 impl ExecutableQuery for ReachabilityExecutor {
     fn execute(self: Box<Self>) -> QueryResult {
-        reachability::find_specific_path(self.start_state, self.end_state, &self.transition_system)
+        if self.parallel {
+            parallel_reachability::find_specific_path_parallel(
+                self.start_state,
+                self.end_state,
+                &self.transition_system,
+            )
+            .into()
+        } else {
+            reachability::find_specific_path(
+                self.start_state,
+                self.end_state,
+                &self.transition_system,
+            )
+            .into()
+        }
+    }
+}
+
+/// Used to store input for the temporal logic checker
+pub struct TemporalExecutor {
+    pub operator: TemporalOperator,
+    pub system: TransitionSystemPtr,
+    // The state predicate which the operator is applied to
+    pub predicate: State,
+    // A clock not used by the system, which measures whether time diverges on a path
+    pub divergence_clock: ClockIndex,
+}
+
+impl ExecutableQuery for TemporalExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        temporal::check_temporal(
+            self.operator,
+            &self.system,
+            self.predicate,
+            self.divergence_clock,
+        )
+        .into()
+    }
+}
+
+/// Used to store input for the leads-to checker
+pub struct LeadsToExecutor {
+    pub system: TransitionSystemPtr,
+    // Every state satisfying the premise must eventually be followed by a state satisfying the response
+    pub premise: State,
+    pub response: State,
+    // A clock not used by the system, which measures whether time diverges on a path
+    pub divergence_clock: ClockIndex,
+}
+
+impl ExecutableQuery for LeadsToExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        temporal::check_leads_to(
+            &self.system,
+            self.premise,
+            self.response,
+            self.divergence_clock,
+        )
+        .into()
+    }
+}
+
+/// Used to store input for replaying a concrete trace
+pub struct ConcreteTraceExecutor {
+    pub system: TransitionSystemPtr,
+    pub steps: Vec<TimedAction>,
+}
+
+impl ExecutableQuery for ConcreteTraceExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let mut simulator = match ConcreteSimulator::new(self.system) {
+            Ok(simulator) => simulator,
+            Err(error) => {
+                return QueryResult::ConcreteTrace(Err(ConcreteTraceFailure { trace: None, error }))
+            }
+        };
+        for step in &self.steps {
+            if let Err(error) = simulator.step(step.delay, &step.action) {
+                return QueryResult::ConcreteTrace(Err(ConcreteTraceFailure {
+                    trace: Some(simulator.timed_trace()),
+                    error,
+                }));
+            }
+        }
+        QueryResult::ConcreteTrace(Ok(simulator.timed_trace()))
+    }
+}
+
+/// Used to store input for checking a recorded trace
+pub struct TraceCheckExecutor {
+    pub system: TransitionSystemPtr,
+    pub events: Vec<TraceEvent>,
+}
+
+impl ExecutableQuery for TraceCheckExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        trace_check::check_trace(&self.system, &self.events)
+            .map(QueryResult::TraceCheck)
+            .into()
+    }
+}
+
+/// Used to store input for automatic simulation
+pub struct SimulationExecutor {
+    pub system: TransitionSystemPtr,
+    pub options: AutomaticSimulationOptions,
+    // The state the simulation is guided towards, if any
+    pub target: Option<State>,
+}
+
+impl ExecutableQuery for SimulationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        automatic::simulate(&self.system, &self.options, self.target.as_ref())
+            .map(QueryResult::Simulation)
+            .into()
+    }
+}
+
+/// Used to store input for generating test cases from a specification
+pub struct TestGenerationExecutor {
+    pub system: TransitionSystemPtr,
+    // A clock the specification does not use
+    pub test_clock: ClockIndex,
+    pub options: TestGenerationOptions,
+}
+
+impl ExecutableQuery for TestGenerationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        test_generation::generate_tests(&self.system, self.test_clock, &self.options)
+            .map(QueryResult::TestCases)
             .into()
     }
 }
@@ -137,3 +442,27 @@ impl ExecutableQuery for DeterminismExecutor {
         self.system.check_determinism().into()
     }
 }
+
+pub struct ImplementationExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for ImplementationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        local_consistency::check_implementation(self.system.as_ref()).into()
+    }
+}
+
+pub struct SpecificationExecutor {
+    pub system: TransitionSystemPtr,
+}
+
+impl ExecutableQuery for SpecificationExecutor {
+    fn execute(self: Box<Self>) -> QueryResult {
+        let determinism = self.system.check_determinism();
+        // Checked separately from determinism, so that both violations can be reported
+        let consistency = self.system.check_local_consistency();
+
+        SpecificationFailure::from_results(consistency, determinism).into()
+    }
+}
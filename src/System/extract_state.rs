@@ -135,6 +135,10 @@ fn construct_location_tree(
                 &construct_location_tree(locations, right, right_system)?,
             ))
         }
+        // Hiding and renaming keep the locations of the system they wrap
+        SystemRecipe::Hiding(system_recipe, _) | SystemRecipe::Renaming(system_recipe, _) => {
+            construct_location_tree(locations, system_recipe, system)
+        }
         SystemRecipe::Component(component) => {
             match locations.iter().find(|loc| {
                 loc.component == component.name && loc.special_id == component.special_id
@@ -142,6 +146,16 @@ fn construct_location_tree(
                 None => Ok(LocationTree::build_any_location_tree()),
                 Some(var) => system
                     .get_location(&LocationID::Simple(var.variable.clone()))
+                    .or_else(|| {
+                        // Locations of components with integer variables are matched for any valuation
+                        system
+                            .get_all_locations()
+                            .iter()
+                            .any(|loc| {
+                                matches!(&loc.id, LocationID::Valued(id, _) if *id == var.variable)
+                            })
+                            .then(|| LocationTree::build_any_valuation_tree(var.variable.clone()))
+                    })
                     .ok_or(format!(
                         "Location {:?} does not exist in the component",
                         var,
@@ -1,18 +1,23 @@
 use crate::DataReader::component_loader::ComponentLoader;
 use crate::ModelObjects::Expressions::{QueryExpression, SaveExpression, SystemExpression};
 use crate::ModelObjects::{Component, Query, State};
+use crate::Simulation::trace_check;
 use crate::System::executable_query::{
-    ConsistencyExecutor, DeterminismExecutor, ExecutableQuery, GetComponentExecutor,
-    ReachabilityExecutor, RefinementExecutor,
+    ConcreteTraceExecutor, ConsistencyExecutor, DeterminismExecutor, ExecutableQuery,
+    GetComponentExecutor, ImplementationExecutor, LeadsToExecutor, ReachabilityExecutor,
+    RefinementExecutor, SimulationExecutor, SpecificationExecutor, StrategyExecutor,
+    TemporalExecutor, TestGenerationExecutor, TraceCheckExecutor,
 };
 use crate::System::extract_state::get_state;
 use std::collections::HashMap;
 
 use crate::TransitionSystems::{
-    CompiledComponent, Composition, Conjunction, Quotient, TransitionSystemPtr,
+    CompiledComponent, Composition, Conjunction, Hiding, Quotient, Renaming, Restriction,
+    TransitionSystemPtr,
 };
 
 use super::query_failures::SystemRecipeFailure;
+use crate::System::bisimulation;
 use crate::System::pruning;
 use crate::TransitionSystems::transition_system::ClockReductionInstruction;
 use edbm::util::constraints::ClockIndex;
@@ -37,6 +42,17 @@ impl<T: Into<String>> From<T> for ExecutableQueryError {
     }
 }
 
+impl std::fmt::Display for ExecutableQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutableQueryError::SystemRecipeFailure(failure) => write!(f, "{}", failure),
+            ExecutableQueryError::Custom(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExecutableQueryError {}
+
 /// This function fetches the appropriate components based on the structure of the query and makes the enum structure match the query
 /// this function also handles setting up the correct indices for clocks based on the amount of components in each system representation
 pub fn create_executable_query<'a>(
@@ -47,13 +63,14 @@ pub fn create_executable_query<'a>(
 
     if let Some(query) = full_query.get_query() {
         match query {
-            QueryExpression::Refinement(left_side, right_side) => {
+            QueryExpression::Refinement(left_side, right_side)
+            | QueryExpression::Strategy(left_side, right_side) => {
                 let mut quotient_index = None;
 
                 let mut left =
-                    get_system_recipe(left_side, component_loader, &mut dim, &mut quotient_index);
+                    get_system_recipe(left_side, component_loader, &mut dim, &mut quotient_index)?;
                 let mut right =
-                    get_system_recipe(right_side, component_loader, &mut dim, &mut quotient_index);
+                    get_system_recipe(right_side, component_loader, &mut dim, &mut quotient_index)?;
 
                 if !component_loader.get_settings().disable_clock_reduction {
                     clock_reduction::clock_reduce(
@@ -65,14 +82,35 @@ pub fn create_executable_query<'a>(
                 }
 
                 let mut component_index = 0;
+                let mut sys1 = left.compile_with_index(dim, &mut component_index)?;
+                let mut sys2 = right.compile_with_index(dim, &mut component_index)?;
+
+                // The actions ignored in the query are removed from both sides, so the refinement holds modulo them
+                let ignored_inputs = full_query.get_ignored_inputs();
+                let ignored_outputs = full_query.get_ignored_outputs();
+                if !ignored_inputs.is_empty() || !ignored_outputs.is_empty() {
+                    debug!(
+                        "Ignoring inputs {:?} and outputs {:?}",
+                        ignored_inputs, ignored_outputs
+                    );
+                    sys1 = Restriction::new_ts(sys1, &ignored_inputs, &ignored_outputs);
+                    sys2 = Restriction::new_ts(sys2, &ignored_inputs, &ignored_outputs);
+                }
 
-                Ok(Box::new(RefinementExecutor {
-                    sys1: left.compile_with_index(dim, &mut component_index)?,
-                    sys2: right.compile_with_index(dim, &mut component_index)?,
-                }))
+                if let QueryExpression::Strategy(..) = query {
+                    Ok(Box::new(StrategyExecutor { sys1, sys2 }))
+                } else {
+                    let settings = component_loader.get_settings();
+                    Ok(Box::new(RefinementExecutor {
+                        sys1,
+                        sys2,
+                        parallel: settings.parallel_refinement,
+                        build_relation: settings.save_refinement_relations,
+                    }))
+                }
             }
             QueryExpression::Reachability { system, from, to } => {
-                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None);
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
                 let transition_system = machine.clone().compile(dim)?;
 
                 // Assign the start state to the initial state of the transition system if no start state is given by the query
@@ -97,6 +135,105 @@ pub fn create_executable_query<'a>(
                     transition_system,
                     start_state,
                     end_state,
+                    parallel: component_loader.get_settings().parallel_reachability,
+                }))
+            }
+            QueryExpression::Temporal {
+                operator,
+                system,
+                state,
+            } => {
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                // Compiled with an extra clock, the last one, which measures whether time diverges
+                let divergence_clock = dim + 1;
+                let transition_system = machine.clone().compile(divergence_clock)?;
+
+                let predicate = get_state(state, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid state predicate: {}", err))?;
+
+                Ok(Box::new(TemporalExecutor {
+                    operator: *operator,
+                    system: transition_system,
+                    predicate,
+                    divergence_clock,
+                }))
+            }
+            QueryExpression::LeadsTo {
+                system,
+                premise,
+                response,
+            } => {
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                // Compiled with an extra clock, the last one, which measures whether time diverges
+                let divergence_clock = dim + 1;
+                let transition_system = machine.clone().compile(divergence_clock)?;
+
+                let premise = get_state(premise, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid premise: {}", err))?;
+                let response = get_state(response, &machine, &transition_system)
+                    .map_err(|err| format!("Invalid response: {}", err))?;
+
+                Ok(Box::new(LeadsToExecutor {
+                    system: transition_system,
+                    premise,
+                    response,
+                    divergence_clock,
+                }))
+            }
+            QueryExpression::ConcreteTrace { system, steps } => {
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                let transition_system = machine.compile(dim)?;
+
+                Ok(Box::new(ConcreteTraceExecutor {
+                    system: transition_system,
+                    steps: steps.clone(),
+                }))
+            }
+            QueryExpression::TraceCheck { system, trace_file } => {
+                let events = match &full_query.trace {
+                    Some(contents) => trace_check::parse_trace(trace_file, contents)?,
+                    None => trace_check::read_trace_file(trace_file)?,
+                };
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                let transition_system = machine.compile(dim)?;
+
+                Ok(Box::new(TraceCheckExecutor {
+                    system: transition_system,
+                    events,
+                }))
+            }
+            QueryExpression::Simulate {
+                system,
+                options,
+                target,
+            } => {
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                let transition_system = machine.clone().compile(dim)?;
+
+                let target = match target {
+                    Some(target) => Some(
+                        get_state(target, &machine, &transition_system)
+                            .map_err(|err| format!("Invalid target: {}", err))?,
+                    ),
+                    None => None,
+                };
+
+                Ok(Box::new(SimulationExecutor {
+                    system: transition_system,
+                    options: options.clone(),
+                    target,
+                }))
+            }
+            QueryExpression::TestGeneration { system, options } => {
+                let machine = get_system_recipe(system, component_loader, &mut dim, &mut None)?;
+                // Compiled with an extra clock, the last one, which the test cases use to measure time
+                let test_clock = dim + 1;
+                let transition_system = machine.compile(test_clock)?;
+
+                Ok(Box::new(TestGenerationExecutor {
+                    system: transition_system,
+                    test_clock,
+                    options: options.clone(),
                 }))
             }
             QueryExpression::Consistency(query_expression) => {
@@ -106,7 +243,7 @@ pub fn create_executable_query<'a>(
                     component_loader,
                     &mut dim,
                     &mut quotient_index,
-                );
+                )?;
 
                 if !component_loader.get_settings().disable_clock_reduction {
                     clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
@@ -123,7 +260,7 @@ pub fn create_executable_query<'a>(
                     component_loader,
                     &mut dim,
                     &mut quotient_index,
-                );
+                )?;
 
                 if !component_loader.get_settings().disable_clock_reduction {
                     clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
@@ -133,10 +270,44 @@ pub fn create_executable_query<'a>(
                     system: recipe.compile(dim)?,
                 }))
             }
+            QueryExpression::Implementation(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                )?;
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
+                }
+
+                Ok(Box::new(ImplementationExecutor {
+                    system: recipe.compile(dim)?,
+                }))
+            }
+            QueryExpression::Specification(query_expression) => {
+                let mut quotient_index = None;
+                let mut recipe = get_system_recipe(
+                    query_expression,
+                    component_loader,
+                    &mut dim,
+                    &mut quotient_index,
+                )?;
+
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
+                }
+
+                Ok(Box::new(SpecificationExecutor {
+                    system: recipe.compile(dim)?,
+                }))
+            }
             QueryExpression::GetComponent(SaveExpression { system, name }) => {
                 let mut quotient_index = None;
                 let mut recipe =
-                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index);
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)?;
 
                 if !component_loader.get_settings().disable_clock_reduction {
                     clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
@@ -151,7 +322,7 @@ pub fn create_executable_query<'a>(
             QueryExpression::Prune(SaveExpression { system, name }) => {
                 let mut quotient_index = None;
                 let mut recipe =
-                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index);
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)?;
 
                 if !component_loader.get_settings().disable_clock_reduction {
                     clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
@@ -163,9 +334,21 @@ pub fn create_executable_query<'a>(
                     component_loader,
                 }))
             }
+            QueryExpression::BisimMinim(SaveExpression { system, name }) => {
+                let mut quotient_index = None;
+                let mut recipe =
+                    get_system_recipe(system, component_loader, &mut dim, &mut quotient_index)?;
 
-            // Should handle consistency, Implementation, determinism and specification here, but we cant deal with it atm anyway
-            _ => bail!("Not yet setup to handle query"),
+                if !component_loader.get_settings().disable_clock_reduction {
+                    clock_reduction::clock_reduce(&mut recipe, None, &mut dim, quotient_index)?;
+                }
+
+                Ok(Box::new(GetComponentExecutor {
+                    system: bisimulation::minimise_system_locations(recipe.compile(dim)?, dim)?,
+                    comp_name: name.clone().unwrap_or("Unnamed".to_string()),
+                    component_loader,
+                }))
+            }
         }
     } else {
         bail!("No query was supplied for extraction")
@@ -177,6 +360,8 @@ pub enum SystemRecipe {
     Composition(Box<SystemRecipe>, Box<SystemRecipe>),
     Conjunction(Box<SystemRecipe>, Box<SystemRecipe>),
     Quotient(Box<SystemRecipe>, Box<SystemRecipe>, ClockIndex),
+    Hiding(Box<SystemRecipe>, Vec<String>),
+    Renaming(Box<SystemRecipe>, Vec<(String, String)>),
     Component(Box<Component>),
 }
 
@@ -216,6 +401,13 @@ impl SystemRecipe {
                 clock_index,
                 dim,
             ),
+            SystemRecipe::Hiding(system, actions) => Ok(Hiding::new_ts(
+                system._compile(dim, component_index)?,
+                &actions,
+            )),
+            SystemRecipe::Renaming(system, renamings) => {
+                Renaming::new_ts(system._compile(dim, component_index)?, &renamings)
+            }
             SystemRecipe::Component(comp) => {
                 CompiledComponent::compile(*comp, dim, component_index)
                     .map(|comp| comp as TransitionSystemPtr)
@@ -231,6 +423,9 @@ impl SystemRecipe {
             | SystemRecipe::Quotient(left, right, _) => {
                 left.get_component_count() + right.get_component_count()
             }
+            SystemRecipe::Hiding(system, _) | SystemRecipe::Renaming(system, _) => {
+                system.get_component_count()
+            }
             SystemRecipe::Component(_) => 1,
         }
     }
@@ -270,6 +465,9 @@ impl SystemRecipe {
                 o.extend(right.get_components_mut());
                 o
             }
+            SystemRecipe::Hiding(system, _) | SystemRecipe::Renaming(system, _) => {
+                system.get_components_mut()
+            }
             SystemRecipe::Component(c) => vec![c],
         }
     }
@@ -283,6 +481,9 @@ impl SystemRecipe {
                 o.extend(right.get_components());
                 o
             }
+            SystemRecipe::Hiding(system, _) | SystemRecipe::Renaming(system, _) => {
+                system.get_components()
+            }
             SystemRecipe::Component(c) => vec![c],
         }
     }
@@ -298,29 +499,34 @@ impl SystemRecipe {
                 l.change_quotient(index);
                 r.change_quotient(index);
             }
+            SystemRecipe::Hiding(system, _) | SystemRecipe::Renaming(system, _) => {
+                system.change_quotient(index);
+            }
             SystemRecipe::Component(_) => (),
         }
     }
 }
 
+/// Builds the [SystemRecipe] of the system expression `side`, where systems of the project are replaced by their definitions.
+/// Returns an error if a system of the project used in `side` is cyclic, has a missing instance or is malformed.
 pub fn get_system_recipe(
     side: &SystemExpression,
     component_loader: &mut dyn ComponentLoader,
     clock_index: &mut ClockIndex,
     quotient_index: &mut Option<ClockIndex>,
-) -> Box<SystemRecipe> {
-    match side {
-        SystemExpression::Composition(left, right) => Box::new(SystemRecipe::Composition(
-            get_system_recipe(left, component_loader, clock_index, quotient_index),
-            get_system_recipe(right, component_loader, clock_index, quotient_index),
-        )),
-        SystemExpression::Conjunction(left, right) => Box::new(SystemRecipe::Conjunction(
-            get_system_recipe(left, component_loader, clock_index, quotient_index),
-            get_system_recipe(right, component_loader, clock_index, quotient_index),
-        )),
+) -> Result<Box<SystemRecipe>, ExecutableQueryError> {
+    let recipe = match side {
+        SystemExpression::Composition(left, right) => SystemRecipe::Composition(
+            get_system_recipe(left, component_loader, clock_index, quotient_index)?,
+            get_system_recipe(right, component_loader, clock_index, quotient_index)?,
+        ),
+        SystemExpression::Conjunction(left, right) => SystemRecipe::Conjunction(
+            get_system_recipe(left, component_loader, clock_index, quotient_index)?,
+            get_system_recipe(right, component_loader, clock_index, quotient_index)?,
+        ),
         SystemExpression::Quotient(left, right) => {
-            let left = get_system_recipe(left, component_loader, clock_index, quotient_index);
-            let right = get_system_recipe(right, component_loader, clock_index, quotient_index);
+            let left = get_system_recipe(left, component_loader, clock_index, quotient_index)?;
+            let right = get_system_recipe(right, component_loader, clock_index, quotient_index)?;
 
             let q_index = match quotient_index {
                 Some(q_i) => *q_i,
@@ -333,17 +539,46 @@ pub fn get_system_recipe(
                 }
             };
 
-            Box::new(SystemRecipe::Quotient(left, right, q_index))
+            SystemRecipe::Quotient(left, right, q_index)
         }
+        SystemExpression::Hiding(system, actions) => SystemRecipe::Hiding(
+            get_system_recipe(system, component_loader, clock_index, quotient_index)?,
+            actions.clone(),
+        ),
+        SystemExpression::Renaming(system, renamings) => SystemRecipe::Renaming(
+            get_system_recipe(system, component_loader, clock_index, quotient_index)?,
+            renamings.clone(),
+        ),
         SystemExpression::Component(name, id) => {
+            // Systems of the project are used by name like components
+            match component_loader.get_system(name) {
+                Some(Ok(system)) => {
+                    debug!("{} System: {}", name, system);
+                    return get_system_recipe(
+                        &system,
+                        component_loader,
+                        clock_index,
+                        quotient_index,
+                    );
+                }
+                Some(Err(err)) => {
+                    return Err(ExecutableQueryError::Custom(format!(
+                        "The system '{}' could not be retrieved: {}",
+                        name, err
+                    )))
+                }
+                None => {}
+            }
+
             let mut component = component_loader.get_component(name).clone();
             component.set_clock_indices(clock_index);
             component.special_id = id.clone();
             debug!("{} Clocks: {:?}", name, component.declarations.clocks);
 
-            Box::new(SystemRecipe::Component(Box::new(component)))
+            SystemRecipe::Component(Box::new(component))
         }
-    }
+    };
+    Ok(Box::new(recipe))
 }
 
 /// Module containing a "safer" function for clock reduction, along with some helper functions
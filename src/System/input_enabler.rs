@@ -5,6 +5,10 @@ use crate::ModelObjects::Expressions::BoolExpression;
 use crate::ModelObjects::{Component, DeclarationProvider, Edge, SyncType};
 
 pub fn make_input_enabled(component: &mut Component, inputs: &[String]) {
+    if !component.declarations.ints.is_empty() {
+        make_valued_input_enabled(component, inputs);
+        return;
+    }
     let dimension = component.declarations.get_clock_count() + 1;
     let mut new_edges: Vec<Edge> = vec![];
     let input_edges = component
@@ -92,3 +96,29 @@ pub fn make_input_enabled(component: &mut Component, inputs: &[String]) {
 
     component.edges.append(&mut new_edges);
 }
+
+/// The enabled inputs of a component with integer variables depend on the valuation, so only the inputs
+/// without any edges are added here, and the rest are enabled when the component is compiled.
+fn make_valued_input_enabled(component: &mut Component, inputs: &[String]) {
+    let missing_inputs: Vec<&String> = inputs
+        .iter()
+        .filter(|input| !component.edges.iter().any(|edge| edge.sync == **input))
+        .collect();
+
+    let mut new_edges: Vec<Edge> = vec![];
+    for location in &component.locations {
+        for input in &missing_inputs {
+            new_edges.push(Edge {
+                id: format!("input_{}_{}", location.id, input),
+                source_location: location.id.to_string(),
+                target_location: location.id.to_string(),
+                sync_type: SyncType::Input,
+                guard: None,
+                update: None,
+                sync: input.to_string(),
+            });
+        }
+    }
+
+    component.edges.append(&mut new_edges);
+}
@@ -0,0 +1,171 @@
+//! Limits on the state space stored by a query, so a single query cannot exhaust the memory of the process.
+//!
+//! The limits are part of the [CancellationToken](super::cancellation::CancellationToken) of the query, and the lists storing states report to them with [record_state], [release_state] and [check_zones_per_location].
+//! When a limit is exceeded, these return an error which the search passes on like that of a cancelled query, see [cancellation](super::cancellation).
+//! The statistics of the stored states are kept for every query, also when it has no limits.
+use edbm::zones::OwnedFederation;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::cancellation::{self, Interrupted};
+use crate::ProtobufServer::services::query_request::Settings;
+
+/// The limits of a query, where 0 means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplorationLimits {
+    /// The maximal number of states stored in the passed and waiting lists at once
+    pub max_states: usize,
+    /// The maximal number of zones stored for a single location
+    pub max_zones_per_location: usize,
+    /// The approximate maximal number of bytes used by the stored zones
+    pub max_bytes: usize,
+}
+
+impl ExplorationLimits {
+    pub fn from_settings(settings: &Settings) -> Self {
+        ExplorationLimits {
+            max_states: settings.max_states as usize,
+            max_zones_per_location: settings.max_zones_per_location as usize,
+            max_bytes: settings.max_memory_bytes as usize,
+        }
+    }
+}
+
+/// The limit which was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    States(usize),
+    ZonesPerLocation(usize),
+    Bytes(usize),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::States(max) => write!(f, "at most {} states", max),
+            Limit::ZonesPerLocation(max) => write!(f, "at most {} zones per location", max),
+            Limit::Bytes(max) => write!(f, "at most approximately {} bytes", max),
+        }
+    }
+}
+
+/// How much a query had stored when it was stopped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExplorationStatistics {
+    pub states: usize,
+    pub approximate_bytes: usize,
+}
+
+/// The result of a query which was stopped because it exceeded one of its limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitReached {
+    pub limit: Limit,
+    pub statistics: ExplorationStatistics,
+}
+
+impl fmt::Display for LimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "inconclusive: limit reached, the query may store {} but stored {} states using approximately {} bytes",
+            self.limit, self.statistics.states, self.statistics.approximate_bytes
+        )
+    }
+}
+
+/// The limits of a query along with how much it has stored, shared between the threads running the query
+#[derive(Debug, Default)]
+pub(super) struct ExplorationBudget {
+    limits: ExplorationLimits,
+    states: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+impl ExplorationBudget {
+    pub fn new(limits: ExplorationLimits) -> Self {
+        ExplorationBudget {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    pub fn statistics(&self) -> ExplorationStatistics {
+        ExplorationStatistics {
+            states: self.states.load(Ordering::Relaxed),
+            approximate_bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn exceeded(&self, limit: Limit) -> Interrupted {
+        Interrupted::LimitReached(LimitReached {
+            limit,
+            statistics: self.statistics(),
+        })
+    }
+
+    pub fn record_state(&self, bytes: usize) -> Result<(), Interrupted> {
+        let states = self.states.fetch_add(1, Ordering::Relaxed) + 1;
+        let total_bytes = self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+
+        let max = self.limits.max_states;
+        if max != 0 && states > max {
+            return Err(self.exceeded(Limit::States(max)));
+        }
+        let max = self.limits.max_bytes;
+        if max != 0 && total_bytes > max {
+            return Err(self.exceeded(Limit::Bytes(max)));
+        }
+        Ok(())
+    }
+
+    pub fn release_state(&self, bytes: usize) {
+        self.states.fetch_sub(1, Ordering::Relaxed);
+        self.bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    pub fn check_zones_per_location(&self, zones: usize) -> Result<(), Interrupted> {
+        let max = self.limits.max_zones_per_location;
+        if max != 0 && zones > max {
+            return Err(self.exceeded(Limit::ZonesPerLocation(max)));
+        }
+        Ok(())
+    }
+}
+
+/// The approximate number of bytes used to store `zone`, counting it as a single DBM
+pub fn approximate_zone_bytes(zone: &OwnedFederation) -> usize {
+    let dim = zone.dim();
+    dim * dim * std::mem::size_of::<i32>()
+}
+
+/// Records that the query running on the current thread stored another state with `zone`,
+/// returning an error if the query exceeds its limits.
+pub fn record_state(zone: &OwnedFederation) -> Result<(), Interrupted> {
+    match cancellation::current_token() {
+        Some(token) => token
+            .budget()
+            .record_state(approximate_zone_bytes(zone))
+            .map_err(|interrupted| token.interrupt(interrupted)),
+        None => Ok(()),
+    }
+}
+
+/// Records that the query running on the current thread no longer stores a state with `zone`,
+/// e.g. as it is moved from a waiting list to a passed list which records it again.
+pub fn release_state(zone: &OwnedFederation) {
+    if let Some(token) = cancellation::current_token() {
+        token.budget().release_state(approximate_zone_bytes(zone));
+    }
+}
+
+/// Checks that the query running on the current thread stores at most its maximal number of zones in a location,
+/// returning an error if the query stores `zones`.
+pub fn check_zones_per_location(zones: usize) -> Result<(), Interrupted> {
+    match cancellation::current_token() {
+        Some(token) => token
+            .budget()
+            .check_zones_per_location(zones)
+            .map_err(|interrupted| token.interrupt(interrupted)),
+        None => Ok(()),
+    }
+}
@@ -1,18 +1,26 @@
+use edbm::util::constraints::{
+    ClockIndex, Conjunction, Constraint, Disjunction, Inequality, RawInequality,
+};
 use edbm::zones::OwnedFederation;
 use log::warn;
 
 use crate::ModelObjects::State;
-use crate::System::query_failures::{ConsistencyFailure, DeterminismFailure};
+use crate::Simulation::decision::Decision;
+use crate::System::cancellation;
+use crate::System::limits;
+use crate::System::query_failures::{
+    ConsistencyFailure, DeterminismFailure, ImplementationFailure,
+};
 use crate::TransitionSystems::TransitionSystem;
 
-use super::query_failures::{ConsistencyResult, DeterminismResult};
+use super::query_failures::{ConsistencyResult, DeterminismResult, ImplementationResult};
 
 ///Local consistency check WITH pruning.
 pub fn is_least_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
     if let Some(mut state) = system.get_initial_state() {
         let mut passed = vec![];
         state.extrapolate_max_bounds(system);
-        consistency_least_helper(state, &mut passed, system)
+        consistency_least_helper(state, &mut passed, &mut vec![], system)
     } else {
         warn!("Empty initial state");
         ConsistencyFailure::no_initial_state(system)
@@ -28,19 +36,28 @@ pub fn check_determinism(system: &dyn TransitionSystem) -> DeterminismResult {
     }
     let mut state = state.unwrap();
     state.update_zone(|_| OwnedFederation::universe(system.get_dim()));
-    is_deterministic_helper(state, &mut passed, system)
+    is_deterministic_helper(state, &mut passed, &mut vec![], system)
 }
 
+/// Searches for a non-deterministic state reachable from `state`, which is reached by the decisions in `trace`
 fn is_deterministic_helper(
     state: State,
     passed_list: &mut Vec<State>,
+    trace: &mut Vec<Decision>,
     system: &dyn TransitionSystem,
 ) -> DeterminismResult {
+    // Stops exploring if the query is interrupted or exceeds its limits, as run_cancellable then discards the result
+    if cancellation::checkpoint().is_err() {
+        return Ok(());
+    }
     if state.is_contained_in_list(passed_list) {
         return Ok(());
     }
 
     passed_list.push(state.clone());
+    if limits::record_state(state.zone_ref()).is_err() {
+        return Ok(());
+    }
 
     for action in system.get_actions() {
         let mut location_fed = OwnedFederation::empty(system.get_dim());
@@ -54,12 +71,17 @@ fn is_deterministic_helper(
                         "Not deterministic from location {} failing action {}",
                         state.decorated_locations.id, action
                     );
-                    return DeterminismFailure::from_system_and_action(system, action, &state);
+                    return DeterminismFailure::from_system_and_action(
+                        system, action, &state, trace,
+                    );
                 }
                 location_fed += allowed_fed;
                 new_state.extrapolate_max_bounds(system);
 
-                is_deterministic_helper(new_state, passed_list, system)?;
+                trace.push(Decision::taking(&state, &action, transition, &new_state));
+                let result = is_deterministic_helper(new_state, passed_list, trace, system);
+                trace.pop();
+                result?;
             }
         }
     }
@@ -74,14 +96,19 @@ pub fn is_fully_consistent(system: &dyn TransitionSystem) -> ConsistencyResult {
         warn!("Empty initial state");
         return ConsistencyFailure::no_initial_state(system);
     }
-    consistency_fully_helper(state.unwrap(), &mut passed, system)
+    consistency_fully_helper(state.unwrap(), &mut passed, &mut vec![], system)
 }
 
+/// Checks that `state`, which is reached by the decisions in `trace`, is consistent when pruning inconsistent states
 pub fn consistency_least_helper(
     state: State,
     passed_list: &mut Vec<State>,
+    trace: &mut Vec<Decision>,
     system: &dyn TransitionSystem,
 ) -> ConsistencyResult {
+    if cancellation::checkpoint().is_err() {
+        return Ok(());
+    }
     if state.is_contained_in_list(passed_list) {
         return Ok(());
     }
@@ -89,10 +116,13 @@ pub fn consistency_least_helper(
         return Ok(());
     }
     if state.decorated_locations.is_inconsistent() {
-        return ConsistencyFailure::inconsistent(system, &state);
+        return ConsistencyFailure::inconsistent(system, &state, trace);
     }
 
     passed_list.push(state.clone());
+    if limits::record_state(state.zone_ref()).is_err() {
+        return Ok(());
+    }
 
     for input in system.get_input_actions() {
         for transition in &system.next_inputs(&state.decorated_locations, &input) {
@@ -100,7 +130,10 @@ pub fn consistency_least_helper(
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
 
-                consistency_least_helper(new_state, passed_list, system)?;
+                trace.push(Decision::taking(&state, &input, transition, &new_state));
+                let result = consistency_least_helper(new_state, passed_list, trace, system);
+                trace.pop();
+                result?;
             }
         }
     }
@@ -114,25 +147,36 @@ pub fn consistency_least_helper(
             let mut new_state = state.clone();
             if transition.use_transition(&mut new_state) {
                 new_state.extrapolate_max_bounds(system);
-                if let Ok(()) = consistency_least_helper(new_state, passed_list, system) {
+                trace.push(Decision::taking(&state, &output, &transition, &new_state));
+                let result = consistency_least_helper(new_state, passed_list, trace, system);
+                trace.pop();
+                if let Ok(()) = result {
                     return Ok(());
                 }
             }
         }
     }
     warn!("No saving outputs from {}", state.decorated_locations.id);
-    ConsistencyFailure::inconsistent_from(system, &state)
+    ConsistencyFailure::inconsistent_from(system, &state, trace)
 }
 
+/// Checks that `state`, which is reached by the decisions in `trace`, is consistent without pruning
 fn consistency_fully_helper(
     state: State,
     passed_list: &mut Vec<State>,
+    trace: &mut Vec<Decision>,
     system: &dyn TransitionSystem,
 ) -> ConsistencyResult {
+    if cancellation::checkpoint().is_err() {
+        return Ok(());
+    }
     if state.is_contained_in_list(passed_list) {
         return Ok(());
     }
     passed_list.push(state.clone());
+    if limits::record_state(state.zone_ref()).is_err() {
+        return Ok(());
+    }
 
     for input in system.get_input_actions() {
         for transition in system.next_inputs(&state.decorated_locations, &input) {
@@ -142,7 +186,10 @@ fn consistency_fully_helper(
                 if new_state.is_subset_of(&state) {
                     continue;
                 }
-                consistency_fully_helper(new_state, passed_list, system)?;
+                trace.push(Decision::taking(&state, &input, &transition, &new_state));
+                let result = consistency_fully_helper(new_state, passed_list, trace, system);
+                trace.pop();
+                result?;
             }
         }
     }
@@ -159,7 +206,10 @@ fn consistency_fully_helper(
 
                 output_existed = true;
 
-                consistency_fully_helper(new_state, passed_list, system)?;
+                trace.push(Decision::taking(&state, &output, &transition, &new_state));
+                let result = consistency_fully_helper(new_state, passed_list, trace, system);
+                trace.pop();
+                result?;
             }
         }
     }
@@ -168,8 +218,120 @@ fn consistency_fully_helper(
     } else {
         let last_state = passed_list.last().unwrap();
         match last_state.zone_ref().can_delay_indefinitely() {
-            false => ConsistencyFailure::inconsistent_from(system, &state),
+            false => ConsistencyFailure::inconsistent_from(system, &state, trace),
             true => Ok(()),
         }
     }
 }
+
+///Checks if a [TransitionSystem] is an implementation, i.e. a specification which is output urgent and has independent progress.
+pub fn check_implementation(system: &dyn TransitionSystem) -> ImplementationResult {
+    system.precheck_sys_rep()?;
+
+    if let Some(mut state) = system.get_initial_state() {
+        let mut passed = vec![];
+        state.extrapolate_max_bounds(system);
+        implementation_helper(state, &mut passed, system)
+    } else {
+        warn!("Empty initial state");
+        ConsistencyFailure::no_initial_state(system).map_err(ImplementationFailure::from)
+    }
+}
+
+fn implementation_helper(
+    state: State,
+    passed_list: &mut Vec<State>,
+    system: &dyn TransitionSystem,
+) -> ImplementationResult {
+    if cancellation::checkpoint().is_err() {
+        return Ok(());
+    }
+    if state.is_contained_in_list(passed_list) {
+        return Ok(());
+    }
+    passed_list.push(state.clone());
+    if limits::record_state(state.zone_ref()).is_err() {
+        return Ok(());
+    }
+
+    let dim = system.get_dim();
+    let location = &state.decorated_locations;
+    let delayable = delayable_federation(location.get_invariants(), dim);
+
+    let mut output_fed = OwnedFederation::empty(dim);
+    for output in system.get_output_actions() {
+        for transition in &system.next_outputs(location, &output) {
+            let allowed = location.apply_invariants(transition.get_allowed_federation());
+            let enabled = allowed.clone().intersection(state.zone_ref());
+            if enabled.has_intersection(&delayable) {
+                warn!(
+                    "Not output urgent in location {} for action {}",
+                    location.id, output
+                );
+                return ImplementationFailure::not_output_urgent(system, output, &state);
+            }
+            output_fed += allowed;
+        }
+    }
+
+    if let Some(invariant) = location.get_invariants() {
+        // The clock valuations which can delay forever without violating the invariant
+        let violating = OwnedFederation::universe(dim).subtraction(invariant).down();
+        let unbounded = OwnedFederation::universe(dim).subtraction(&violating);
+        let progress = unbounded.union(&output_fed.down());
+        if !state.zone_ref().clone().subtraction(&progress).is_empty() {
+            warn!("No independent progress from location {}", location.id);
+            return ImplementationFailure::no_independent_progress(system, &state);
+        }
+    }
+
+    for action in system.get_actions() {
+        for transition in &system.next_transitions(location, &action) {
+            let mut new_state = state.clone();
+            if transition.use_transition(&mut new_state) {
+                new_state.extrapolate_max_bounds(system);
+                implementation_helper(new_state, passed_list, system)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the clock valuations satisfying `invariant` from which a strictly positive delay is possible.
+fn delayable_federation(invariant: Option<&OwnedFederation>, dim: ClockIndex) -> OwnedFederation {
+    let invariant = match invariant {
+        Some(invariant) => invariant,
+        None => return OwnedFederation::universe(dim),
+    };
+
+    // Make every upper bound on a clock strict, so the valuations on the boundary are excluded
+    let conjunctions = invariant
+        .minimal_constraints()
+        .conjunctions
+        .into_iter()
+        .map(|conjunction| {
+            Conjunction::new(
+                conjunction
+                    .constraints
+                    .into_iter()
+                    .map(|constraint| {
+                        let ineq = constraint.ineq();
+                        if constraint.j == 0 && constraint.i != 0 {
+                            let strict = Inequality::LS(ineq.bound());
+                            Constraint::new(
+                                constraint.i,
+                                constraint.j,
+                                RawInequality::from_inequality(&strict),
+                            )
+                        } else {
+                            constraint
+                        }
+                    })
+                    .collect(),
+            )
+        })
+        .collect();
+    let strict = OwnedFederation::from_disjunction(&Disjunction::new(conjunctions), dim);
+
+    strict.down().intersection(invariant)
+}
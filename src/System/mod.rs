@@ -1,11 +1,18 @@
+pub mod bisimulation;
+pub mod cancellation;
 pub mod executable_query;
 pub mod extract_state;
 pub mod extract_system_rep;
 pub mod input_enabler;
+pub mod limits;
 pub mod local_consistency;
+pub mod parallel_reachability;
+pub mod parallel_refine;
 pub mod pruning;
 pub mod query_failures;
 pub mod reachability;
 pub mod refine;
+pub mod refinement_strategy;
 pub mod save_component;
 pub mod specifics;
+pub mod temporal;
@@ -0,0 +1,179 @@
+use edbm::util::bounds::Bounds;
+use edbm::zones::OwnedFederation;
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use super::cancellation::{self, CancellationToken, Interrupted};
+use super::limits;
+use super::query_failures::{PathFailure, PathResult};
+use super::reachability::{
+    is_trivially_unreachable, make_path, reached_end_state, remove_existing_subsets_of_zone,
+    zone_subset_of_existing_zones, Path, SubPath,
+};
+use super::specifics::SpecificPath;
+use crate::ModelObjects::State;
+use crate::TransitionSystems::{LocationID, TransitionSystemPtr};
+
+/// The number of shards of the visited states per thread, to reduce lock contention between threads
+const SHARDS_PER_THREAD: usize = 4;
+
+/// The zones visited in each location, sharded by the hash of the location
+struct VisitedStates {
+    shards: Vec<Mutex<HashMap<LocationID, Vec<OwnedFederation>>>>,
+}
+
+impl VisitedStates {
+    fn new(shard_count: usize) -> Self {
+        VisitedStates {
+            shards: (0..shard_count)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+        }
+    }
+
+    /// Marks `state` as visited, returning false if its location has already been visited with a larger zone.
+    /// Returns an error if the query exceeds its limits.
+    fn insert_if_new(&self, state: &State) -> Result<bool, Interrupted> {
+        let location_id = &state.decorated_locations.id;
+        let mut hasher = DefaultHasher::new();
+        location_id.hash(&mut hasher);
+        let shard = &self.shards[hasher.finish() as usize % self.shards.len()];
+
+        let zones = {
+            let mut shard = shard.lock().unwrap();
+            let existing_zones = shard.entry(location_id.clone()).or_default();
+            if zone_subset_of_existing_zones(state.zone_ref(), existing_zones) {
+                return Ok(false);
+            }
+            remove_existing_subsets_of_zone(state.zone_ref(), existing_zones);
+            existing_zones.push(state.zone_ref().clone());
+            existing_zones.len()
+        };
+        // The limits are checked without holding the lock, as the other threads need not wait for them
+        limits::record_state(state.zone_ref())?;
+        limits::check_zones_per_location(zones)?;
+        Ok(true)
+    }
+}
+
+/// Returns a path from a start state to an end state in a transition system like [find_path](super::reachability::find_path),
+/// but explores the states on every thread of the current rayon thread pool.
+pub fn find_path_parallel(
+    start_state: State,
+    end_state: State,
+    system: &TransitionSystemPtr,
+) -> Result<Result<Path, PathFailure>, Interrupted> {
+    if is_trivially_unreachable(&start_state, &end_state) {
+        return Ok(Err(PathFailure::Unreachable));
+    }
+
+    parallel_reachability_search(&start_state, &end_state, system)
+}
+
+pub fn find_specific_path_parallel(
+    start_state: State,
+    end_state: State,
+    system: &TransitionSystemPtr,
+) -> Result<PathResult, Interrupted> {
+    let path = find_path_parallel(start_state, end_state, system)?;
+    Ok(path.map(|p| SpecificPath::from_path(&p, system.as_ref())))
+}
+
+/// Runs a BFS on the transition system one depth at a time, exploring the states of each depth in parallel.
+/// As every state of a depth is checked before the next depth is explored, the path found is as short as the one found by the sequential search,
+/// but it may take other transitions when several shortest paths exist.
+fn parallel_reachability_search(
+    start_state: &State,
+    end_state: &State,
+    system: &TransitionSystemPtr,
+) -> Result<Result<Path, PathFailure>, Interrupted> {
+    // Apply the invariant of the start state to the start state
+    let mut start_state = start_state.clone();
+    start_state.apply_invariants();
+
+    let visited_states = VisitedStates::new(rayon::current_num_threads() * SHARDS_PER_THREAD);
+    visited_states.insert_if_new(&start_state)?;
+
+    let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+    actions.sort();
+
+    let target_bounds = end_state.zone_ref().get_bounds();
+
+    let mut frontier_states = vec![Arc::new(SubPath {
+        previous_sub_path: None,
+        destination_state: start_state.clone(),
+        transition: None,
+    })];
+
+    // The frontier is explored on other threads, so they must be given the cancellation token of this one
+    let token = cancellation::current_token();
+
+    while !frontier_states.is_empty() {
+        cancellation::checkpoint()?;
+        if let Some(sub_path) = frontier_states
+            .par_iter()
+            .find_first(|sub_path| reached_end_state(&sub_path.destination_state, end_state))
+        {
+            return Ok(Ok(make_path(Arc::clone(sub_path), start_state)));
+        }
+
+        let new_sub_paths: Result<Vec<_>, Interrupted> = frontier_states
+            .par_iter()
+            .map(|sub_path| {
+                take_transitions(
+                    sub_path,
+                    &actions,
+                    &visited_states,
+                    system,
+                    &target_bounds,
+                    token.as_ref(),
+                )
+            })
+            .collect();
+        frontier_states = new_sub_paths?.into_iter().flatten().collect();
+    }
+    // If nothing has been found, it is not reachable
+    Ok(Err(PathFailure::Unreachable))
+}
+
+/// Returns the new states reached by taking every transition from the `destination_state` of `sub_path`
+fn take_transitions(
+    sub_path: &Arc<SubPath>,
+    actions: &[String],
+    visited_states: &VisitedStates,
+    system: &TransitionSystemPtr,
+    target_bounds: &Bounds,
+    token: Option<&CancellationToken>,
+) -> Result<Vec<Arc<SubPath>>, Interrupted> {
+    if let Some(token) = token {
+        token.checkpoint()?;
+    }
+
+    // The visited states report to the limits of the current token, so this thread must use the token of the query
+    cancellation::with_token(token.cloned(), || {
+        let mut new_sub_paths = vec![];
+        for action in actions {
+            for transition in
+                &system.next_transitions(&sub_path.destination_state.decorated_locations, action)
+            {
+                let mut new_state = sub_path.destination_state.clone();
+                if transition.use_transition(&mut new_state) {
+                    // Extrapolation ensures the bounds cant grow indefinitely, avoiding infinite loops
+                    new_state
+                        .extrapolate_max_bounds_with_extra_bounds(system.as_ref(), target_bounds);
+                    if visited_states.insert_if_new(&new_state)? {
+                        new_sub_paths.push(Arc::new(SubPath {
+                            previous_sub_path: Some(Arc::clone(sub_path)),
+                            destination_state: new_state,
+                            transition: Some((transition.clone(), action.to_string())),
+                        }));
+                    }
+                }
+            }
+        }
+        Ok(new_sub_paths)
+    })
+}
@@ -0,0 +1,105 @@
+use log::{debug, info, trace};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+use crate::DataTypes::{PassedStateList, PassedStateListExt};
+use crate::ModelObjects::StatePair;
+use crate::TransitionSystems::TransitionSystemPtr;
+
+use super::cancellation::{self, Interrupted};
+use super::query_failures::{RefinementFailure, RefinementResult};
+use super::refine::{
+    check_state_pair, prepare_refinement, RefinementActions, RefinementContext, StatePairStore,
+};
+use super::specifics::SpecificRelation;
+
+/// Collects the state pairs found from a single state pair, which are added to the passed list once their depth is checked
+#[derive(Default)]
+struct FoundPairs(Vec<StatePair>);
+
+impl StatePairStore for FoundPairs {
+    fn add(&mut self, pair: StatePair) -> Result<(), Interrupted> {
+        self.0.push(pair);
+        Ok(())
+    }
+}
+
+/// Checks if sys1 refines sys2 like [check_refinement](super::refine::check_refinement), but explores the state pairs on every thread of the current rayon thread pool.
+///
+/// The state pairs are explored breadth first, one depth at a time.
+/// The state pairs of a depth are checked in parallel, after which the state pairs found from them are added in the order they were found,
+/// so the state pairs explored do not depend on the scheduling of the threads.
+/// When the refinement fails, the failure reported is that of the first failing state pair of the shallowest depth with a failure.
+/// It is the same every time, but may differ from the failure found by the depth first sequential check.
+pub fn check_refinement_parallel(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    build_relation: bool,
+) -> Result<RefinementResult, Interrupted> {
+    let (initial_pair, actions) = match prepare_refinement(&sys1, &sys2) {
+        Ok(Some(prepared)) => prepared,
+        // Both are empty, so trivially true
+        Ok(None) => return Ok(Ok(build_relation.then(SpecificRelation::default))),
+        Err(failure) => return Ok(Err(failure)),
+    };
+
+    // Every state pair found so far, both explored and waiting
+    let mut passed_list = PassedStateList::new();
+    passed_list.put(initial_pair.clone())?;
+    let mut depth = vec![Arc::new(initial_pair)];
+
+    // The state pairs are checked on other threads, so they must be given the cancellation token of this one
+    let token = cancellation::current_token();
+    while !depth.is_empty() {
+        cancellation::checkpoint()?;
+        debug!("Checking {} state pairs in parallel", depth.len());
+
+        let results: Vec<_> = depth
+            .par_iter()
+            .map(|pair| {
+                cancellation::with_token(token.clone(), || {
+                    check_found_pairs(pair, &actions, &sys1, &sys2)
+                })
+            })
+            .collect();
+
+        let mut next_depth = vec![];
+        for result in results {
+            let found_pairs = match result? {
+                Ok(found_pairs) => found_pairs,
+                Err(failure) => return Ok(Err(failure)),
+            };
+            for pair in found_pairs {
+                if !passed_list.has(&pair) {
+                    debug!("New state {}", pair);
+                    passed_list.put(pair.clone())?;
+                    next_depth.push(Arc::new(pair));
+                }
+            }
+        }
+        depth = next_depth;
+    }
+    info!("Refinement check passed");
+
+    Ok(Ok(build_relation.then(|| {
+        SpecificRelation::from_passed_list(&passed_list, sys1.as_ref(), sys2.as_ref())
+    })))
+}
+
+/// Checks `pair` like the sequential check, returning the state pairs found from it
+fn check_found_pairs(
+    pair: &Arc<StatePair>,
+    actions: &RefinementActions,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> Result<Result<Vec<StatePair>, RefinementFailure>, Interrupted> {
+    cancellation::checkpoint()?;
+    trace!("Checking {}", pair);
+
+    let mut context = RefinementContext {
+        store: FoundPairs::default(),
+        sys1,
+        sys2,
+    };
+    Ok(check_state_pair(pair, actions, &mut context)?.map(|()| context.store.0))
+}
@@ -1,12 +1,26 @@
 use std::{collections::HashSet, fmt};
 
+use serde::Serialize;
+
 use crate::ModelObjects::{Component, State, StatePair};
 use crate::TransitionSystems::{CompositionType, TransitionSystem, TransitionSystemPtr};
 
-use super::specifics::{SpecificPath, SpecificState};
+use crate::ModelObjects::Expressions::TemporalOperator;
+use crate::Simulation::automatic::SimulationReport;
+use crate::Simulation::concrete::{
+    ConcreteSimulationError, Rational, SpecificConcreteState, SpecificTimedTrace,
+};
+use crate::Simulation::decision::Decision;
+use crate::Simulation::test_generation::TestSuite;
+use crate::Simulation::trace_check::TraceEvent;
+
+use super::cancellation::Interrupted;
+use super::limits::LimitReached;
+use super::refinement_strategy::RefinementStrategy;
+use super::specifics::{SpecificLasso, SpecificPath, SpecificRelation, SpecificState};
 
 /// Represents how a system is composed at the highest level
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum SystemType {
     /// A refinement between two systems
     Refinement,
@@ -112,7 +126,7 @@ impl fmt::Display for ActionSet {
 /// along with the `action` and whether the action is an input (`is_input`).
 ///
 /// For representing a set of actions, see [ActionSet].
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Action {
     pub name: String,
     pub is_input: bool,
@@ -148,21 +162,91 @@ pub enum QueryResult {
     Consistency(ConsistencyResult),
     /// A determinism query returned a success or failure, see [DeterminismResult].
     Determinism(DeterminismResult),
+    /// An implementation query returned a success or failure, see [ImplementationResult].
+    Implementation(ImplementationResult),
+    /// A specification query returned a success or failure, see [SpecificationResult].
+    Specification(SpecificationResult),
+    /// A temporal logic query returned a success or failure, see [TemporalResult].
+    Temporal(TemporalResult),
+    /// A concrete trace query returned the timed trace or a failure, see [ConcreteTraceResult].
+    ConcreteTrace(ConcreteTraceResult),
+    /// A trace check query returned whether the trace conforms to the system, see [TraceCheckResult].
+    TraceCheck(TraceCheckResult),
+    /// An automatic simulation returned its runs and coverage.
+    Simulation(SimulationReport),
+    /// A test generation query returned the test cases derived from the specification.
+    TestCases(TestSuite),
+    /// A strategy query returned the winning strategy of the environment in a refinement, see [StrategyResult].
+    Strategy(StrategyResult),
     /// A get components query returned a new component.
     GetComponent(Component),
+    /// The query ran for longer than the timeout in its settings.
+    Timeout,
+    /// The query was cancelled before it finished.
+    Cancelled,
+    /// The query stored more states than its limits allow, so it is unknown whether the property holds.
+    Inconclusive(LimitReached),
     /// The query resulted in an unclassified error.
     CustomError(String),
 }
 
 pub type PathResult = Result<SpecificPath, PathFailure>;
 
-//TODO: add refinement Ok result
-pub type RefinementResult = Result<(), RefinementFailure>;
+/// The result of a refinement check, holding the refinement relation if it holds and the relation was asked for.
+pub type RefinementResult = Result<Option<SpecificRelation>, RefinementFailure>;
 
 pub type ConsistencyResult = Result<(), ConsistencyFailure>;
 
 pub type DeterminismResult = Result<(), DeterminismFailure>;
 
+pub type ImplementationResult = Result<(), ImplementationFailure>;
+
+pub type SpecificationResult = Result<(), SpecificationFailure>;
+
+/// The winning strategy of the environment if the refinement fails, or [None] if it holds.
+pub type StrategyResult = Result<Option<RefinementStrategy>, RefinementPrecondition>;
+
+/// The witness of a satisfied `E<>` or `E[]` query, or [None] for satisfied `A[]`, `A<>` and leads-to queries.
+pub type TemporalResult = Result<Option<SpecificLasso>, TemporalFailure>;
+
+/// The timed trace of a feasible concrete trace query.
+pub type ConcreteTraceResult = Result<SpecificTimedTrace, ConcreteTraceFailure>;
+
+/// A concrete trace query failed with `error`, after taking the steps in `trace` if the system has an initial state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConcreteTraceFailure {
+    pub trace: Option<SpecificTimedTrace>,
+    pub error: ConcreteSimulationError,
+}
+
+/// The exact states the system can be in after a trace which conforms to it.
+pub type TraceCheckResult = Result<Vec<SpecificConcreteState>, TraceCheckFailure>;
+
+/// A trace check query found the first `event` of the trace which leaves the behaviour of the system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceCheckFailure {
+    /// The index and the event of the trace, which is `None` if the system has no initial state
+    pub event: Option<(usize, TraceEvent)>,
+    pub deviation: TraceDeviation,
+    /// The exact states the system could be in when the trace left its behaviour
+    pub states: Vec<SpecificConcreteState>,
+}
+
+/// Represents the different ways that an event of a trace can leave the behaviour of a system
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceDeviation {
+    /// The system has no initial state to replay the trace from
+    NoInitialState,
+    /// The event happened before the `previous` event
+    TimeDecreasing { previous: Rational },
+    /// The action of the event is not an action of the system
+    UnknownAction,
+    /// The invariants of the system do not allow waiting until the event happened
+    InvariantViolated,
+    /// No transition with the action of the event is enabled when it happened
+    NotEnabled,
+}
+
 /// Represents the different ways that a reachability query can fail
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PathFailure {
@@ -170,20 +254,38 @@ pub enum PathFailure {
     Unreachable,
 }
 
+/// Represents the different ways that a temporal logic query can fail
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemporalFailure {
+    /// No path satisfies the property of the existential `operator` (`E<>` or `E[]`).
+    NoWitness(TemporalOperator),
+    /// The `counterexample` path violates the property of the universal `operator` (`A[]` or `A<>`).
+    Counterexample {
+        operator: TemporalOperator,
+        counterexample: SpecificLasso,
+    },
+    /// The `counterexample` path reaches a state satisfying the premise of a leads-to query and then never satisfies its response.
+    NotLeadsTo { counterexample: SpecificLasso },
+}
+
 /// Represents the different ways that a refinement query can fail
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RefinementFailure {
-    /// The refinement failed for `system` because the right side cannot match left sides delay after taking `action` from `state`.
+    /// The refinement failed for `system` because the right side cannot match left sides delay after taking `action` from `state`,
+    /// which is reached from the initial state pair by `path`.
     CutsDelaySolutions {
         system: System,
         action: Action,
         state: SpecificState,
+        path: SpecificPath,
     },
-    /// The refinement failed for `system` because one side could not match the `action` from `state`.
+    /// The refinement failed for `system` because one side could not match the `action` from `state`,
+    /// which is reached from the initial state pair by `path`.
     CannotMatch {
         system: System,
         action: Action,
         state: SpecificState,
+        path: SpecificPath,
     },
     /// The refinement failed on a precondition, see [RefinementPrecondition].
     Precondition(RefinementPrecondition),
@@ -238,13 +340,14 @@ impl RefinementFailure {
         sys2: &dyn TransitionSystem,
         action: impl Into<String>,
         state: &StatePair,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         let action: String = action.into();
         let is_input = sys1.inputs_contain(&action) || sys2.inputs_contain(&action);
         Err(RefinementFailure::CannotMatch {
             system: System::refinement(sys1, sys2),
             action: Action::new(action, is_input),
             state: SpecificState::from_state_pair(state, sys1, sys2),
+            path: SpecificPath::from_state_pair(state, sys1, sys2),
         })
     }
 
@@ -254,13 +357,14 @@ impl RefinementFailure {
         sys2: &dyn TransitionSystem,
         action: impl Into<String>,
         state: &StatePair,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         let action: String = action.into();
         let is_input = sys1.inputs_contain(&action) || sys2.inputs_contain(&action);
         Err(RefinementFailure::CutsDelaySolutions {
             system: System::refinement(sys1, sys2),
             action: Action::new(action, is_input),
             state: SpecificState::from_state_pair(state, sys1, sys2),
+            path: SpecificPath::from_state_pair(state, sys1, sys2),
         })
     }
 }
@@ -413,6 +517,21 @@ pub enum SystemRecipeFailure {
     Action(ActionFailure, System),
     /// The recipe failed because a conjunction in the system was empty (and therefore inconsistent).
     Inconsistent(ConsistencyFailure, System),
+    /// The recipe failed because a component could not be compiled, e.g. as an integer variable overflowed.
+    Invalid(String, System),
+}
+
+impl SystemRecipeFailure {
+    /// Creates a [SystemRecipeFailure] for the component named `comp` which could not be compiled because of `reason`.
+    pub fn invalid(reason: impl Into<String>, comp: impl Into<String>) -> Box<Self> {
+        Box::new(SystemRecipeFailure::Invalid(
+            reason.into(),
+            System {
+                name: comp.into(),
+                sys_type: SystemType::Simple,
+            },
+        ))
+    }
 }
 
 /// Represents the different ways that clock reduction can fail.
@@ -426,15 +545,17 @@ pub enum ConsistencyFailure {
     NoInitialState { system: String },
     /// The system is not deterministic.
     NotDeterministic(DeterminismFailure),
-    /// The `system` cannot prune an inconsistent locaction `state`.
+    /// The `system` cannot prune an inconsistent locaction `state`, which is reached from the initial state by `path`.
     InconsistentLoc {
         system: String,
         state: SpecificState,
+        path: SpecificPath,
     },
-    /// The `system` cannot prune an inconsistent `state`.
+    /// The `system` cannot prune an inconsistent `state`, which is reached from the initial state by `path`.
     InconsistentFrom {
         system: String,
         state: SpecificState,
+        path: SpecificPath,
     },
 }
 
@@ -446,19 +567,25 @@ impl ConsistencyFailure {
         })
     }
 
-    /// Creates a new [ConsistencyFailure] that failed because the system cannot prune an inconsistent location `state`.
-    pub fn inconsistent(system: &dyn TransitionSystem, state: &State) -> ConsistencyResult {
+    /// Creates a new [ConsistencyFailure] that failed because the system cannot prune an inconsistent location `state` reached by `trace`.
+    pub fn inconsistent(
+        system: &dyn TransitionSystem,
+        state: &State,
+        trace: &[Decision],
+    ) -> ConsistencyResult {
         Err(ConsistencyFailure::InconsistentLoc {
             system: system.to_string(),
             state: SpecificState::from_state(state, system),
+            path: SpecificPath::from_decisions(trace, system),
         })
     }
 
-    /// Creates a new [ConsistencyFailure] that failed because the system cannot prune an inconsistent `state`.
+    /// Creates a new [ConsistencyFailure] that failed because the system cannot prune an inconsistent `state` reached by `trace`.
     pub fn inconsistent_from(
         system: &dyn TransitionSystem,
         //action: impl Into<String>,
         state: &State,
+        trace: &[Decision],
     ) -> ConsistencyResult {
         //let action: String = action.into();
         //let is_input = system.inputs_contain(&action);
@@ -466,9 +593,20 @@ impl ConsistencyFailure {
             system: system.to_string(),
             //action: Action::new(action, is_input),
             state: SpecificState::from_state(state, system),
+            path: SpecificPath::from_decisions(trace, system),
         })
     }
 
+    /// The path from the initial state to the failing state, if the failure happened in a state.
+    pub fn path(&self) -> Option<&SpecificPath> {
+        match self {
+            ConsistencyFailure::NoInitialState { .. } => None,
+            ConsistencyFailure::NotDeterministic(determ) => Some(&determ.path),
+            ConsistencyFailure::InconsistentLoc { path, .. }
+            | ConsistencyFailure::InconsistentFrom { path, .. } => Some(path),
+        }
+    }
+
     /// Converts this [ConsistencyFailure] into a [RefinementPrecondition] given the two [TransitionSystem]s that failed.
     pub fn to_precondition(
         self,
@@ -492,20 +630,23 @@ impl ConsistencyFailure {
     }
 }
 
-/// Represents how a [TransitionSystem] named `system` failed to be deterministic for `action` in `state`.
+/// Represents how a [TransitionSystem] named `system` failed to be deterministic for `action` in `state`,
+/// which is reached from the initial state by `path`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeterminismFailure {
     pub system: String,
     pub action: Action,
     pub state: SpecificState,
+    pub path: SpecificPath,
 }
 
 impl DeterminismFailure {
-    /// Creates a new [DeterminismFailure] from a `system`, `action`, and `state`.
+    /// Creates a new [DeterminismFailure] from a `system`, `action`, and `state` reached by `trace`.
     pub fn from_system_and_action(
         system: &dyn TransitionSystem,
         action: impl Into<String>,
         state: &State,
+        trace: &[Decision],
     ) -> DeterminismResult {
         let action: String = action.into();
         let is_input = system.inputs_contain(&action);
@@ -513,10 +654,81 @@ impl DeterminismFailure {
             system: system.to_string(),
             action: Action::new(action, is_input),
             state: SpecificState::from_state(state, system),
+            path: SpecificPath::from_decisions(trace, system),
         })
     }
 }
 
+/// Represents the different ways that a [TransitionSystem] can fail to be an implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImplementationFailure {
+    /// The system is not a valid specification, as it is inconsistent or not deterministic.
+    NotSpecification(ConsistencyFailure),
+    /// The `system` can delay in `state` even though the output `action` is enabled.
+    NotOutputUrgent {
+        system: String,
+        action: Action,
+        state: SpecificState,
+    },
+    /// The `system` can neither delay indefinitely nor eventually take an output from `state`.
+    NoIndependentProgress {
+        system: String,
+        state: SpecificState,
+    },
+}
+
+impl ImplementationFailure {
+    /// Creates a new [ImplementationFailure] that failed because the system can delay in `state` while the output `action` is enabled.
+    pub fn not_output_urgent(
+        system: &dyn TransitionSystem,
+        action: impl Into<String>,
+        state: &State,
+    ) -> ImplementationResult {
+        Err(ImplementationFailure::NotOutputUrgent {
+            system: system.to_string(),
+            action: Action::new(action.into(), false),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+
+    /// Creates a new [ImplementationFailure] that failed because the system has no independent progress from `state`.
+    pub fn no_independent_progress(
+        system: &dyn TransitionSystem,
+        state: &State,
+    ) -> ImplementationResult {
+        Err(ImplementationFailure::NoIndependentProgress {
+            system: system.to_string(),
+            state: SpecificState::from_state(state, system),
+        })
+    }
+}
+
+/// Represents the different ways that a [TransitionSystem] can fail to be a specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpecificationFailure {
+    /// The system is not consistent, see [ConsistencyFailure].
+    NotConsistent(ConsistencyFailure),
+    /// The system is not deterministic, see [DeterminismFailure].
+    NotDeterministic(DeterminismFailure),
+    /// The system is neither consistent nor deterministic.
+    NotConsistentNorDeterministic(ConsistencyFailure, DeterminismFailure),
+}
+
+impl SpecificationFailure {
+    /// Combines the results of a consistency and a determinism check into a [SpecificationResult].
+    pub fn from_results(
+        consistency: ConsistencyResult,
+        determinism: DeterminismResult,
+    ) -> SpecificationResult {
+        match (consistency, determinism) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(cf), Ok(())) => Err(SpecificationFailure::NotConsistent(cf)),
+            (Ok(()), Err(df)) => Err(SpecificationFailure::NotDeterministic(df)),
+            (Err(cf), Err(df)) => Err(SpecificationFailure::NotConsistentNorDeterministic(cf, df)),
+        }
+    }
+}
+
 // ---------------------------- //
 // --- Format Display Impl  --- //
 // ---------------------------- //
@@ -538,6 +750,7 @@ impl std::fmt::Display for RefinementFailure {
                 system,
                 action,
                 state,
+                ..
             } => write!(
                 f,
                 "The refinement '{}' fails because delay solutions are cut in state {} for {}",
@@ -547,6 +760,7 @@ impl std::fmt::Display for RefinementFailure {
                 system,
                 action,
                 state,
+                ..
             } => write!(
                 f,
                 "The refinement '{}' fails in state {} because {} cannot be matched",
@@ -566,8 +780,8 @@ impl std::fmt::Display for ConsistencyFailure {
                 system
             ),
             ConsistencyFailure::NotDeterministic(determ) => determ.fmt(f),
-            ConsistencyFailure::InconsistentLoc { system, state }
-            | ConsistencyFailure::InconsistentFrom { system, state } => write!(
+            ConsistencyFailure::InconsistentLoc { system, state, .. }
+            | ConsistencyFailure::InconsistentFrom { system, state, .. } => write!(
                 f,
                 "The system '{}' is inconsistent because there are no saving outputs from state {}",
                 system, state
@@ -576,6 +790,121 @@ impl std::fmt::Display for ConsistencyFailure {
     }
 }
 
+impl std::fmt::Display for ImplementationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImplementationFailure::NotSpecification(cf) => write!(
+                f,
+                "The system is not an implementation because it is not a specification: {}",
+                cf
+            ),
+            ImplementationFailure::NotOutputUrgent {
+                system,
+                action,
+                state,
+            } => write!(
+                f,
+                "The system '{}' is not an implementation because it can delay in state {} while {} is enabled",
+                system, state, action
+            ),
+            ImplementationFailure::NoIndependentProgress { system, state } => write!(
+                f,
+                "The system '{}' is not an implementation because it has no independent progress from state {}",
+                system, state
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for SpecificationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecificationFailure::NotConsistent(cf) => cf.fmt(f),
+            SpecificationFailure::NotDeterministic(df) => df.fmt(f),
+            SpecificationFailure::NotConsistentNorDeterministic(cf, df) => {
+                write!(f, "{}\n{}", cf, df)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ConcreteTraceFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.trace {
+            Some(trace) => write!(f, "Step {} failed: {}", trace.steps.len() + 1, self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
+impl std::fmt::Display for TraceCheckFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.event {
+            Some((index, event)) => write!(
+                f,
+                "Event {} ({}) leaves the behaviour of the system: {}",
+                index + 1,
+                event,
+                self.deviation
+            ),
+            None => write!(f, "{}", self.deviation),
+        }
+    }
+}
+
+impl std::fmt::Display for TraceDeviation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TraceDeviation::NoInitialState => write!(f, "The system has no initial state"),
+            TraceDeviation::TimeDecreasing { previous } => {
+                write!(f, "it happened before the previous event at {}", previous)
+            }
+            TraceDeviation::UnknownAction => write!(f, "the action is not in the system"),
+            TraceDeviation::InvariantViolated => {
+                write!(f, "the invariants do not allow waiting until it happened")
+            }
+            TraceDeviation::NotEnabled => {
+                write!(
+                    f,
+                    "no transition with the action is enabled when it happened"
+                )
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for TemporalFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemporalFailure::NoWitness(TemporalOperator::EventuallyAlways) => write!(
+                f,
+                "No maximal path stays in states satisfying the predicate"
+            ),
+            TemporalFailure::NoWitness(_) => {
+                write!(f, "No reachable state satisfies the predicate")
+            }
+            TemporalFailure::Counterexample {
+                operator: TemporalOperator::Potentially,
+                counterexample,
+            } => write!(
+                f,
+                "A maximal path of {} transitions never reaches a state satisfying the predicate",
+                counterexample.len()
+            ),
+            TemporalFailure::Counterexample { counterexample, .. } => write!(
+                f,
+                "A state violating the predicate is reachable in {} transitions",
+                counterexample.len()
+            ),
+            TemporalFailure::NotLeadsTo { counterexample } => write!(
+                f,
+                "A maximal path of {} transitions reaches a state satisfying the premise but never the response",
+                counterexample.len()
+            ),
+        }
+    }
+}
+
 impl std::fmt::Display for SystemRecipeFailure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -591,6 +920,13 @@ impl std::fmt::Display for SystemRecipeFailure {
                     system.sys_type, system.name, cf
                 )
             }
+            SystemRecipeFailure::Invalid(reason, system) => {
+                write!(
+                    f,
+                    "{} in {} is invalid: {}",
+                    system.sys_type, system.name, reason
+                )
+            }
         }
     }
 }
@@ -655,6 +991,9 @@ mod conversions {
     impl Error for RefinementFailure {}
     impl Error for ConsistencyFailure {}
     impl Error for DeterminismFailure {}
+    impl Error for ImplementationFailure {}
+    impl Error for SpecificationFailure {}
+    impl Error for TemporalFailure {}
 
     impl From<RefinementPrecondition> for RefinementFailure {
         fn from(failure: RefinementPrecondition) -> Self {
@@ -685,12 +1024,37 @@ mod conversions {
         }
     }
 
+    impl From<ConsistencyFailure> for ImplementationFailure {
+        fn from(failure: ConsistencyFailure) -> Self {
+            ImplementationFailure::NotSpecification(failure)
+        }
+    }
+
     impl From<SystemRecipeFailure> for QueryResult {
         fn from(res: SystemRecipeFailure) -> Self {
             QueryResult::RecipeFailure(res)
         }
     }
 
+    impl From<Interrupted> for QueryResult {
+        fn from(interrupted: Interrupted) -> Self {
+            match interrupted {
+                Interrupted::Timeout => QueryResult::Timeout,
+                Interrupted::Cancelled => QueryResult::Cancelled,
+                Interrupted::LimitReached(limit) => QueryResult::Inconclusive(limit),
+            }
+        }
+    }
+
+    impl<T: Into<QueryResult>> From<Result<T, Interrupted>> for QueryResult {
+        fn from(res: Result<T, Interrupted>) -> Self {
+            match res {
+                Ok(res) => res.into(),
+                Err(interrupted) => interrupted.into(),
+            }
+        }
+    }
+
     impl From<PathResult> for QueryResult {
         fn from(res: PathResult) -> Self {
             QueryResult::Reachability(res)
@@ -714,4 +1078,22 @@ mod conversions {
             QueryResult::Refinement(res)
         }
     }
+
+    impl From<ImplementationResult> for QueryResult {
+        fn from(res: ImplementationResult) -> Self {
+            QueryResult::Implementation(res)
+        }
+    }
+
+    impl From<TemporalResult> for QueryResult {
+        fn from(res: TemporalResult) -> Self {
+            QueryResult::Temporal(res)
+        }
+    }
+
+    impl From<SpecificationResult> for QueryResult {
+        fn from(res: SpecificationResult) -> Self {
+            QueryResult::Specification(res)
+        }
+    }
 }
@@ -1,13 +1,15 @@
 use edbm::util::bounds::Bounds;
 use edbm::zones::OwnedFederation;
 
+use super::cancellation::{self, Interrupted};
+use super::limits;
 use super::query_failures::PathFailure;
 use super::specifics::SpecificPath;
 use crate::ModelObjects::{State, Transition};
 use crate::Simulation::decision::Decision;
 use crate::TransitionSystems::{LocationID, TransitionSystemPtr};
 use std::collections::{HashMap, VecDeque};
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::query_failures::PathResult;
 
@@ -18,13 +20,13 @@ pub struct Path {
 }
 
 /// This holds which transition from which state (the `destination_state` of the `previous_sub_path`) it took to reach this state
-struct SubPath {
-    previous_sub_path: Option<Rc<SubPath>>,
-    destination_state: State,
-    transition: Option<(Transition, String)>,
+pub(super) struct SubPath {
+    pub previous_sub_path: Option<Arc<SubPath>>,
+    pub destination_state: State,
+    pub transition: Option<(Transition, String)>,
 }
 
-fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
+pub(super) fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
     // If any of the zones are empty
     if start_state.zone_ref().is_empty() || end_state.zone_ref().is_empty() {
         return true;
@@ -50,6 +52,8 @@ fn is_trivially_unreachable(start_state: &State, end_state: &State) -> bool {
 ///
 /// The start state can be omitted with None to use the start state of the transition system.
 ///
+/// Returns an error if the query is interrupted, see [cancellation].
+///
 ///## Checking if a state can reach another:
 /// ```ignore
 /// let is_reachable: bool = match find_path(Some(start_state), end_state, transition_system) {
@@ -75,9 +79,9 @@ pub fn find_path(
     start_state: State,
     end_state: State,
     system: &TransitionSystemPtr,
-) -> Result<Path, PathFailure> {
+) -> Result<Result<Path, PathFailure>, Interrupted> {
     if is_trivially_unreachable(&start_state, &end_state) {
-        return Err(PathFailure::Unreachable);
+        return Ok(Err(PathFailure::Unreachable));
     }
 
     reachability_search(&start_state, &end_state, system)
@@ -87,8 +91,9 @@ pub fn find_specific_path(
     start_state: State,
     end_state: State,
     system: &TransitionSystemPtr,
-) -> PathResult {
-    find_path(start_state, end_state, system).map(|p| SpecificPath::from_path(&p, system.as_ref()))
+) -> Result<PathResult, Interrupted> {
+    let path = find_path(start_state, end_state, system)?;
+    Ok(path.map(|p| SpecificPath::from_path(&p, system.as_ref())))
 }
 
 /// Currently runs a BFS search on the transition system.
@@ -99,7 +104,7 @@ fn reachability_search(
     start_state: &State,
     end_state: &State,
     system: &TransitionSystemPtr,
-) -> Result<Path, PathFailure> {
+) -> Result<Result<Path, PathFailure>, Interrupted> {
     // Apply the invariant of the start state to the start state
     let mut start_state = start_state.clone();
     start_state.apply_invariants();
@@ -108,7 +113,7 @@ fn reachability_search(
     let mut visited_states: HashMap<LocationID, Vec<OwnedFederation>> = HashMap::new();
 
     // List of states that are to be visited
-    let mut frontier_states: VecDeque<Rc<SubPath>> = VecDeque::new();
+    let mut frontier_states: VecDeque<Arc<SubPath>> = VecDeque::new();
 
     let mut actions: Vec<String> = system.get_actions().into_iter().collect();
     actions.sort();
@@ -120,7 +125,7 @@ fn reachability_search(
     );
 
     // Push initial state to frontier
-    frontier_states.push_back(Rc::new(SubPath {
+    frontier_states.push_back(Arc::new(SubPath {
         previous_sub_path: None,
         destination_state: start_state.clone(),
         transition: None,
@@ -130,8 +135,9 @@ fn reachability_search(
 
     // Take the first state from the frontier and explore it
     while let Some(sub_path) = frontier_states.pop_front() {
+        cancellation::checkpoint()?;
         if reached_end_state(&sub_path.destination_state, end_state) {
-            return Ok(make_path(sub_path, start_state));
+            return Ok(Ok(make_path(sub_path, start_state)));
         }
 
         for action in &actions {
@@ -146,15 +152,15 @@ fn reachability_search(
                     system,
                     action,
                     &target_bounds,
-                );
+                )?;
             }
         }
     }
     // If nothing has been found, it is not reachable
-    Err(PathFailure::Unreachable)
+    Ok(Err(PathFailure::Unreachable))
 }
 
-fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
+pub(crate) fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
     cur_state
         .decorated_locations
         .compare_partial_locations(&end_state.decorated_locations)
@@ -162,14 +168,14 @@ fn reached_end_state(cur_state: &State, end_state: &State) -> bool {
 }
 
 fn take_transition(
-    sub_path: &Rc<SubPath>,
+    sub_path: &Arc<SubPath>,
     transition: &Transition,
-    frontier_states: &mut VecDeque<Rc<SubPath>>,
+    frontier_states: &mut VecDeque<Arc<SubPath>>,
     visited_states: &mut HashMap<LocationID, Vec<OwnedFederation>>,
     system: &TransitionSystemPtr,
     action: &str,
     target_bounds: &Bounds,
-) {
+) -> Result<(), Interrupted> {
     let mut new_state = sub_path.destination_state.clone();
     if transition.use_transition(&mut new_state) {
         // Extrapolation ensures the bounds cant grow indefinitely, avoiding infinite loops
@@ -182,22 +188,23 @@ fn take_transition(
             // Remove the smaller zones for this location in visited_states
             remove_existing_subsets_of_zone(new_state.zone_ref(), existing_zones);
             // Add the new zone to the list of zones for this location in visited_states
-            visited_states
-                .get_mut(new_location_id)
-                .unwrap()
-                .push(new_state.zone_ref().clone());
+            let existing_zones = visited_states.get_mut(new_location_id).unwrap();
+            existing_zones.push(new_state.zone_ref().clone());
+            limits::record_state(new_state.zone_ref())?;
+            limits::check_zones_per_location(existing_zones.len())?;
             // Add the new state to the frontier
-            frontier_states.push_back(Rc::new(SubPath {
-                previous_sub_path: Some(Rc::clone(sub_path)),
+            frontier_states.push_back(Arc::new(SubPath {
+                previous_sub_path: Some(Arc::clone(sub_path)),
                 destination_state: new_state,
                 transition: Some((transition.clone(), action.to_string())),
             }));
         }
     }
+    Ok(())
 }
 
 /// Checks if this zone is redundant by being a subset of any other zone
-fn zone_subset_of_existing_zones(
+pub(super) fn zone_subset_of_existing_zones(
     new_state: &OwnedFederation,
     existing_states: &[OwnedFederation],
 ) -> bool {
@@ -207,19 +214,19 @@ fn zone_subset_of_existing_zones(
 }
 
 /// Removes everything in existing_zones that is a subset of zone
-fn remove_existing_subsets_of_zone(
+pub(super) fn remove_existing_subsets_of_zone(
     new_zone: &OwnedFederation,
     existing_zones: &mut Vec<OwnedFederation>,
 ) {
     existing_zones.retain(|existing_zone| !existing_zone.subset_eq(new_zone));
 }
 /// Makes the path from the last subpath
-fn make_path(mut sub_path: Rc<SubPath>, start_state: State) -> Path {
+pub(super) fn make_path(mut sub_path: Arc<SubPath>, start_state: State) -> Path {
     let mut path: Vec<(Transition, String)> = Vec::new();
     // Traverse the subpaths to make the path (from end location to start location)
     while sub_path.previous_sub_path.is_some() {
         path.push(sub_path.transition.clone().unwrap());
-        sub_path = Rc::clone(sub_path.previous_sub_path.as_ref().unwrap());
+        sub_path = Arc::clone(sub_path.previous_sub_path.as_ref().unwrap());
     }
     // Reverse the path since the transitions are in reverse order (now from start location to end location)
     path.reverse();
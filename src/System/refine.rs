@@ -2,16 +2,19 @@ use edbm::zones::OwnedFederation;
 use log::{debug, info, log_enabled, trace, Level};
 
 use crate::DataTypes::{PassedStateList, PassedStateListExt, WaitingStateList};
-use crate::ModelObjects::{StatePair, Transition};
+use crate::ModelObjects::{StatePair, StatePairStep, Transition};
 use crate::System::query_failures::RefinementFailure;
-use crate::TransitionSystems::{LocationTree, TransitionSystemPtr};
-use std::collections::HashSet;
+use crate::TransitionSystems::{
+    LocationID, LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr,
+};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
 
+use super::cancellation::{self, Interrupted};
 use super::query_failures::{ActionFailure, RefinementPrecondition, RefinementResult};
+use super::specifics::SpecificRelation;
 
-const SUCCESS: RefinementResult = Ok(());
-
-enum StatePairResult {
+pub(super) enum StatePairResult {
     Valid,
     EmptyTransition2s,
     NotEmptyResult,
@@ -25,7 +28,7 @@ impl StatePairResult {
         sys2: &TransitionSystemPtr,
         action: &str,
         curr_pair: &StatePair,
-    ) -> RefinementResult {
+    ) -> Result<(), RefinementFailure> {
         match self {
             StatePairResult::Valid => Ok(()),
             StatePairResult::EmptyTransition2s | StatePairResult::NotEmptyResult => {
@@ -38,18 +41,22 @@ impl StatePairResult {
     }
 }
 
+/// The actions are sorted, so the state pairs are explored in the same order every time
 fn common_actions(
     sys1: &TransitionSystemPtr,
     sys2: &TransitionSystemPtr,
     is_input: bool,
-) -> HashSet<String> {
+) -> BTreeSet<String> {
     if is_input {
-        sys2.get_input_actions()
+        sys2.get_input_actions().into_iter().collect()
     } else {
-        sys1.get_output_actions()
+        sys1.get_output_actions().into_iter().collect()
     }
 }
 
+/// The inputs of sys2 which sys1 does not have, matched by sys1 staying in its location,
+/// and the outputs of sys1 which sys2 does not have, matched by sys2 staying in its location.
+/// The latter include the internal moves of sys1 which sys2 does not have, so sys2 lets time pass while sys1 moves internally.
 fn extra_actions(
     sys1: &TransitionSystemPtr,
     sys2: &TransitionSystemPtr,
@@ -68,36 +75,105 @@ fn extra_actions(
     }
 }
 
-struct RefinementContext<'a> {
-    pub passed_list: PassedStateList,
-    pub waiting_list: WaitingStateList,
+/// Stores the state pairs found by a refinement check until they are explored
+pub(super) trait StatePairStore {
+    /// Adds `pair` to be explored, unless it is included in a state pair which is already explored or waiting.
+    /// Returns an error if the query exceeds its [limits](super::limits).
+    fn add(&mut self, pair: StatePair) -> Result<(), Interrupted>;
+}
+
+pub(super) struct RefinementContext<'a, S: StatePairStore> {
+    pub store: S,
     pub sys1: &'a TransitionSystemPtr,
     pub sys2: &'a TransitionSystemPtr,
 }
 
-impl<'a> RefinementContext<'a> {
-    fn new(sys1: &'a TransitionSystemPtr, sys2: &'a TransitionSystemPtr) -> RefinementContext<'a> {
-        RefinementContext {
+struct SequentialStore {
+    passed_list: PassedStateList,
+    waiting_list: WaitingStateList,
+}
+
+impl StatePairStore for SequentialStore {
+    fn add(&mut self, pair: StatePair) -> Result<(), Interrupted> {
+        if !self.passed_list.has(&pair) && !self.waiting_list.has(&pair) {
+            debug!("New state {}", pair);
+
+            self.waiting_list.put(pair)?;
+        }
+        Ok(())
+    }
+}
+
+/// The actions checked in every state pair of a refinement
+pub(super) struct RefinementActions {
+    pub inputs: BTreeSet<String>,
+    pub outputs: BTreeSet<String>,
+    extra_inputs: HashSet<String>,
+    extra_outputs: HashSet<String>,
+}
+
+/// Checks if sys1 refines sys2, returning the refinement relation if it does and `build_relation` is set.
+///
+/// The state pairs are explored depth first in a fixed order, so the failure reported is the same every time.
+/// See [check_refinement_parallel](super::parallel_refine::check_refinement_parallel) for exploring them in parallel.
+/// Returns an error if the query is interrupted, see [cancellation].
+pub fn check_refinement(
+    sys1: TransitionSystemPtr,
+    sys2: TransitionSystemPtr,
+    build_relation: bool,
+) -> Result<RefinementResult, Interrupted> {
+    let (initial_pair, actions) = match prepare_refinement(&sys1, &sys2) {
+        Ok(Some(prepared)) => prepared,
+        // Both are empty, so trivially true
+        Ok(None) => return Ok(Ok(build_relation.then(SpecificRelation::default))),
+        Err(failure) => return Ok(Err(failure)),
+    };
+
+    let mut context = RefinementContext {
+        store: SequentialStore {
             passed_list: PassedStateList::new(),
             waiting_list: WaitingStateList::new(),
-            sys1,
-            sys2,
+        },
+        sys1: &sys1,
+        sys2: &sys2,
+    };
+    context.store.waiting_list.put(initial_pair)?;
+
+    while let Some(curr_pair) = context.store.waiting_list.pop() {
+        cancellation::checkpoint()?;
+        trace!("Checking {}", curr_pair);
+
+        context.store.passed_list.put(curr_pair.clone())?;
+        if let Err(failure) = check_state_pair(&Arc::new(curr_pair), &actions, &mut context)? {
+            return Ok(Err(failure));
         }
     }
+    info!("Refinement check passed");
+    if log_enabled!(Level::Debug) {
+        debug!("With relation:");
+        print_relation(&context.store.passed_list);
+    }
+
+    Ok(Ok(build_relation.then(|| {
+        SpecificRelation::from_passed_list(&context.store.passed_list, sys1.as_ref(), sys2.as_ref())
+    })))
 }
 
-/// Checks if sys1 refines sys2
-pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) -> RefinementResult {
-    let mut context = RefinementContext::new(&sys1, &sys2);
+/// Checks the preconditions of the refinement and creates its initial state pair along with the actions to check.
+/// Returns [None] if both systems are empty.
+pub(super) fn prepare_refinement(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> Result<Option<(StatePair, RefinementActions)>, RefinementFailure> {
     let dimensions = sys1.get_dim();
     debug!("Dimensions: {}", dimensions);
 
     //Firstly we check the preconditions
-    check_preconditions(&sys1, &sys2)?;
+    check_preconditions(sys1, sys2)?;
 
     // Common inputs and outputs
-    let inputs = common_actions(&sys1, &sys2, true);
-    let outputs = common_actions(&sys1, &sys2, false);
+    let inputs = common_actions(sys1, sys2, true);
+    let outputs = common_actions(sys1, sys2, false);
 
     info!(
         "Left inputs: {:?}, Left outputs: {:?}",
@@ -112,8 +188,8 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     );
 
     // Extra inputs and outputs are ignored by default
-    let extra_inputs = extra_actions(&sys1, &sys2, true);
-    let extra_outputs = extra_actions(&sys1, &sys2, false);
+    let extra_inputs = extra_actions(sys1, sys2, true);
+    let extra_outputs = extra_actions(sys1, sys2, false);
 
     let initial_locations_1 = sys1.get_initial_location();
     let initial_locations_2 = sys2.get_initial_location();
@@ -123,15 +199,14 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
 
     if initial_locations_1.is_none() {
         if initial_locations_2.is_none() {
-            // Both are empty, so trivially true
-            return SUCCESS;
+            return Ok(None);
         }
-        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), true);
+        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), true).map(|_| None);
     }
 
     if initial_locations_2.is_none() {
         //The empty automata cannot implement
-        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), false);
+        return RefinementFailure::empty_child(sys1.as_ref(), sys2.as_ref(), false).map(|_| None);
     }
 
     let initial_locations_1 = initial_locations_1.unwrap();
@@ -144,72 +219,108 @@ pub fn check_refinement(sys1: TransitionSystemPtr, sys2: TransitionSystemPtr) ->
     );
 
     if !prepare_init_state(&mut initial_pair, initial_locations_1, initial_locations_2) {
-        return RefinementFailure::empty_initial(sys1.as_ref(), sys2.as_ref());
+        return RefinementFailure::empty_initial(sys1.as_ref(), sys2.as_ref()).map(|_| None);
     }
-    initial_pair.extrapolate_max_bounds(context.sys1, context.sys2);
+    initial_pair.extrapolate_max_bounds(sys1, sys2);
 
     debug!("Initial {}", initial_pair);
-    context.waiting_list.put(initial_pair);
 
-    while !context.waiting_list.is_empty() {
-        let curr_pair = context.waiting_list.pop().unwrap();
-        trace!("Checking {}", curr_pair);
-
-        context.passed_list.put(curr_pair.clone());
-        for output in &outputs {
-            let extra = extra_outputs.contains(output);
+    let actions = RefinementActions {
+        inputs,
+        outputs,
+        extra_inputs,
+        extra_outputs,
+    };
+    Ok(Some((initial_pair, actions)))
+}
 
-            let output_transition1 = sys1.next_outputs(curr_pair.get_locations1(), output);
-            let output_transition2 = if extra {
-                vec![Transition::without_id(
-                    curr_pair.get_locations2(),
-                    dimensions,
-                )]
-            } else {
-                sys2.next_outputs(curr_pair.get_locations2(), output)
-            };
-
-            has_valid_state_pairs(
-                &output_transition1,
-                &output_transition2,
-                &curr_pair,
-                &mut context,
-                true,
-            )
-            .check(&sys1, &sys2, output, &curr_pair)?;
+/// Checks that every output of sys1 and every input of sys2 can be matched from `curr_pair`,
+/// adding the resulting state pairs to the store of the `context`.
+/// The outer error is returned if the query exceeds its limits.
+pub(super) fn check_state_pair(
+    curr_pair: &Arc<StatePair>,
+    actions: &RefinementActions,
+    context: &mut RefinementContext<impl StatePairStore>,
+) -> Result<Result<(), RefinementFailure>, Interrupted> {
+    let (sys1, sys2) = (context.sys1, context.sys2);
+
+    for output in &actions.outputs {
+        let (output_transition1, output_transition2) =
+            action_transitions(curr_pair, output, false, actions, sys1, sys2);
+
+        let result = has_valid_state_pairs(
+            &output_transition1,
+            &output_transition2,
+            curr_pair,
+            output,
+            context,
+            true,
+        )?;
+        if let Err(failure) = result.check(sys1, sys2, output, curr_pair) {
+            return Ok(Err(failure));
         }
+    }
 
-        for input in &inputs {
-            let extra = extra_inputs.contains(input);
-
-            let input_transitions1 = if extra {
-                vec![Transition::without_id(
-                    curr_pair.get_locations1(),
-                    dimensions,
-                )]
-            } else {
-                sys1.next_inputs(curr_pair.get_locations1(), input)
-            };
-
-            let input_transitions2 = sys2.next_inputs(curr_pair.get_locations2(), input);
-
-            has_valid_state_pairs(
-                &input_transitions2,
-                &input_transitions1,
-                &curr_pair,
-                &mut context,
-                false,
-            )
-            .check(&sys1, &sys2, input, &curr_pair)?;
+    for input in &actions.inputs {
+        let (input_transitions2, input_transitions1) =
+            action_transitions(curr_pair, input, true, actions, sys1, sys2);
+
+        let result = has_valid_state_pairs(
+            &input_transitions2,
+            &input_transitions1,
+            curr_pair,
+            input,
+            context,
+            false,
+        )?;
+        if let Err(failure) = result.check(sys1, sys2, input, curr_pair) {
+            return Ok(Err(failure));
         }
     }
-    info!("Refinement check passed");
-    if log_enabled!(Level::Debug) {
-        debug!("With relation:");
-        print_relation(&context.passed_list);
-    }
+    Ok(Ok(()))
+}
 
-    SUCCESS
+/// Returns the transitions for `action` from `curr_pair` of the side taking it first, sys1 for outputs and sys2 for inputs,
+/// followed by the transitions of the side which has to match it
+pub(super) fn action_transitions(
+    curr_pair: &StatePair,
+    action: &str,
+    is_input: bool,
+    actions: &RefinementActions,
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> (Vec<Transition>, Vec<Transition>) {
+    let dimensions = sys1.get_dim();
+
+    if is_input {
+        let extra = actions.extra_inputs.contains(action);
+
+        let input_transitions1 = if extra {
+            vec![Transition::without_id(
+                curr_pair.get_locations1(),
+                dimensions,
+            )]
+        } else {
+            sys1.next_inputs(curr_pair.get_locations1(), action)
+        };
+        let input_transitions2 = sys2.next_inputs(curr_pair.get_locations2(), action);
+
+        (input_transitions2, input_transitions1)
+    } else {
+        let extra = actions.extra_outputs.contains(action);
+
+        let output_transition1 = sys1.next_outputs(curr_pair.get_locations1(), action);
+        let output_transition2 = if extra {
+            vec![Transition::without_id(
+                curr_pair.get_locations2(),
+                dimensions,
+            )]
+        } else {
+            sys2.next_outputs(curr_pair.get_locations2(), action)
+        };
+
+        (output_transition1, output_transition2)
+    }
 }
 
 fn print_relation(passed_list: &PassedStateList) {
@@ -233,40 +344,49 @@ fn print_relation(passed_list: &PassedStateList) {
     }
 }
 
-fn has_valid_state_pairs(
+/// Checks that the `transitions2` can match the `transitions1` from `curr_pair`, adding the resulting state pairs to the store of the `context`
+pub(super) fn has_valid_state_pairs(
     transitions1: &[Transition],
     transitions2: &[Transition],
-    curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    curr_pair: &Arc<StatePair>,
+    action: &str,
+    context: &mut RefinementContext<impl StatePairStore>,
     is_state1: bool,
-) -> StatePairResult {
+) -> Result<StatePairResult, Interrupted> {
     let (fed1, fed2) = get_guard_fed_for_sides(transitions1, transitions2, curr_pair, is_state1);
 
     // If there are no valid transition1s, continue
     if fed1.is_empty() {
-        return StatePairResult::Valid;
+        return Ok(StatePairResult::Valid);
     }
 
     // If there are (valid) transition1s but no transition2s there are no valid pairs
     if fed2.is_empty() {
         trace!("Empty transition2s");
-        return StatePairResult::EmptyTransition2s;
+        return Ok(StatePairResult::EmptyTransition2s);
     };
 
     let result_federation = fed1.subtraction(&fed2);
 
     // If the entire zone of transition1s cannot be matched by transition2s
     if !result_federation.is_empty() {
-        return StatePairResult::NotEmptyResult;
+        return Ok(StatePairResult::NotEmptyResult);
     }
 
     // Finally try to create the pairs
-    let res = try_create_new_state_pairs(transitions1, transitions2, curr_pair, context, is_state1);
-
-    match res {
+    let res = try_create_new_state_pairs(
+        transitions1,
+        transitions2,
+        curr_pair,
+        action,
+        context,
+        is_state1,
+    )?;
+
+    Ok(match res {
         BuildResult::Success => StatePairResult::Valid,
         BuildResult::Failure => StatePairResult::CutsDelaySolutions,
-    }
+    })
 }
 
 fn get_guard_fed_for_sides(
@@ -311,32 +431,39 @@ enum BuildResult {
 fn try_create_new_state_pairs(
     transitions1: &[Transition],
     transitions2: &[Transition],
-    curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    curr_pair: &Arc<StatePair>,
+    action: &str,
+    context: &mut RefinementContext<impl StatePairStore>,
     is_state1: bool,
-) -> BuildResult {
+) -> Result<BuildResult, Interrupted> {
     for transition1 in transitions1 {
         for transition2 in transitions2 {
-            if let BuildResult::Failure =
-                build_state_pair(transition1, transition2, curr_pair, context, is_state1)
-            {
-                return BuildResult::Failure;
+            if let BuildResult::Failure = build_state_pair(
+                transition1,
+                transition2,
+                curr_pair,
+                action,
+                context,
+                is_state1,
+            )? {
+                return Ok(BuildResult::Failure);
             }
         }
     }
 
-    BuildResult::Success
+    Ok(BuildResult::Success)
 }
 
 fn build_state_pair(
     transition1: &Transition,
     transition2: &Transition,
-    curr_pair: &StatePair,
-    context: &mut RefinementContext,
+    curr_pair: &Arc<StatePair>,
+    action: &str,
+    context: &mut RefinementContext<impl StatePairStore>,
     is_state1: bool,
-) -> BuildResult {
+) -> Result<BuildResult, Interrupted> {
     //Creates new state pair
-    let mut new_sp: StatePair = curr_pair.clone();
+    let mut new_sp: StatePair = curr_pair.as_ref().clone();
     //Creates DBM for that state pair
     let mut new_sp_zone = new_sp.take_zone();
     //Apply guards on both sides
@@ -349,7 +476,7 @@ fn build_state_pair(
 
     // Continue to the next transition pair if the zone is empty
     if new_sp_zone.is_empty() {
-        return BuildResult::Success;
+        return Ok(BuildResult::Success);
     }
 
     //Apply updates on both sides
@@ -383,7 +510,7 @@ fn build_state_pair(
 
     // Continue to the next transition pair if the newly built zones are empty
     if new_sp_zone.is_empty() || s_invariant.is_empty() {
-        return BuildResult::Success;
+        return Ok(BuildResult::Success);
     }
 
     // inv_s = x<10, inv_t = x>2 -> t cuts solutions but not delays, so it is fine and we can call down:
@@ -391,20 +518,109 @@ fn build_state_pair(
 
     // Check if the invariant of T (right) cuts delay solutions from S (left) and if so, report failure
     if !(s_invariant.subset_eq(&t_invariant)) {
-        return BuildResult::Failure;
+        return Ok(BuildResult::Failure);
     }
 
     new_sp.set_zone(new_sp_zone);
 
+    // Remember how the state pair was reached, so a failure in it can be given with the path leading to it
+    let (left_transition, right_transition) = if is_state1 {
+        (transition1, transition2)
+    } else {
+        (transition2, transition1)
+    };
+    new_sp.step = Some(Arc::new(StatePairStep {
+        previous: curr_pair.step.clone(),
+        action: action.to_string(),
+        transition1: left_transition.id.clone(),
+        target1: left_transition.target_locations.id.clone(),
+        transition2: right_transition.id.clone(),
+        target2: right_transition.target_locations.id.clone(),
+    }));
+
     new_sp.extrapolate_max_bounds(context.sys1, context.sys2);
 
-    if !context.passed_list.has(&new_sp) && !context.waiting_list.has(&new_sp) {
-        debug!("New state {}", new_sp);
+    context.store.add(new_sp)?;
 
-        context.waiting_list.put(new_sp);
+    Ok(BuildResult::Success)
+}
+
+/// Rebuilds the state pairs along the `steps` of a refinement check of `sys1` and `sys2`, starting with the initial state pair.
+///
+/// The steps only keep the ids of their transitions, so the transitions are looked up again and taken as in [build_state_pair].
+/// Guards, updates and invariants of the two systems constrain different clocks, so the order they are applied in does not matter.
+/// The replay stops early if the transition of a step cannot be found.
+pub fn replay_steps(
+    steps: &[&StatePairStep],
+    sys1: &dyn TransitionSystem,
+    sys2: &dyn TransitionSystem,
+) -> Vec<StatePair> {
+    let (locations1, locations2) = match (sys1.get_initial_location(), sys2.get_initial_location())
+    {
+        (Some(locations1), Some(locations2)) => (locations1, locations2),
+        _ => return vec![],
+    };
+    let mut initial_pair =
+        StatePair::from_locations(sys1.get_dim(), locations1.clone(), locations2.clone());
+    prepare_init_state(&mut initial_pair, locations1, locations2);
+    initial_pair.extrapolate_max_bounds(sys1, sys2);
+
+    let mut pairs = vec![initial_pair];
+    for step in steps {
+        let mut pair = pairs.last().unwrap().clone();
+        let transition1 = find_transition(
+            sys1,
+            &pair.locations1,
+            &step.action,
+            &step.transition1,
+            &step.target1,
+        );
+        let transition2 = find_transition(
+            sys2,
+            &pair.locations2,
+            &step.action,
+            &step.transition2,
+            &step.target2,
+        );
+        let (transition1, transition2) = match (transition1, transition2) {
+            (Some(transition1), Some(transition2)) => (transition1, transition2),
+            _ => break,
+        };
+
+        let mut zone = pair.take_zone();
+        zone = transition1.apply_guards(zone);
+        zone = transition2.apply_guards(zone);
+        zone = transition1.apply_updates(zone);
+        zone = transition2.apply_updates(zone);
+        zone = zone.up();
+        transition1.move_locations(&mut pair.locations1);
+        transition2.move_locations(&mut pair.locations2);
+        zone = pair.locations1.apply_invariants(zone);
+        zone = pair.locations2.apply_invariants(zone);
+        pair.set_zone(zone);
+        pair.extrapolate_max_bounds(sys1, sys2);
+        pairs.push(pair);
     }
+    pairs
+}
 
-    BuildResult::Success
+/// Finds the transition with `id` taking `action` from `locations` to `target`,
+/// which is a transition without an id staying in `locations` if `system` ignores the action
+fn find_transition(
+    system: &dyn TransitionSystem,
+    locations: &LocationTree,
+    action: &str,
+    id: &TransitionID,
+    target: &LocationID,
+) -> Option<Transition> {
+    system
+        .next_transitions_if_available(locations, action)
+        .into_iter()
+        .find(|transition| transition.id == *id && transition.target_locations.id == *target)
+        .or_else(|| {
+            (*id == TransitionID::None && locations.id == *target)
+                .then(|| Transition::without_id(locations, system.get_dim()))
+        })
 }
 
 fn prepare_init_state(
@@ -430,8 +646,17 @@ fn check_preconditions(
     sys2.precheck_sys_rep()
         .map_err(|e| e.to_precondition(sys1.as_ref(), sys2.as_ref()))?;
 
-    let s_outputs = sys1.get_output_actions();
-    let t_outputs = sys2.get_output_actions();
+    // Internal moves are not visible to the other side, so only the visible outputs must agree
+    let s_outputs: HashSet<String> = sys1
+        .get_output_actions()
+        .difference(&sys1.get_internal_actions())
+        .cloned()
+        .collect();
+    let t_outputs: HashSet<String> = sys2
+        .get_output_actions()
+        .difference(&sys2.get_internal_actions())
+        .cloned()
+        .collect();
 
     let s_inputs = sys1.get_input_actions();
     let t_inputs = sys2.get_input_actions();
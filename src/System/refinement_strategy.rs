@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use edbm::zones::OwnedFederation;
+use log::{debug, info};
+use serde::Serialize;
+
+use crate::ModelObjects::StatePair;
+use crate::TransitionSystems::{LocationID, TransitionID, TransitionSystemPtr};
+
+use super::cancellation::{self, Interrupted};
+use super::query_failures::{Action, RefinementFailure, StrategyResult, System};
+use super::refine::{
+    self, action_transitions, has_valid_state_pairs, prepare_refinement, RefinementActions,
+    RefinementContext, StatePairResult, StatePairStore,
+};
+use super::specifics::{SpecificEdge, SpecificState};
+
+/// A winning strategy for the environment in the refinement game of `system`, i.e. how it can force the refinement to fail.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RefinementStrategy {
+    pub system: String,
+    /// Whether the strategy answers every way the right side can match the moves of the left side and vice versa.
+    /// Otherwise only the answers leading to the failure of the refinement are followed.
+    pub complete: bool,
+    pub root: StrategyNode,
+}
+
+/// A node in the decision tree of a [RefinementStrategy].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StrategyNode {
+    /// Take `action` along the `edges` in `state`, and continue with the response taken by the other side.
+    /// Outputs are taken in the left system and inputs in the right system.
+    Move {
+        state: SpecificState,
+        action: Action,
+        edges: Vec<SpecificEdge>,
+        responses: Vec<StrategyResponse>,
+    },
+    /// The other side cannot match `action` in `state`, so the refinement fails.
+    CannotMatch {
+        state: SpecificState,
+        action: Action,
+    },
+    /// Matching `action` in `state` cuts delay solutions, so the refinement fails.
+    CutsDelaySolutions {
+        state: SpecificState,
+        action: Action,
+    },
+}
+
+/// The other side matched the last move along the `edges`, after which the strategy continues with `next`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StrategyResponse {
+    pub edges: Vec<SpecificEdge>,
+    pub next: StrategyNode,
+}
+
+/// Collects the state pairs built for a single move, so every answer to it can be followed
+#[derive(Default)]
+struct MovePairs(Vec<StatePair>);
+
+impl StatePairStore for MovePairs {
+    fn add(&mut self, pair: StatePair) -> Result<(), Interrupted> {
+        self.0.push(pair);
+        Ok(())
+    }
+}
+
+/// Synthesizes a strategy for the environment to make `sys1` fail to refine `sys2`.
+/// Returns [None] if the refinement holds, as the environment has no winning strategy then.
+///
+/// The shortest strategy is searched for up to the depth of the failure found by the refinement check.
+/// If no strategy answering every response is found within that depth, the strategy only follows the responses leading to a failure.
+/// Returns an error if neither is found, which only happens if the search does not agree with the refinement check.
+/// The outer error is returned if the query is interrupted, see [cancellation].
+pub fn synthesize_strategy(
+    sys1: &TransitionSystemPtr,
+    sys2: &TransitionSystemPtr,
+) -> Result<Result<StrategyResult, String>, Interrupted> {
+    let depth = match refine::check_refinement(sys1.clone(), sys2.clone(), false)? {
+        Ok(_) => return Ok(Ok(Ok(None))),
+        Err(RefinementFailure::Precondition(precondition)) => return Ok(Ok(Err(precondition))),
+        Err(
+            RefinementFailure::CannotMatch { path, .. }
+            | RefinementFailure::CutsDelaySolutions { path, .. },
+        ) => path.path.len(),
+    };
+    let system = System::refinement(sys1.as_ref(), sys2.as_ref()).name;
+
+    let (initial_pair, actions) = match prepare_refinement(sys1, sys2) {
+        Ok(Some(prepared)) => prepared,
+        _ => {
+            return Ok(Err(format!(
+                "The refinement {} failed without an initial state pair to search from",
+                system
+            )))
+        }
+    };
+    let initial_pair = Arc::new(initial_pair);
+
+    let mut search = StrategySearch {
+        sys1,
+        sys2,
+        actions,
+        complete: true,
+        explored: HashMap::new(),
+    };
+    let root = match search.shortest_win(&initial_pair, depth)? {
+        Some(root) => root,
+        None => {
+            info!("No complete strategy within depth {}", depth);
+            search.complete = false;
+            search.explored.clear();
+            match search.shortest_win(&initial_pair, depth)? {
+                Some(root) => root,
+                None => {
+                    return Ok(Err(format!(
+                        "No strategy forcing the failure of the refinement {} was found within {} moves",
+                        system, depth
+                    )))
+                }
+            }
+        }
+    };
+
+    Ok(Ok(Ok(Some(RefinementStrategy {
+        system,
+        complete: search.complete,
+        root,
+    }))))
+}
+
+/// The result of searching for a strategy from a state pair
+#[derive(Clone)]
+enum Outcome {
+    /// The strategy found within `depth` moves
+    Won(StrategyNode, usize),
+    /// There is no strategy within `depth` moves
+    Lost(usize),
+}
+
+/// A state pair the search has explored, identified by its locations and `zone`
+struct Explored {
+    zone: OwnedFederation,
+    outcome: Outcome,
+}
+
+struct StrategySearch<'a> {
+    sys1: &'a TransitionSystemPtr,
+    sys2: &'a TransitionSystemPtr,
+    actions: RefinementActions,
+    /// Whether every response to a move must be answered
+    complete: bool,
+    /// The outcomes of the explored state pairs by their location pairs,
+    /// so a state pair reached along several paths is only searched once for each depth
+    explored: HashMap<(LocationID, LocationID), Vec<Explored>>,
+}
+
+impl StrategySearch<'_> {
+    /// The outputs of the left side followed by the inputs of the right side, as `(action, is_input)`
+    fn moves(&self) -> Vec<(String, bool)> {
+        let outputs = self.actions.outputs.iter().cloned();
+        let inputs = self.actions.inputs.iter().cloned();
+
+        outputs
+            .map(|output| (output, false))
+            .chain(inputs.map(|input| (input, true)))
+            .collect()
+    }
+
+    fn state(&self, pair: &StatePair) -> SpecificState {
+        SpecificState::from_state_pair(pair, self.sys1.as_ref(), self.sys2.as_ref())
+    }
+
+    /// The edges of the transition with `id` in the left system if `left`, otherwise in the right system
+    fn edges(&self, id: &TransitionID, left: bool) -> Vec<SpecificEdge> {
+        let system = if left { self.sys1 } else { self.sys2 };
+        SpecificEdge::from_transition_id(id.clone(), system.as_ref())
+    }
+
+    /// Finds the strategy forcing a failure from `pair` in the fewest moves, up to `depth` moves
+    fn shortest_win(
+        &mut self,
+        pair: &Arc<StatePair>,
+        depth: usize,
+    ) -> Result<Option<StrategyNode>, Interrupted> {
+        for depth in 0..=depth {
+            if let Some(node) = self.win(pair, depth)? {
+                return Ok(Some(node));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds a strategy forcing a failure from `pair` within `depth` moves,
+    /// reusing the outcome of an explored state pair with the same locations and zone
+    fn win(
+        &mut self,
+        pair: &Arc<StatePair>,
+        depth: usize,
+    ) -> Result<Option<StrategyNode>, Interrupted> {
+        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        let explored = self.explored.entry(key).or_default();
+        let index = explored
+            .iter()
+            .position(|other| other.zone.equals(pair.ref_zone()));
+        if let Some(index) = index {
+            match &explored[index].outcome {
+                Outcome::Won(node, within) if *within <= depth => return Ok(Some(node.clone())),
+                Outcome::Lost(within) if *within >= depth => return Ok(None),
+                _ => {}
+            }
+        }
+
+        let node = self.search(pair, depth)?;
+        let outcome = match &node {
+            Some(node) => Outcome::Won(node.clone(), depth),
+            None => Outcome::Lost(depth),
+        };
+        let key = (pair.locations1.id.clone(), pair.locations2.id.clone());
+        let explored = self.explored.entry(key).or_default();
+        match index {
+            Some(index) => explored[index].outcome = outcome,
+            None => explored.push(Explored {
+                zone: pair.clone_zone(),
+                outcome,
+            }),
+        }
+        Ok(node)
+    }
+
+    /// Searches for a strategy forcing a failure from `pair` within `depth` moves
+    fn search(
+        &mut self,
+        pair: &Arc<StatePair>,
+        depth: usize,
+    ) -> Result<Option<StrategyNode>, Interrupted> {
+        cancellation::checkpoint()?;
+
+        // A move which cannot be matched wins right away, so those are looked for before going deeper
+        let mut moves = vec![];
+        for (action, is_input) in self.moves() {
+            let (transitions, matching) =
+                action_transitions(pair, &action, is_input, &self.actions, self.sys1, self.sys2);
+            let mut context = RefinementContext {
+                store: MovePairs::default(),
+                sys1: self.sys1,
+                sys2: self.sys2,
+            };
+            let result = has_valid_state_pairs(
+                &transitions,
+                &matching,
+                pair,
+                &action,
+                &mut context,
+                !is_input,
+            )?;
+
+            match result {
+                StatePairResult::Valid => moves.push((action, is_input, context.store.0)),
+                StatePairResult::EmptyTransition2s | StatePairResult::NotEmptyResult => {
+                    return Ok(Some(StrategyNode::CannotMatch {
+                        state: self.state(pair),
+                        action: Action::new(action, is_input),
+                    }))
+                }
+                StatePairResult::CutsDelaySolutions => {
+                    return Ok(Some(StrategyNode::CutsDelaySolutions {
+                        state: self.state(pair),
+                        action: Action::new(action, is_input),
+                    }))
+                }
+            }
+        }
+
+        if depth == 0 {
+            return Ok(None);
+        }
+
+        for (action, is_input, pairs) in moves {
+            // The new state pairs grouped by the transition of the side taking the action
+            let mut groups: Vec<(TransitionID, Vec<StatePair>)> = vec![];
+            for new_pair in pairs {
+                let step = new_pair
+                    .step
+                    .as_ref()
+                    .expect("Built state pairs have a step");
+                let id = if is_input {
+                    step.transition2.clone()
+                } else {
+                    step.transition1.clone()
+                };
+                match groups.iter_mut().find(|(other, _)| *other == id) {
+                    Some((_, group)) => group.push(new_pair),
+                    None => groups.push((id, vec![new_pair])),
+                }
+            }
+
+            for (id, group) in groups {
+                if let Some(responses) = self.answer(group, is_input, depth)? {
+                    debug!("Winning move {} from {}", action, pair);
+                    return Ok(Some(StrategyNode::Move {
+                        state: self.state(pair),
+                        edges: self.edges(&id, !is_input),
+                        action: Action::new(action, is_input),
+                        responses,
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds the strategies from the state pairs resulting from the responses to a move,
+    /// which must win from all of them if the search is `complete`, otherwise from one of them
+    fn answer(
+        &mut self,
+        pairs: Vec<StatePair>,
+        is_input: bool,
+        depth: usize,
+    ) -> Result<Option<Vec<StrategyResponse>>, Interrupted> {
+        let mut responses = vec![];
+        for new_pair in pairs {
+            let step = new_pair
+                .step
+                .clone()
+                .expect("Built state pairs have a step");
+            // The response is taken by the left side for inputs and the right side for outputs
+            let id = if is_input {
+                &step.transition1
+            } else {
+                &step.transition2
+            };
+
+            match self.win(&Arc::new(new_pair), depth - 1)? {
+                Some(next) => {
+                    responses.push(StrategyResponse {
+                        edges: self.edges(id, is_input),
+                        next,
+                    });
+                    if !self.complete {
+                        break;
+                    }
+                }
+                None if self.complete => return Ok(None),
+                None => {}
+            }
+        }
+
+        if responses.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(responses))
+        }
+    }
+}
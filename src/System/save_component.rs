@@ -33,8 +33,8 @@ pub fn combine_components(
     Component {
         name: "".to_string(),
         declarations: Declarations {
-            ints: HashMap::new(),
             clocks,
+            ..Declarations::empty()
         },
         locations,
         edges,
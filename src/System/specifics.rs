@@ -2,7 +2,10 @@ use std::{collections::HashMap, fmt};
 
 use edbm::util::constraints::{ClockIndex, Conjunction, Constraint, Disjunction};
 
-use crate::ModelObjects::{State, StatePair};
+use serde::Serialize;
+
+use crate::DataTypes::PassedStateList;
+use crate::ModelObjects::{State, StatePair, StatePairStep};
 use crate::{
     Simulation::decision::Decision,
     TransitionSystems::{
@@ -11,7 +14,7 @@ use crate::{
     },
 };
 
-use super::{query_failures::SystemType, reachability::Path};
+use super::{query_failures::SystemType, reachability::Path, refine};
 
 /// Intermediate representation of a [decision](Decision) from a `source` specific state to a `destination` specific state with an `action`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -50,6 +53,11 @@ fn transition_id_to_specific_edges(
                 transition_id_to_specific_edges(right, &**r, edges);
             }
         }
+        // Hiding and renaming do not change the locations and transitions of the system they wrap,
+        // so `system` behaves as that system
+        TransitionID::Hiding(_, id) | TransitionID::Renaming(_, _, id) => {
+            transition_id_to_specific_edges(*id, system, edges);
+        }
         TransitionID::Simple(edge_id) => {
             assert_eq!(system.get_composition_type(), CompositionType::Simple);
             if let ComponentInfoTree::Info(info) = system.comp_infos() {
@@ -77,6 +85,27 @@ impl SpecificDecision {
             destination_state: SpecificState::from_state(&decision.next_state, system),
         }
     }
+
+    /// Converts the `step` of a refinement check from `sys1` and `sys2` from the `source` state pair to the `destination` state pair,
+    /// with the edges taken in both systems
+    pub fn from_state_pair_step(
+        step: &StatePairStep,
+        source: &StatePair,
+        destination: &StatePair,
+        sys1: &dyn TransitionSystem,
+        sys2: &dyn TransitionSystem,
+    ) -> Self {
+        let mut edges = vec![];
+        transition_id_to_specific_edges(step.transition1.clone(), sys1, &mut edges);
+        transition_id_to_specific_edges(step.transition2.clone(), sys2, &mut edges);
+
+        Self {
+            source_state: SpecificState::from_state_pair(source, sys1, sys2),
+            action: step.action.clone(),
+            edges,
+            destination_state: SpecificState::from_state_pair(destination, sys1, sys2),
+        }
+    }
 }
 
 /// Intermediate representation of a [path](Path) of [decisions](SpecificDecision).
@@ -87,18 +116,68 @@ pub struct SpecificPath {
 
 impl SpecificPath {
     pub fn from_path(path: &Path, system: &dyn TransitionSystem) -> Self {
+        Self::from_decisions(&path.path, system)
+    }
+
+    /// Converts a sequence of `decisions` taken in `system`
+    pub fn from_decisions(decisions: &[Decision], system: &dyn TransitionSystem) -> Self {
         Self {
-            path: path
-                .path
+            path: decisions
                 .iter()
                 .map(|d| SpecificDecision::from_decision(d, system))
                 .collect(),
         }
     }
+
+    /// The path of a refinement check of `sys1` and `sys2` from the initial state pair to `pair`,
+    /// where the state pairs along the path are rebuilt from its steps
+    pub fn from_state_pair(
+        pair: &StatePair,
+        sys1: &dyn TransitionSystem,
+        sys2: &dyn TransitionSystem,
+    ) -> Self {
+        let steps = pair.steps();
+        let pairs = refine::replay_steps(&steps, sys1, sys2);
+        Self {
+            path: steps
+                .iter()
+                .zip(pairs.windows(2))
+                .map(|(step, pairs)| {
+                    SpecificDecision::from_state_pair_step(step, &pairs[0], &pairs[1], sys1, sys2)
+                })
+                .collect(),
+        }
+    }
 }
 
-/// Intermediate representation of a component instance. `id` is used to distinguish different instances of the same components in a system.
+/// Intermediate representation of a maximal path: a finite `prefix` followed by a `cycle` which can be repeated forever.
+/// An empty `cycle` means that the path ends in the last state of the `prefix`.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecificLasso {
+    pub prefix: SpecificPath,
+    pub cycle: SpecificPath,
+}
+
+impl SpecificLasso {
+    pub fn from_paths(prefix: &Path, cycle: &Path, system: &dyn TransitionSystem) -> Self {
+        Self {
+            prefix: SpecificPath::from_path(prefix, system),
+            cycle: SpecificPath::from_path(cycle, system),
+        }
+    }
+
+    /// The number of transitions in the prefix and one repetition of the cycle
+    pub fn len(&self) -> usize {
+        self.prefix.path.len() + self.cycle.path.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Intermediate representation of a component instance. `id` is used to distinguish different instances of the same components in a system.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificComp {
     pub name: String,
     pub id: u32,
@@ -111,7 +190,7 @@ impl SpecificComp {
 }
 
 /// Intermediate representation of an [edge](crate::ModelObjects::component::Edge) in a component instance.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificEdge {
     pub comp: SpecificComp,
     pub edge_id: String,
@@ -128,10 +207,17 @@ impl SpecificEdge {
             edge_id: edge_id.into(),
         }
     }
+
+    /// The edges of the components in `system` which make up the transition with `id`
+    pub fn from_transition_id(id: TransitionID, system: &dyn TransitionSystem) -> Vec<Self> {
+        let mut edges = vec![];
+        transition_id_to_specific_edges(id, system, &mut edges);
+        edges
+    }
 }
 
 /// Intermediate representaton of a [disjunction](Disjunction) of conjunctions of clock constraints.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificDisjunction {
     pub conjunctions: Vec<SpecificConjunction>,
 }
@@ -148,8 +234,21 @@ impl SpecificDisjunction {
     }
 }
 
+impl fmt::Display for SpecificDisjunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.conjunctions.is_empty() {
+            return write!(f, "false");
+        }
+        let conjunctions: Vec<String> = self.conjunctions.iter().map(|c| c.to_string()).collect();
+        match conjunctions.len() {
+            1 => write!(f, "{}", conjunctions[0]),
+            _ => write!(f, "({})", conjunctions.join(") || (")),
+        }
+    }
+}
+
 /// Intermediate representaton of a [conjunction](Conjunction) of clock constraints.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificConjunction {
     pub constraints: Vec<SpecificConstraint>,
 }
@@ -166,8 +265,18 @@ impl SpecificConjunction {
     }
 }
 
+impl fmt::Display for SpecificConjunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.constraints.is_empty() {
+            return write!(f, "true");
+        }
+        let constraints: Vec<String> = self.constraints.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", constraints.join(" && "))
+    }
+}
+
 /// Intermediate representation of a [clock](ClockIndex) used in a constraint.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum SpecificClockVar {
     /// The zero clock.
     Zero,
@@ -177,8 +286,20 @@ pub enum SpecificClockVar {
     SystemClock(ClockIndex),
 }
 
+impl fmt::Display for SpecificClockVar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecificClockVar::Zero => write!(f, "0"),
+            SpecificClockVar::ComponentClock(clock) => {
+                write!(f, "{}.{}", clock.comp.name, clock.name)
+            }
+            SpecificClockVar::SystemClock(index) => write!(f, "c{}", index),
+        }
+    }
+}
+
 /// Intermediate representation of a clock [constraint](Constraint) of the form `i-j <?= c`.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificConstraint {
     pub i: SpecificClockVar,
     pub j: SpecificClockVar,
@@ -213,8 +334,28 @@ impl SpecificConstraint {
     }
 }
 
+impl fmt::Display for SpecificConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.i, &self.j) {
+            // A lower bound 0-j <?= c is written as j >?= -c
+            (SpecificClockVar::Zero, j) => {
+                let op = if self.strict { ">" } else { ">=" };
+                write!(f, "{} {} {}", j, op, -self.c)
+            }
+            (i, SpecificClockVar::Zero) => {
+                let op = if self.strict { "<" } else { "<=" };
+                write!(f, "{} {} {}", i, op, self.c)
+            }
+            (i, j) => {
+                let op = if self.strict { "<" } else { "<=" };
+                write!(f, "{} - {} {} {}", i, j, op, self.c)
+            }
+        }
+    }
+}
+
 /// Intermediate representation of a [State] in a system with its `locations` and zone `constraints`.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificState {
     pub locations: SpecificLocation,
     pub constraints: SpecificDisjunction,
@@ -222,7 +363,7 @@ pub struct SpecificState {
 
 /// Intermediate representation of a [LocationID](crate::TransitionSystems::location_id::LocationID) in a system.
 /// It is a binary tree with either [component](SpecificComp) locations or [special](SpecialLocation) locations at the leaves.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum SpecificLocation {
     /// A location in a component instance.
     ComponentLocation {
@@ -273,7 +414,7 @@ impl fmt::Display for SpecificLocation {
 }
 
 /// Intermediate representation of a [special](crate::TransitionSystems::location_id::LocationID::Special) location. E.g. `Error` or `Universal` from a quotient.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum SpecialLocation {
     Universal,
     Error,
@@ -321,6 +462,42 @@ impl SpecificState {
     }
 }
 
+/// Intermediate representation of the relation found by a successful refinement check.
+/// Each pair of related locations is represented as a [SpecificState] with a refinement location and the zone in which the locations are related.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct SpecificRelation {
+    pub pairs: Vec<SpecificState>,
+}
+
+impl SpecificRelation {
+    /// Create a new [SpecificRelation] from the passed list of a refinement check between `sys1` and `sys2`.
+    pub fn from_passed_list(
+        passed_list: &PassedStateList,
+        sys1: &dyn TransitionSystem,
+        sys2: &dyn TransitionSystem,
+    ) -> Self {
+        let clock_map = specific_clock_comp_map_composite(sys1, sys2);
+
+        let mut pairs: Vec<SpecificState> = passed_list
+            .iter()
+            .map(|((id1, id2), zone)| SpecificState {
+                locations: SpecificLocation::BranchLocation(
+                    Box::new(specific_location(id1, sys1)),
+                    Box::new(specific_location(id2, sys2)),
+                    SystemType::Refinement,
+                ),
+                constraints: SpecificDisjunction::from_disjunction(
+                    zone.minimal_constraints(),
+                    &clock_map,
+                ),
+            })
+            .collect();
+        pairs.sort_by_key(|pair| pair.locations.to_string());
+
+        Self { pairs }
+    }
+}
+
 impl fmt::Display for SpecificState {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let locs = &self.locations;
@@ -332,7 +509,7 @@ impl fmt::Display for SpecificState {
 }
 
 /// Intermediate representation of a clock name in a specific [component instance](SpecificComp).
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct SpecificClock {
     pub name: String,
     pub comp: SpecificComp,
@@ -417,8 +594,17 @@ pub fn specific_location(location_id: &LocationID, sys: &dyn TransitionSystem) -
                     location_id: loc_id.clone(),
                 }
             }
+            LocationID::Valued(..) => {
+                let info = infos.info();
+                SpecificLocation::ComponentLocation {
+                    comp: SpecificComp::new(info.name.clone(), info.id),
+                    location_id: location_id.to_string(),
+                }
+            }
             LocationID::Special(kind) => SpecificLocation::SpecialLocation(kind.clone()),
-            LocationID::AnyLocation => unreachable!("AnyLocation should not be used in a state"),
+            LocationID::AnyLocation | LocationID::AnyValuation(_) => {
+                unreachable!("Partial locations should not be used in a state")
+            }
         }
     }
     inner(location_id, sys.comp_infos())
@@ -0,0 +1,664 @@
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::{ClockIndex, Inequality};
+use edbm::zones::OwnedFederation;
+
+use super::cancellation::{self, Interrupted};
+use super::limits;
+use super::query_failures::{TemporalFailure, TemporalResult};
+use super::reachability::{remove_existing_subsets_of_zone, zone_subset_of_existing_zones, Path};
+use super::specifics::SpecificLasso;
+use crate::ModelObjects::Expressions::TemporalOperator;
+use crate::ModelObjects::{State, Transition};
+use crate::Simulation::decision::Decision;
+use crate::TransitionSystems::{LocationID, LocationTree, TransitionSystemPtr};
+use std::collections::{HashMap, VecDeque};
+
+/// The states whose locations match the (possibly partial) locations of `state` and whose clock valuations are in its zone,
+/// or every other state if `negated`.
+#[derive(Clone)]
+pub(crate) struct StatePredicate {
+    state: State,
+    negated: bool,
+}
+
+impl StatePredicate {
+    pub fn new(state: State) -> Self {
+        StatePredicate {
+            state,
+            negated: false,
+        }
+    }
+
+    pub fn negate(&self) -> Self {
+        StatePredicate {
+            state: self.state.clone(),
+            negated: !self.negated,
+        }
+    }
+
+    /// Restricts `zone` in `location` to the clock valuations satisfying the predicate
+    pub fn restrict(&self, location: &LocationTree, zone: OwnedFederation) -> OwnedFederation {
+        let matches = location.compare_partial_locations(&self.state.decorated_locations);
+        match (matches, self.negated) {
+            (true, false) => zone.intersection(self.state.zone_ref()),
+            (true, true) => zone.subtraction(self.state.zone_ref()),
+            (false, false) => OwnedFederation::empty(zone.dim()),
+            (false, true) => zone,
+        }
+    }
+
+    pub fn is_satisfied_in(&self, state: &State) -> bool {
+        !self
+            .restrict(&state.decorated_locations, state.zone_ref().clone())
+            .is_empty()
+    }
+}
+
+/// The symbolic states of a system reachable from its initial state.
+/// If a `constraint` is given, only the states satisfying it are part of the graph,
+/// so every path in the graph only visits states satisfying the constraint.
+///
+/// The system must have a `divergence_clock` which none of its components use.
+/// It measures the time passed since it was last reset, so the search for a maximal path can reject cycles in which time cannot diverge.
+pub(crate) struct ZoneGraph<'a> {
+    system: &'a TransitionSystemPtr,
+    actions: Vec<String>,
+    /// The clock constants of the predicates, so extrapolation cannot change whether a state satisfies them
+    bounds: Bounds,
+    constraint: Option<StatePredicate>,
+    divergence_clock: ClockIndex,
+}
+
+impl<'a> ZoneGraph<'a> {
+    pub fn new(
+        system: &'a TransitionSystemPtr,
+        predicates: &[&StatePredicate],
+        constraint: Option<StatePredicate>,
+        divergence_clock: ClockIndex,
+    ) -> Self {
+        let mut actions: Vec<String> = system.get_actions().into_iter().collect();
+        actions.sort();
+
+        let mut bounds = Bounds::new(system.get_dim());
+        for predicate in predicates {
+            bounds.add_bounds(&predicate.state.zone_ref().get_bounds());
+        }
+        // Only whether the divergence clock has reached 1 matters
+        bounds.add_lower(divergence_clock, 1);
+        bounds.add_upper(divergence_clock, 1);
+
+        ZoneGraph {
+            system,
+            actions,
+            bounds,
+            constraint,
+            divergence_clock,
+        }
+    }
+
+    fn restrict(&self, location: &LocationTree, zone: OwnedFederation) -> OwnedFederation {
+        match &self.constraint {
+            Some(constraint) => constraint.restrict(location, zone),
+            None => zone,
+        }
+    }
+
+    /// Lets time pass from `zone` in `location` for as long as the invariant and the constraint allow.
+    /// The constraint is applied both before and after the delay, as the delay must start in a state satisfying it.
+    fn delay(&self, location: LocationTree, zone: OwnedFederation) -> Option<State> {
+        let zone = self.restrict(&location, zone);
+        if zone.is_empty() {
+            return None;
+        }
+        let zone = location.apply_invariants(zone.up());
+        let zone = self.restrict(&location, zone);
+        if zone.is_empty() {
+            return None;
+        }
+
+        let mut state = State::new(location, zone);
+        state.extrapolate_max_bounds_with_extra_bounds(self.system.as_ref(), &self.bounds);
+        Some(state)
+    }
+
+    pub fn initial_state(&self) -> Option<State> {
+        let state = self.system.get_initial_state()?;
+        let zone = state.zone_ref().clone();
+        self.delay(state.decorated_locations, zone)
+    }
+
+    /// Restricts `state` to `predicate` and lets time pass from it, to use it as the start of a search in this graph
+    pub fn start_from(&self, state: &State, predicate: &StatePredicate) -> Option<State> {
+        let location = state.decorated_locations.clone();
+        let zone = predicate.restrict(&location, state.zone_ref().clone());
+        self.delay(location, zone)
+    }
+
+    /// Takes `transition` from the clock valuations `zone` in `location`, followed by a delay
+    fn take(
+        &self,
+        location: &LocationTree,
+        zone: OwnedFederation,
+        transition: &Transition,
+    ) -> Option<State> {
+        let zone = transition.apply_guards(zone);
+        if zone.is_empty() {
+            return None;
+        }
+        let mut location = location.clone();
+        transition.move_locations(&mut location);
+        let zone = location.apply_invariants(transition.apply_updates(zone));
+        self.delay(location, zone)
+    }
+
+    /// Returns the states reachable from `state` by taking a transition followed by a delay, along with the transition and its action
+    pub fn successors(&self, state: &State) -> Vec<(Transition, String, State)> {
+        let mut successors = Vec::new();
+        for action in &self.actions {
+            for transition in self
+                .system
+                .next_transitions(&state.decorated_locations, action)
+            {
+                let location = &state.decorated_locations;
+                if let Some(next_state) = self.take(location, state.zone_ref().clone(), &transition)
+                {
+                    successors.push((transition, action.clone(), next_state));
+                }
+            }
+        }
+        successors
+    }
+
+    /// Returns the successors of `state` like [ZoneGraph::successors], split on whether the divergence clock has reached 1.
+    /// The transitions taken where it has are progressing: they reset the divergence clock,
+    /// so at least one time unit passes on every repetition of a cycle with a progressing transition.
+    fn timed_successors(&self, state: &State) -> Vec<(Transition, String, State, bool)> {
+        let clock = self.divergence_clock;
+        let location = &state.decorated_locations;
+        let waiting = state
+            .zone_ref()
+            .clone()
+            .constrain(clock, 0, Inequality::LS(1));
+        let progressed = state
+            .zone_ref()
+            .clone()
+            .constrain(0, clock, Inequality::LE(-1))
+            .update_clock_val(clock, 0);
+
+        let mut successors = Vec::new();
+        for action in &self.actions {
+            for transition in self.system.next_transitions(location, action) {
+                for (zone, progressing) in [(&waiting, false), (&progressed, true)] {
+                    if let Some(next_state) = self.take(location, zone.clone(), &transition) {
+                        successors.push((
+                            transition.clone(),
+                            action.clone(),
+                            next_state,
+                            progressing,
+                        ));
+                    }
+                }
+            }
+        }
+        successors
+    }
+
+    /// Checks whether a maximal path can end in `state` without leaving the constraint,
+    /// either because it can delay forever, or because it can delay until no transition is enabled.
+    /// It suffices that some of the clock valuations of `state` end the path.
+    pub fn ends_maximal_path(&self, state: &State) -> bool {
+        let dim = self.system.get_dim();
+        let location = &state.decorated_locations;
+        let invariant = location.apply_invariants(OwnedFederation::universe(dim));
+        let allowed = self.restrict(location, invariant.clone());
+
+        // The clock valuations which can delay forever without leaving the allowed states
+        let violating = OwnedFederation::universe(dim).subtraction(&allowed).down();
+        let unbounded = OwnedFederation::universe(dim).subtraction(&violating);
+        if state.zone_ref().has_intersection(&unbounded) {
+            return true;
+        }
+
+        // The clock valuations which can delay until a transition is enabled or the constraint is left, so the path goes on
+        let mut continuing = invariant.clone().subtraction(&allowed);
+        for action in &self.actions {
+            for transition in self.system.next_transitions(location, action) {
+                continuing += transition.get_allowed_federation();
+            }
+        }
+        let continuing = continuing.intersection(&invariant).down();
+        !state.zone_ref().clone().subtraction(&continuing).is_empty()
+    }
+}
+
+/// A state in a search, along with the transition and action taken from the state at index `parent` to reach it
+struct Node {
+    state: State,
+    parent: Option<(usize, Transition, String)>,
+}
+
+/// Makes the path of decisions from `start` to `end`, where `start` must be an ancestor of `end`
+fn make_path(nodes: &[Node], start: usize, end: usize) -> Path {
+    let mut path = Vec::new();
+    let mut index = end;
+    while index != start {
+        let (parent, transition, action) = nodes[index]
+            .parent
+            .as_ref()
+            .expect("The start of a path should be an ancestor of its end");
+        path.push(Decision {
+            state: nodes[*parent].state.clone(),
+            action: action.clone(),
+            transition: Some(transition.clone()),
+            next_state: nodes[index].state.clone(),
+        });
+        index = *parent;
+    }
+    path.reverse();
+    Path { path }
+}
+
+/// Runs a BFS of the graph, calling `visit` on every explored state until it returns a result.
+/// States which are included in an already explored state are skipped, as in [reachability](super::reachability).
+fn explore<T>(
+    graph: &ZoneGraph,
+    mut visit: impl FnMut(&[Node], usize) -> Result<Option<T>, Interrupted>,
+) -> Result<Option<T>, Interrupted> {
+    let initial_state = match graph.initial_state() {
+        Some(initial_state) => initial_state,
+        None => return Ok(None),
+    };
+
+    let mut visited_states: HashMap<LocationID, Vec<OwnedFederation>> = HashMap::new();
+    visited_states.insert(
+        initial_state.decorated_locations.id.clone(),
+        vec![initial_state.zone_ref().clone()],
+    );
+
+    let mut nodes = vec![Node {
+        state: initial_state,
+        parent: None,
+    }];
+    let mut frontier = VecDeque::from([0]);
+
+    while let Some(index) = frontier.pop_front() {
+        cancellation::checkpoint()?;
+        if let Some(result) = visit(&nodes, index)? {
+            return Ok(Some(result));
+        }
+
+        for (transition, action, state) in graph.successors(&nodes[index].state) {
+            let existing_zones = visited_states
+                .entry(state.decorated_locations.id.clone())
+                .or_default();
+            if zone_subset_of_existing_zones(state.zone_ref(), existing_zones) {
+                continue;
+            }
+            remove_existing_subsets_of_zone(state.zone_ref(), existing_zones);
+            existing_zones.push(state.zone_ref().clone());
+            limits::record_state(state.zone_ref())?;
+            limits::check_zones_per_location(existing_zones.len())?;
+
+            nodes.push(Node {
+                state,
+                parent: Some((index, transition, action)),
+            });
+            frontier.push_back(nodes.len() - 1);
+        }
+    }
+    Ok(None)
+}
+
+/// Makes the path from the initial state to the state at `index`, whose last state is restricted to `predicate`
+fn make_path_to(nodes: &[Node], index: usize, predicate: &StatePredicate) -> Path {
+    let mut path = make_path(nodes, 0, index);
+    if let Some(decision) = path.path.last_mut() {
+        let location = decision.next_state.decorated_locations.clone();
+        decision
+            .next_state
+            .update_zone(|zone| predicate.restrict(&location, zone));
+    }
+    path
+}
+
+/// Returns the path from the initial state to a state satisfying `target`, if any
+pub(crate) fn find_path_to(
+    graph: &ZoneGraph,
+    target: &StatePredicate,
+) -> Result<Option<Path>, Interrupted> {
+    explore(graph, |nodes, index| {
+        Ok(target
+            .is_satisfied_in(&nodes[index].state)
+            .then(|| make_path_to(nodes, index, target)))
+    })
+}
+
+/// Searches for a maximal path in the graph, returning the prefix of the path and the cycle repeated after it.
+/// The cycle is empty if the path ends in a state, see [ZoneGraph::ends_maximal_path].
+/// Otherwise time diverges on the path, as the cycle has a progressing transition, see [ZoneGraph::timed_successors].
+///
+/// The search is a nested DFS: when a state is done, a second DFS is run from the target of each of its progressing transitions,
+/// looking for a path back to a state on the stack of the first DFS, which closes a cycle through the progressing transition.
+/// States are only considered equal if their zones are equal, as including states in larger ones could introduce cycles that do not exist.
+pub(crate) fn find_maximal_path(graph: &ZoneGraph) -> Result<Option<(Path, Path)>, Interrupted> {
+    match graph.initial_state() {
+        Some(initial_state) => find_maximal_path_from(graph, initial_state),
+        None => Ok(None),
+    }
+}
+
+/// A state on the stack of the first DFS of [find_maximal_path], with the successors left to explore
+/// and the progressing transitions taken from it so far along with the index of their target
+struct Frame {
+    index: usize,
+    successors: std::vec::IntoIter<(Transition, String, State, bool)>,
+    progressing: Vec<(Transition, String, usize)>,
+}
+
+/// Returns the index of the node with the same locations and zone as `state`, if it has been found
+fn find_node(
+    visited_states: &HashMap<LocationID, Vec<usize>>,
+    nodes: &[Node],
+    state: &State,
+) -> Option<usize> {
+    visited_states
+        .get(&state.decorated_locations.id)?
+        .iter()
+        .copied()
+        .find(|&other| nodes[other].state.zone_ref().equals(state.zone_ref()))
+}
+
+/// Runs the search of [find_maximal_path] from `initial_state` instead of the initial state of the system
+fn find_maximal_path_from(
+    graph: &ZoneGraph,
+    initial_state: State,
+) -> Result<Option<(Path, Path)>, Interrupted> {
+    if graph.ends_maximal_path(&initial_state) {
+        return Ok(Some((Path { path: vec![] }, Path { path: vec![] })));
+    }
+
+    let mut visited_states: HashMap<LocationID, Vec<usize>> = HashMap::new();
+    visited_states.insert(initial_state.decorated_locations.id.clone(), vec![0]);
+
+    let mut stack = vec![Frame {
+        index: 0,
+        successors: graph.timed_successors(&initial_state).into_iter(),
+        progressing: vec![],
+    }];
+    let mut on_stack = vec![true];
+    // The states visited by the second DFS, which cannot close a cycle when visited again
+    let mut returned = vec![false];
+    let mut nodes = vec![Node {
+        state: initial_state,
+        parent: None,
+    }];
+
+    while let Some(frame) = stack.last_mut() {
+        cancellation::checkpoint()?;
+        let index = frame.index;
+        let (transition, action, state, progressing) = match frame.successors.next() {
+            Some(successor) => successor,
+            None => {
+                // Every state reachable from this one has been explored, so a cycle through one of its progressing transitions
+                // must return to a state on the stack, which includes this one
+                let frame = stack.pop().unwrap();
+                for (transition, action, target) in frame.progressing {
+                    let found = find_path_to_stack(
+                        graph,
+                        &nodes,
+                        &visited_states,
+                        &on_stack,
+                        &mut returned,
+                        target,
+                    )?;
+                    if let Some((end, mut rest)) = found {
+                        let mut cycle = make_path(&nodes, end, index);
+                        cycle.path.push(Decision {
+                            state: nodes[index].state.clone(),
+                            action,
+                            transition: Some(transition),
+                            next_state: nodes[target].state.clone(),
+                        });
+                        cycle.path.append(&mut rest.path);
+                        return Ok(Some((make_path(&nodes, 0, end), cycle)));
+                    }
+                }
+                on_stack[index] = false;
+                continue;
+            }
+        };
+
+        let (target, is_new) = match find_node(&visited_states, &nodes, &state) {
+            // The state has already been found
+            Some(target) => (target, false),
+            None => {
+                let new_index = nodes.len();
+                visited_states
+                    .entry(state.decorated_locations.id.clone())
+                    .or_default()
+                    .push(new_index);
+                nodes.push(Node {
+                    state,
+                    parent: Some((index, transition.clone(), action.clone())),
+                });
+                on_stack.push(false);
+                returned.push(false);
+                if graph.ends_maximal_path(&nodes[new_index].state) {
+                    return Ok(Some((
+                        make_path(&nodes, 0, new_index),
+                        Path { path: vec![] },
+                    )));
+                }
+                (new_index, true)
+            }
+        };
+        if progressing {
+            stack
+                .last_mut()
+                .unwrap()
+                .progressing
+                .push((transition, action, target));
+        }
+        if is_new {
+            on_stack[target] = true;
+            stack.push(Frame {
+                index: target,
+                successors: graph.timed_successors(&nodes[target].state).into_iter(),
+                progressing: vec![],
+            });
+        }
+    }
+    Ok(None)
+}
+
+/// Runs the second DFS of [find_maximal_path] from `start`, looking for a state on the stack of the first DFS.
+/// Returns the index of the state found and the path to it from `start`.
+/// The states visited are marked as `returned`, as a later search reaching them cannot find a state on the stack from them either.
+fn find_path_to_stack(
+    graph: &ZoneGraph,
+    nodes: &[Node],
+    visited_states: &HashMap<LocationID, Vec<usize>>,
+    on_stack: &[bool],
+    returned: &mut [bool],
+    start: usize,
+) -> Result<Option<(usize, Path)>, Interrupted> {
+    if on_stack[start] {
+        return Ok(Some((start, Path { path: vec![] })));
+    }
+    if returned[start] {
+        return Ok(None);
+    }
+    returned[start] = true;
+
+    let mut path: Vec<Decision> = vec![];
+    let mut stack = vec![(
+        start,
+        graph.timed_successors(&nodes[start].state).into_iter(),
+    )];
+    while let Some((index, successors)) = stack.last_mut() {
+        cancellation::checkpoint()?;
+        let index = *index;
+        let (transition, action, state) = match successors.next() {
+            Some((transition, action, state, _)) => (transition, action, state),
+            None => {
+                stack.pop();
+                path.pop();
+                continue;
+            }
+        };
+        // Every state reachable from the start has been found by the first DFS
+        let target = match find_node(visited_states, nodes, &state) {
+            Some(target) => target,
+            None => continue,
+        };
+        if returned[target] && !on_stack[target] {
+            continue;
+        }
+        path.push(Decision {
+            state: nodes[index].state.clone(),
+            action,
+            transition: Some(transition),
+            next_state: nodes[target].state.clone(),
+        });
+        if on_stack[target] {
+            return Ok(Some((target, Path { path })));
+        }
+        returned[target] = true;
+        stack.push((
+            target,
+            graph.timed_successors(&nodes[target].state).into_iter(),
+        ));
+    }
+    Ok(None)
+}
+
+/// Makes the lasso of `prefix` and `cycle` in `system`, leaving out the divergence clock as it is not part of the system
+fn to_lasso(
+    (prefix, cycle): (Path, Path),
+    system: &TransitionSystemPtr,
+    divergence_clock: ClockIndex,
+) -> SpecificLasso {
+    let hide_clock = |mut path: Path| {
+        for decision in &mut path.path {
+            decision
+                .state
+                .update_zone(|zone| zone.free_clock(divergence_clock));
+            decision
+                .next_state
+                .update_zone(|zone| zone.free_clock(divergence_clock));
+        }
+        path
+    };
+    SpecificLasso::from_paths(&hide_clock(prefix), &hide_clock(cycle), system.as_ref())
+}
+
+/// Checks the TCTL property `operator` `predicate` in the system, where `divergence_clock` is a clock not used by it, see [ZoneGraph]
+pub fn check_temporal(
+    operator: TemporalOperator,
+    system: &TransitionSystemPtr,
+    predicate: State,
+    divergence_clock: ClockIndex,
+) -> Result<TemporalResult, Interrupted> {
+    let predicate = StatePredicate::new(predicate);
+    let negated = predicate.negate();
+    let to_lasso = |lasso: (Path, Path)| to_lasso(lasso, system, divergence_clock);
+    let finite = |path: Path| (path, Path { path: vec![] });
+
+    Ok(match operator {
+        // E<> p: Some reachable state satisfies p
+        TemporalOperator::Possibly => {
+            let graph = ZoneGraph::new(system, &[&predicate], None, divergence_clock);
+            match find_path_to(&graph, &predicate)? {
+                Some(path) => Ok(Some(to_lasso(finite(path)))),
+                None => Err(TemporalFailure::NoWitness(operator)),
+            }
+        }
+        // A[] p = not E<> not p
+        TemporalOperator::Invariantly => {
+            let graph = ZoneGraph::new(system, &[&predicate], None, divergence_clock);
+            match find_path_to(&graph, &negated)? {
+                Some(path) => Err(TemporalFailure::Counterexample {
+                    operator,
+                    counterexample: to_lasso(finite(path)),
+                }),
+                None => Ok(None),
+            }
+        }
+        // E[] p: Some maximal path only visits states satisfying p
+        TemporalOperator::EventuallyAlways => {
+            let graph = ZoneGraph::new(
+                system,
+                &[&predicate],
+                Some(predicate.clone()),
+                divergence_clock,
+            );
+            match find_maximal_path(&graph)? {
+                Some(lasso) => Ok(Some(to_lasso(lasso))),
+                None => Err(TemporalFailure::NoWitness(operator)),
+            }
+        }
+        // A<> p = not E[] not p
+        TemporalOperator::Potentially => {
+            let graph = ZoneGraph::new(system, &[&predicate], Some(negated), divergence_clock);
+            match find_maximal_path(&graph)? {
+                Some(lasso) => Err(TemporalFailure::Counterexample {
+                    operator,
+                    counterexample: to_lasso(lasso),
+                }),
+                None => Ok(None),
+            }
+        }
+    })
+}
+
+/// Checks the response property `premise --> response`, i.e. that every path reaching a state satisfying `premise`
+/// eventually reaches a state satisfying `response` (`A[] (premise imply A<> response)`).
+///
+/// For every reachable state satisfying `premise` and not `response`, a maximal path never satisfying `response` is searched for,
+/// in which time diverges unless it ends in a state, see [find_maximal_path].
+/// Such a path is returned as a counterexample, prefixed by the path to the state satisfying `premise`.
+pub fn check_leads_to(
+    system: &TransitionSystemPtr,
+    premise: State,
+    response: State,
+    divergence_clock: ClockIndex,
+) -> Result<TemporalResult, Interrupted> {
+    let premise = StatePredicate::new(premise);
+    let response = StatePredicate::new(response);
+    let not_response = response.negate();
+
+    let predicates = [&premise, &response];
+    let graph = ZoneGraph::new(system, &predicates, None, divergence_clock);
+    let nested_graph = ZoneGraph::new(
+        system,
+        &predicates,
+        Some(not_response.clone()),
+        divergence_clock,
+    );
+
+    let counterexample = explore(&graph, |nodes, index| {
+        let state = &nodes[index].state;
+        if !premise.is_satisfied_in(state) {
+            return Ok(None);
+        }
+        let start = match nested_graph.start_from(state, &premise) {
+            Some(start) => start,
+            None => return Ok(None),
+        };
+        let (mut prefix, cycle) = match find_maximal_path_from(&nested_graph, start)? {
+            Some(lasso) => lasso,
+            None => return Ok(None),
+        };
+
+        let mut path = make_path_to(nodes, index, &premise);
+        path.path.append(&mut prefix.path);
+        Ok(Some((path, cycle)))
+    })?;
+
+    Ok(match counterexample {
+        Some((prefix, cycle)) => Err(TemporalFailure::NotLeadsTo {
+            counterexample: to_lasso((prefix, cycle), system, divergence_clock),
+        }),
+        None => Ok(None),
+    })
+}
@@ -1,4 +1,7 @@
-use crate::ModelObjects::{Component, DeclarationProvider, Declarations, State, Transition};
+use crate::ModelObjects::{
+    Component, DeclarationProvider, Declarations, Location, LocationType, State, Transition,
+    Valuation,
+};
 use crate::System::local_consistency::{self};
 use crate::System::query_failures::{
     ActionFailure, ConsistencyResult, DeterminismResult, SystemRecipeFailure,
@@ -7,15 +10,27 @@ use crate::System::specifics::SpecificLocation;
 use crate::TransitionSystems::{LocationTree, TransitionSystem, TransitionSystemPtr};
 use edbm::util::bounds::Bounds;
 use edbm::util::constraints::ClockIndex;
+use edbm::zones::OwnedFederation;
 use std::collections::hash_set::HashSet;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::iter::FromIterator;
 
 use super::transition_system::ComponentInfoTree;
-use super::{CompositionType, LocationID};
+use super::{CompositionType, LocationID, TransitionID};
 
 type Action = String;
 
+/// The largest number of pairs of a location and a valuation a component with integer variables is compiled into
+pub const MAX_VALUED_LOCATIONS: usize = 10_000;
+
+/// The locations, the outgoing transitions of each location, the initial location and the clock bounds of a component.
+type CompiledLocations = (
+    HashMap<LocationID, LocationTree>,
+    HashMap<LocationID, Vec<(Action, Transition)>>,
+    Option<LocationTree>,
+    Bounds,
+);
+
 #[derive(Clone)]
 pub struct ComponentInfo {
     pub name: String,
@@ -48,6 +63,31 @@ impl CompiledComponent {
                 .map_err(|e| e.to_simple_failure(&component.name))?;
         }
 
+        let (locations, location_edges, initial_location, max_bounds) =
+            if component.declarations.ints.is_empty() {
+                Self::compile_locations(&component, dim)
+            } else {
+                Self::compile_valued_locations(&component, &inputs, dim)
+                    .map_err(|err| SystemRecipeFailure::invalid(err, &component.name))?
+            };
+
+        Ok(Box::new(CompiledComponent {
+            inputs,
+            outputs,
+            locations,
+            location_edges,
+            initial_location,
+            dim,
+            comp_info: ComponentInfo {
+                name: component.name,
+                declarations: component.declarations,
+                max_bounds,
+                id,
+            },
+        }))
+    }
+
+    fn compile_locations(component: &Component, dim: ClockIndex) -> CompiledLocations {
         let locations: HashMap<LocationID, LocationTree> = component
             .locations
             .iter()
@@ -68,7 +108,7 @@ impl CompiledComponent {
         log::debug!("Edges: {:?}", component.edges);
         for edge in &component.edges {
             let id = LocationID::Simple(edge.source_location.clone());
-            let transition = Transition::from_component_and_edge(&component, edge, dim);
+            let transition = Transition::from_component_and_edge(component, edge, dim);
             location_edges
                 .get_mut(&id)
                 .unwrap()
@@ -78,20 +118,106 @@ impl CompiledComponent {
         let initial_location = locations.values().find(|loc| loc.is_initial()).cloned();
 
         let max_bounds = component.get_max_bounds(dim);
-        Ok(Box::new(CompiledComponent {
-            inputs,
-            outputs,
-            locations,
-            location_edges,
-            initial_location,
-            dim,
-            comp_info: ComponentInfo {
-                name: component.name,
-                declarations: component.declarations,
-                max_bounds,
-                id,
-            },
-        }))
+        (locations, location_edges, initial_location, max_bounds)
+    }
+
+    /// Compiles a component with integer variables by exploring the valuations reachable from the initial location.
+    /// Every pair of a location and a valuation becomes a location of its own, so the integer variables are part of the state.
+    ///
+    /// As the input enabling of the component depends on the valuations, the missing inputs are added here.
+    /// Fails if more than [MAX_VALUED_LOCATIONS] pairs are reachable, as the ranges of the variables are then too large to unroll.
+    fn compile_valued_locations(
+        component: &Component,
+        inputs: &HashSet<Action>,
+        dim: ClockIndex,
+    ) -> Result<CompiledLocations, String> {
+        let decls = component.get_declarations();
+        let mut locations: HashMap<LocationID, LocationTree> = HashMap::new();
+        let mut location_edges: HashMap<LocationID, Vec<(Action, Transition)>> = HashMap::new();
+        let mut clock_bounds: HashMap<ClockIndex, i32> = HashMap::new();
+
+        let initial = component
+            .locations
+            .iter()
+            .find(|loc| loc.location_type == LocationType::Initial);
+        let initial_location = initial
+            .map(|loc| LocationTree::valued(loc, &Valuation::from_declarations(decls), decls, dim))
+            .transpose()?;
+
+        let mut waiting: VecDeque<(&Location, Valuation)> = initial
+            .map(|loc| (loc, Valuation::from_declarations(decls)))
+            .into_iter()
+            .collect();
+        while let Some((location, valuation)) = waiting.pop_front() {
+            let loc = LocationTree::valued(location, &valuation, decls, dim)?;
+            if locations.contains_key(&loc.id) {
+                continue;
+            }
+            if locations.len() == MAX_VALUED_LOCATIONS {
+                return Err(format!(
+                    "More than {} pairs of a location and a valuation of the integer variables are reachable, declare smaller ranges for the variables, e.g. int[0,10] n",
+                    MAX_VALUED_LOCATIONS
+                ));
+            }
+            if let Some(invariant) = loc.get_invariants() {
+                add_clock_bounds(&mut clock_bounds, invariant);
+            }
+
+            let mut edges = vec![];
+            for edge in component
+                .edges
+                .iter()
+                .filter(|edge| edge.source_location == location.id)
+            {
+                if let Some((transition, target_valuation)) =
+                    Transition::from_valued_edge(component, edge, &valuation, dim)?
+                {
+                    add_clock_bounds(&mut clock_bounds, &transition.guard_zone);
+                    waiting.push_back((
+                        component.get_location_by_name(&edge.target_location),
+                        target_valuation,
+                    ));
+                    edges.push((edge.sync.clone(), transition));
+                }
+            }
+
+            let invariant = loc
+                .get_invariants()
+                .cloned()
+                .unwrap_or_else(|| OwnedFederation::universe(dim));
+            for input in inputs {
+                let mut enabled = OwnedFederation::empty(dim);
+                for (_, transition) in edges
+                    .iter()
+                    .filter(|(sync, _)| sync == input || sync == "*")
+                {
+                    enabled += transition.get_allowed_federation().intersection(&invariant);
+                }
+
+                let missing = invariant.clone().subtraction(&enabled);
+                if !missing.is_empty() {
+                    let transition = Transition {
+                        id: TransitionID::Simple(format!("input_{}_{}", loc.id, input)),
+                        guard_zone: missing,
+                        target_locations: loc.clone(),
+                        updates: vec![],
+                    };
+                    edges.push((input.clone(), transition));
+                }
+            }
+
+            location_edges.insert(loc.id.clone(), edges);
+            locations.insert(loc.id.clone(), loc);
+        }
+
+        let mut max_bounds = Bounds::new(dim);
+        for clock in decls.clocks.values() {
+            let bound = clock_bounds.get(clock).copied().unwrap_or_default();
+            max_bounds.add_lower(*clock, bound);
+            max_bounds.add_upper(*clock, bound);
+        }
+
+        Ok((locations, location_edges, initial_location, max_bounds))
     }
 
     pub fn compile(
@@ -216,7 +342,7 @@ impl TransitionSystem for CompiledComponent {
                 assert_eq!(comp.name, self.comp_info.name);
                 self.get_all_locations()
                     .into_iter()
-                    .find(|loc| loc.id == LocationID::Simple(location_id.clone()))
+                    .find(|loc| loc.id.to_string() == location_id)
                     .ok_or_else(|| {
                         format!(
                             "Could not find location {} in component {}",
@@ -230,3 +356,18 @@ impl TransitionSystem for CompiledComponent {
         }
     }
 }
+
+/// Updates `bounds` with the largest constant each clock is compared to in `fed`.
+fn add_clock_bounds(bounds: &mut HashMap<ClockIndex, i32>, fed: &OwnedFederation) {
+    for conjunction in fed.minimal_constraints().conjunctions {
+        for constraint in conjunction.constraints {
+            let bound = constraint.ineq().bound().abs();
+            for clock in [constraint.i, constraint.j] {
+                if clock != 0 {
+                    let max = bounds.entry(clock).or_default();
+                    *max = (*max).max(bound);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::ClockIndex;
+use std::collections::hash_map::HashMap;
+use std::collections::hash_set::HashSet;
+
+use crate::ModelObjects::{Declarations, State, Transition};
+use crate::System::query_failures::{ConsistencyResult, DeterminismResult};
+use crate::System::specifics::SpecificLocation;
+use crate::TransitionSystems::{LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr};
+
+use super::transition_system::ComponentInfoTree;
+use super::{CompositionType, LocationID};
+
+/// Prefix of the internal outputs which hidden outputs are turned into.
+/// It cannot be written in a query, so internal outputs never synchronize with other systems.
+pub const INTERNAL_PREFIX: &str = "τ";
+
+/// A [TransitionSystem] behaving as `system` where some of its outputs are hidden.
+/// A hidden output `a` is turned into the internal move `τa`, see [get_internal_actions](TransitionSystem::get_internal_actions).
+/// Internal moves are uncontrollable like outputs, but they are not visible to the other side of a refinement:
+/// the specification matches an internal move of the implementation by letting time pass in its location,
+/// or by the same internal move if it hides `a` as well, and its own internal moves need not be outputs of the implementation.
+///
+/// The locations are those of `system`, and the transitions of hidden outputs are wrapped in [TransitionID::Hiding].
+#[derive(Clone)]
+pub struct Hiding {
+    system: TransitionSystemPtr,
+    outputs: HashSet<String>,
+    /// The hidden outputs of `system` by the internal outputs they are turned into
+    hidden: HashMap<String, String>,
+}
+
+impl Hiding {
+    /// Creates a new [TransitionSystem] that is `system` with the outputs in `actions` hidden.
+    /// Actions which are not outputs of `system` are left as they are.
+    pub fn new_ts(system: TransitionSystemPtr, actions: &[String]) -> TransitionSystemPtr {
+        let system_outputs = system.get_output_actions();
+        let hidden: HashMap<String, String> = actions
+            .iter()
+            .filter(|action| system_outputs.contains(*action))
+            .map(|action| (format!("{}{}", INTERNAL_PREFIX, action), action.clone()))
+            .collect();
+
+        let outputs = system_outputs
+            .into_iter()
+            .filter(|output| !actions.contains(output))
+            .chain(hidden.keys().cloned())
+            .collect();
+
+        Box::new(Hiding {
+            system,
+            outputs,
+            hidden,
+        })
+    }
+}
+
+impl TransitionSystem for Hiding {
+    fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds {
+        self.system.get_local_max_bounds(loc)
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.system.get_dim()
+    }
+
+    fn next_transitions(&self, location: &LocationTree, action: &str) -> Vec<Transition> {
+        match self.hidden.get(action) {
+            Some(hidden) => self
+                .system
+                .next_transitions(location, hidden)
+                .into_iter()
+                .map(|transition| Transition {
+                    id: TransitionID::Hiding(hidden.clone(), Box::new(transition.id)),
+                    ..transition
+                })
+                .collect(),
+            None if self.actions_contain(action) => self.system.next_transitions(location, action),
+            None => vec![],
+        }
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.system.get_input_actions()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.outputs.clone()
+    }
+
+    fn get_actions(&self) -> HashSet<String> {
+        self.get_input_actions()
+            .union(&self.outputs)
+            .cloned()
+            .collect()
+    }
+
+    fn get_initial_location(&self) -> Option<LocationTree> {
+        self.system.get_initial_location()
+    }
+
+    fn get_all_locations(&self) -> Vec<LocationTree> {
+        self.system.get_all_locations()
+    }
+
+    fn get_location(&self, id: &LocationID) -> Option<LocationTree> {
+        self.system.get_location(id)
+    }
+
+    fn get_decls(&self) -> Vec<&Declarations> {
+        self.system.get_decls()
+    }
+
+    /// Each hidden output gets its own internal output, so hiding preserves determinism
+    fn check_determinism(&self) -> DeterminismResult {
+        self.system.check_determinism()
+    }
+
+    /// Internal moves are uncontrollable like the outputs they hide, so hiding preserves consistency
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        self.system.check_local_consistency()
+    }
+
+    fn get_initial_state(&self) -> Option<State> {
+        self.system.get_initial_state()
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        self.system.get_children()
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        self.system.get_composition_type()
+    }
+
+    fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
+        self.system.comp_infos()
+    }
+
+    fn to_string(&self) -> String {
+        let mut hidden: Vec<&str> = self.hidden.values().map(String::as_str).collect();
+        hidden.sort();
+        format!("({} \\ {{{}}})", self.system.to_string(), hidden.join(", "))
+    }
+
+    fn component_names(&self) -> Vec<&str> {
+        self.system.component_names()
+    }
+
+    fn construct_location_tree(&self, target: SpecificLocation) -> Result<LocationTree, String> {
+        self.system.construct_location_tree(target)
+    }
+}
@@ -1,6 +1,9 @@
 use std::fmt::{Display, Formatter};
 
-use crate::{ModelObjects::Expressions::SystemExpression, System::specifics::SpecialLocation};
+use crate::{
+    ModelObjects::{Expressions::SystemExpression, Valuation},
+    System::specifics::SpecialLocation,
+};
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub enum LocationID {
@@ -9,9 +12,13 @@ pub enum LocationID {
     Quotient(Box<LocationID>, Box<LocationID>),
     /// Represents the potentially complete identifier of a location
     Simple(String),
+    /// Represents a location of a component with integer variables together with the values of the variables
+    Valued(String, Valuation),
     Special(SpecialLocation),
     /// Used for representing a partial state and it is generated when a location's name is set as `_`
     AnyLocation,
+    /// Used for representing a partial state of a component with integer variables, where only the name of the location is given
+    AnyValuation(String),
 }
 
 impl LocationID {
@@ -35,8 +42,8 @@ impl LocationID {
             | LocationID::Quotient(left, right) => {
                 left.is_partial_location() || right.is_partial_location()
             }
-            LocationID::Simple { .. } | LocationID::Special(_) => false,
-            LocationID::AnyLocation => true,
+            LocationID::Simple { .. } | LocationID::Valued(..) | LocationID::Special(_) => false,
+            LocationID::AnyLocation | LocationID::AnyValuation(_) => true,
         }
     }
 
@@ -52,7 +59,10 @@ impl LocationID {
                 format!("({}\\{})", a.get_unique_string(), b.get_unique_string())
             }
             LocationID::AnyLocation => "_".to_string(),
-            LocationID::Simple(location_id) => location_id.clone(),
+            LocationID::Simple(location_id) | LocationID::AnyValuation(location_id) => {
+                location_id.clone()
+            }
+            LocationID::Valued(location_id, valuation) => format!("{}{}", location_id, valuation),
             LocationID::Special(location_id) => location_id.to_string(),
         }
     }
@@ -70,6 +80,9 @@ impl From<SystemExpression> for LocationID {
             SystemExpression::Quotient(left, right) => {
                 LocationID::Quotient(Box::new((*left).into()), Box::new((*right).into()))
             }
+            SystemExpression::Hiding(system, _) | SystemExpression::Renaming(system, _) => {
+                (*system).into()
+            }
             SystemExpression::Component(name, _id) => LocationID::Simple(name),
         }
     }
@@ -81,43 +94,46 @@ impl Display for LocationID {
             LocationID::Conjunction(left, right) => {
                 match **left {
                     LocationID::Conjunction(_, _) => write!(f, "{}", (*left))?,
-                    LocationID::Simple(_) => write!(f, "{}", (*left))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*left))?,
                     _ => write!(f, "({})", (*left))?,
                 };
                 write!(f, "&&")?;
                 match **right {
                     LocationID::Conjunction(_, _) => write!(f, "{}", (*right))?,
-                    LocationID::Simple(_) => write!(f, "{}", (*right))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*right))?,
                     _ => write!(f, "({})", (*right))?,
                 };
             }
             LocationID::Composition(left, right) => {
                 match **left {
                     LocationID::Composition(_, _) => write!(f, "{}", (*left))?,
-                    LocationID::Simple(_) => write!(f, "{}", (*left))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*left))?,
                     _ => write!(f, "({})", (*left))?,
                 };
                 write!(f, "||")?;
                 match **right {
                     LocationID::Composition(_, _) => write!(f, "{}", (*right))?,
-                    LocationID::Simple(_) => write!(f, "{}", (*right))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*right))?,
                     _ => write!(f, "({})", (*right))?,
                 };
             }
             LocationID::Quotient(left, right) => {
                 match **left {
-                    LocationID::Simple(_) => write!(f, "{}", (*left))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*left))?,
                     _ => write!(f, "({})", (*left))?,
                 };
                 write!(f, "\\\\")?;
                 match **right {
-                    LocationID::Simple(_) => write!(f, "{}", (*right))?,
+                    LocationID::Simple(_) | LocationID::Valued(..) => write!(f, "{}", (*right))?,
                     _ => write!(f, "({})", (*right))?,
                 };
             }
-            LocationID::Simple(location_id) => {
+            LocationID::Simple(location_id) | LocationID::AnyValuation(location_id) => {
                 write!(f, "{}", location_id)?;
             }
+            LocationID::Valued(location_id, valuation) => {
+                write!(f, "{}{}", location_id, valuation)?;
+            }
             LocationID::AnyLocation => write!(f, "_")?,
             LocationID::Special(location_id) => write!(f, "{}", location_id)?,
         }
@@ -1,7 +1,7 @@
 use edbm::{util::constraints::ClockIndex, zones::OwnedFederation};
 
 use crate::EdgeEval::constraint_applyer::apply_constraints_to_state;
-use crate::ModelObjects::{Declarations, Location, LocationType};
+use crate::ModelObjects::{Declarations, Location, LocationType, Valuation};
 
 use super::LocationID;
 
@@ -68,6 +68,35 @@ impl LocationTree {
             right: None,
         }
     }
+
+    /// Creates the [`LocationTree`] of `location` where the integer variables of the component hold the values in `valuation`.
+    /// Fails if the invariant of `location` cannot be evaluated in `valuation`.
+    pub fn valued(
+        location: &Location,
+        valuation: &Valuation,
+        decls: &Declarations,
+        dim: ClockIndex,
+    ) -> Result<Self, String> {
+        let invariant = location
+            .invariant
+            .as_ref()
+            .map(|inv| {
+                apply_constraints_to_state(
+                    inv,
+                    &valuation.to_declarations(decls),
+                    OwnedFederation::universe(dim),
+                )
+            })
+            .transpose()?;
+        Ok(LocationTree {
+            id: LocationID::Valued(location.id.clone(), valuation.clone()),
+            invariant,
+            loc_type: location.location_type,
+            left: None,
+            right: None,
+        })
+    }
+
     /// This method is used to a build partial [`LocationTree`].
     /// A partial [`LocationTree`] means it has a [`LocationID`] that is [`LocationID::AnyLocation`].
     /// A partial [`LocationTree`] has `None` in the field `invariant` since a partial [`LocationTree`]
@@ -82,6 +111,18 @@ impl LocationTree {
         }
     }
 
+    /// This method is used to build a partial [`LocationTree`] of a component with integer variables,
+    /// which covers the location named `location_id` with any valuation of the variables.
+    pub fn build_any_valuation_tree(location_id: String) -> Self {
+        LocationTree {
+            id: LocationID::AnyValuation(location_id),
+            invariant: None,
+            loc_type: LocationType::Any,
+            left: None,
+            right: None,
+        }
+    }
+
     //Merge two locations keeping the invariants seperate
     pub fn merge_as_quotient(left: &Self, right: &Self) -> Self {
         let id = LocationID::Quotient(Box::new(left.id.clone()), Box::new(right.id.clone()));
@@ -174,8 +215,20 @@ impl LocationTree {
             }
             (LocationID::AnyLocation, LocationID::Simple { .. })
             | (LocationID::Simple { .. }, LocationID::AnyLocation)
+            | (LocationID::AnyLocation, LocationID::Valued(..))
+            | (LocationID::Valued(..), LocationID::AnyLocation)
+            | (LocationID::AnyLocation, LocationID::AnyValuation(_))
+            | (LocationID::AnyValuation(_), LocationID::AnyLocation)
             | (LocationID::AnyLocation, LocationID::AnyLocation) => true,
             (LocationID::Simple(loc_id_1), LocationID::Simple(loc_id_2)) => loc_id_1 == loc_id_2,
+            (LocationID::Valued(loc_id_1, val_1), LocationID::Valued(loc_id_2, val_2)) => {
+                loc_id_1 == loc_id_2 && val_1 == val_2
+            }
+            (LocationID::Valued(loc_id_1, _), LocationID::AnyValuation(loc_id_2))
+            | (LocationID::AnyValuation(loc_id_1), LocationID::Valued(loc_id_2, _))
+            | (LocationID::AnyValuation(loc_id_1), LocationID::AnyValuation(loc_id_2)) => {
+                loc_id_1 == loc_id_2
+            }
             (_, _) => false,
         }
     }
@@ -3,17 +3,23 @@ pub(crate) mod common;
 mod compiled_component;
 mod composition;
 mod conjunction;
+mod hiding;
 pub mod location_id;
 mod location_tree;
 mod quotient;
+mod renaming;
+mod restriction;
 mod transition_id;
 pub mod transition_system;
 
-pub use compiled_component::{CompiledComponent, ComponentInfo};
+pub use compiled_component::{CompiledComponent, ComponentInfo, MAX_VALUED_LOCATIONS};
 pub use composition::Composition;
 pub use conjunction::Conjunction;
+pub use hiding::Hiding;
 pub use location_id::LocationID;
 pub use location_tree::{CompositionType, LocationTree};
 pub use quotient::Quotient;
+pub use renaming::Renaming;
+pub use restriction::Restriction;
 pub use transition_id::TransitionID;
 pub use transition_system::{TransitionSystem, TransitionSystemPtr};
@@ -0,0 +1,203 @@
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::ClockIndex;
+use std::collections::hash_map::HashMap;
+use std::collections::hash_set::HashSet;
+
+use crate::ModelObjects::{Declarations, State, Transition};
+use crate::System::local_consistency;
+use crate::System::query_failures::{
+    ActionFailure, ConsistencyResult, DeterminismResult, SystemRecipeFailure,
+};
+use crate::System::specifics::SpecificLocation;
+use crate::TransitionSystems::{LocationTree, TransitionID, TransitionSystem, TransitionSystemPtr};
+
+use super::transition_system::ComponentInfoTree;
+use super::{CompositionType, LocationID};
+
+/// A [TransitionSystem] behaving as `system` where some of its actions are renamed.
+///
+/// The locations are those of `system`, and the transitions of renamed actions are wrapped in [TransitionID::Renaming].
+#[derive(Clone)]
+pub struct Renaming {
+    system: TransitionSystemPtr,
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    /// The actions of `system` by the action they are renamed to, which may be the same action
+    originals: HashMap<String, Vec<String>>,
+    /// The renamed actions as `(new, old)` pairs
+    renamings: Vec<(String, String)>,
+}
+
+impl Renaming {
+    /// Creates a new [TransitionSystem] that is `system` with its actions renamed by the `(new, old)` pairs in `renamings`.
+    /// Actions which `system` does not have are left as they are.
+    ///
+    /// Renaming several actions to the same action merges them,
+    /// but an input and an output cannot be merged.
+    pub fn new_ts(
+        system: TransitionSystemPtr,
+        renamings: &[(String, String)],
+    ) -> Result<TransitionSystemPtr, Box<SystemRecipeFailure>> {
+        let actions = system.get_actions();
+        let renamings: Vec<(String, String)> = renamings
+            .iter()
+            .filter(|(_, old)| actions.contains(old))
+            .cloned()
+            .collect();
+        let rename = |action: &String| {
+            renamings
+                .iter()
+                .find(|(_, old)| old == action)
+                .map_or_else(|| action.clone(), |(new, _)| new.clone())
+        };
+
+        let inputs: HashSet<String> = system.get_input_actions().iter().map(rename).collect();
+        let outputs: HashSet<String> = system.get_output_actions().iter().map(rename).collect();
+
+        let mut originals: HashMap<String, Vec<String>> = HashMap::new();
+        for action in &actions {
+            originals
+                .entry(rename(action))
+                .or_default()
+                .push(action.clone());
+        }
+
+        let renaming = Renaming {
+            system,
+            inputs,
+            outputs,
+            originals,
+            renamings,
+        };
+
+        if !renaming.inputs.is_disjoint(&renaming.outputs) {
+            ActionFailure::not_disjoint_IO(
+                renaming.to_string(),
+                renaming.inputs.clone(),
+                renaming.outputs.clone(),
+            )
+            .map_err(|e| e.to_recipe_failure(&renaming))?;
+        }
+
+        Ok(Box::new(renaming))
+    }
+
+    fn merges_actions(&self) -> bool {
+        self.originals.values().any(|originals| originals.len() > 1)
+    }
+}
+
+impl TransitionSystem for Renaming {
+    fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds {
+        self.system.get_local_max_bounds(loc)
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.system.get_dim()
+    }
+
+    fn next_transitions(&self, location: &LocationTree, action: &str) -> Vec<Transition> {
+        let originals = match self.originals.get(action) {
+            Some(originals) => originals,
+            None => return vec![],
+        };
+
+        originals
+            .iter()
+            .flat_map(|original| {
+                self.system
+                    .next_transitions(location, original)
+                    .into_iter()
+                    .map(move |transition| {
+                        if original == action {
+                            transition
+                        } else {
+                            Transition {
+                                id: TransitionID::Renaming(
+                                    action.to_string(),
+                                    original.clone(),
+                                    Box::new(transition.id),
+                                ),
+                                ..transition
+                            }
+                        }
+                    })
+            })
+            .collect()
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.inputs.clone()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.outputs.clone()
+    }
+
+    fn get_actions(&self) -> HashSet<String> {
+        self.inputs.union(&self.outputs).cloned().collect()
+    }
+
+    fn get_initial_location(&self) -> Option<LocationTree> {
+        self.system.get_initial_location()
+    }
+
+    fn get_all_locations(&self) -> Vec<LocationTree> {
+        self.system.get_all_locations()
+    }
+
+    fn get_location(&self, id: &LocationID) -> Option<LocationTree> {
+        self.system.get_location(id)
+    }
+
+    fn get_decls(&self) -> Vec<&Declarations> {
+        self.system.get_decls()
+    }
+
+    /// Renaming preserves determinism, unless several actions are merged into one
+    fn check_determinism(&self) -> DeterminismResult {
+        if self.merges_actions() {
+            local_consistency::check_determinism(self)
+        } else {
+            self.system.check_determinism()
+        }
+    }
+
+    /// Inputs and outputs stay inputs and outputs respectively, so renaming preserves consistency
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        self.system.check_local_consistency()
+    }
+
+    fn get_initial_state(&self) -> Option<State> {
+        self.system.get_initial_state()
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        self.system.get_children()
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        self.system.get_composition_type()
+    }
+
+    fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
+        self.system.comp_infos()
+    }
+
+    fn to_string(&self) -> String {
+        let renamings: Vec<String> = self
+            .renamings
+            .iter()
+            .map(|(new, old)| format!("{}/{}", new, old))
+            .collect();
+        format!("({}[{}])", self.system.to_string(), renamings.join(", "))
+    }
+
+    fn component_names(&self) -> Vec<&str> {
+        self.system.component_names()
+    }
+
+    fn construct_location_tree(&self, target: SpecificLocation) -> Result<LocationTree, String> {
+        self.system.construct_location_tree(target)
+    }
+}
@@ -0,0 +1,142 @@
+use edbm::util::bounds::Bounds;
+use edbm::util::constraints::ClockIndex;
+use std::collections::hash_set::HashSet;
+
+use crate::ModelObjects::{Declarations, State, Transition};
+use crate::System::local_consistency;
+use crate::System::query_failures::{ConsistencyResult, DeterminismResult};
+use crate::System::specifics::SpecificLocation;
+use crate::TransitionSystems::{LocationTree, TransitionSystem, TransitionSystemPtr};
+
+use super::transition_system::ComponentInfoTree;
+use super::{CompositionType, LocationID};
+
+/// A [TransitionSystem] behaving as `system` without some of its actions, whose transitions can never be taken.
+///
+/// The locations and transitions are those of `system`, so [LocationTree]s and [TransitionID](super::TransitionID)s
+/// refer to the components of `system` as if it was not restricted.
+#[derive(Clone)]
+pub struct Restriction {
+    system: TransitionSystemPtr,
+    inputs: HashSet<String>,
+    outputs: HashSet<String>,
+    ignored: Vec<String>,
+}
+
+impl Restriction {
+    /// Creates a new [TransitionSystem] that is `system` without the `ignored_inputs` and `ignored_outputs`.
+    /// Ignored actions which `system` does not have as inputs or outputs respectively are left as they are.
+    pub fn new_ts(
+        system: TransitionSystemPtr,
+        ignored_inputs: &HashSet<String>,
+        ignored_outputs: &HashSet<String>,
+    ) -> TransitionSystemPtr {
+        let all_inputs = system.get_input_actions();
+        let all_outputs = system.get_output_actions();
+        let inputs: HashSet<String> = all_inputs.difference(ignored_inputs).cloned().collect();
+        let outputs: HashSet<String> = all_outputs.difference(ignored_outputs).cloned().collect();
+
+        let mut ignored: Vec<String> = all_inputs
+            .difference(&inputs)
+            .chain(all_outputs.difference(&outputs))
+            .cloned()
+            .collect();
+        ignored.sort();
+
+        Box::new(Restriction {
+            system,
+            inputs,
+            outputs,
+            ignored,
+        })
+    }
+}
+
+impl TransitionSystem for Restriction {
+    fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds {
+        self.system.get_local_max_bounds(loc)
+    }
+
+    fn get_dim(&self) -> ClockIndex {
+        self.system.get_dim()
+    }
+
+    fn next_transitions(&self, location: &LocationTree, action: &str) -> Vec<Transition> {
+        if self.actions_contain(action) {
+            self.system.next_transitions(location, action)
+        } else {
+            vec![]
+        }
+    }
+
+    fn get_input_actions(&self) -> HashSet<String> {
+        self.inputs.clone()
+    }
+
+    fn get_output_actions(&self) -> HashSet<String> {
+        self.outputs.clone()
+    }
+
+    fn get_actions(&self) -> HashSet<String> {
+        self.inputs.union(&self.outputs).cloned().collect()
+    }
+
+    fn get_initial_location(&self) -> Option<LocationTree> {
+        self.system.get_initial_location()
+    }
+
+    fn get_all_locations(&self) -> Vec<LocationTree> {
+        self.system.get_all_locations()
+    }
+
+    fn get_location(&self, id: &LocationID) -> Option<LocationTree> {
+        self.system.get_location(id)
+    }
+
+    fn get_decls(&self) -> Vec<&Declarations> {
+        self.system.get_decls()
+    }
+
+    /// Checked on the restricted system, as the non-determinism of `system` may only be reachable through an ignored action
+    fn check_determinism(&self) -> DeterminismResult {
+        local_consistency::check_determinism(self)
+    }
+
+    /// Checked on the restricted system, as an inconsistent state of `system` may only be reachable through an ignored action,
+    /// and ignoring an output may remove the only way to leave an inconsistent state
+    fn check_local_consistency(&self) -> ConsistencyResult {
+        local_consistency::is_least_consistent(self)
+    }
+
+    fn get_initial_state(&self) -> Option<State> {
+        self.system.get_initial_state()
+    }
+
+    fn get_children(&self) -> (&TransitionSystemPtr, &TransitionSystemPtr) {
+        self.system.get_children()
+    }
+
+    fn get_composition_type(&self) -> CompositionType {
+        self.system.get_composition_type()
+    }
+
+    fn comp_infos(&'_ self) -> ComponentInfoTree<'_> {
+        self.system.comp_infos()
+    }
+
+    fn to_string(&self) -> String {
+        format!(
+            "({} without {{{}}})",
+            self.system.to_string(),
+            self.ignored.join(", ")
+        )
+    }
+
+    fn component_names(&self) -> Vec<&str> {
+        self.system.component_names()
+    }
+
+    fn construct_location_tree(&self, target: SpecificLocation) -> Result<LocationTree, String> {
+        self.system.construct_location_tree(target)
+    }
+}
@@ -8,6 +8,10 @@ pub enum TransitionID {
     Conjunction(Box<TransitionID>, Box<TransitionID>),
     Composition(Box<TransitionID>, Box<TransitionID>),
     Quotient(Vec<TransitionID>, Vec<TransitionID>),
+    /// A transition of a hidden output, which is the action in the system without hiding.
+    Hiding(String, Box<TransitionID>),
+    /// A transition of a renamed action, as `(new, old)` names of the action.
+    Renaming(String, String, Box<TransitionID>),
     Simple(String),
     None,
 }
@@ -51,6 +55,9 @@ impl TransitionID {
                 }
                 index_right
             }
+            TransitionID::Hiding(_, id) | TransitionID::Renaming(_, _, id) => {
+                id.get_leaves_helper(current_leaves, index)
+            }
             TransitionID::Simple(_) | TransitionID::None => {
                 if current_leaves.len() <= index {
                     current_leaves.push(Vec::new());
@@ -158,6 +165,20 @@ impl Display for TransitionID {
                     };
                 }
             }
+            TransitionID::Hiding(action, id) => {
+                match **id {
+                    TransitionID::Simple(_) => write!(f, "{}", id)?,
+                    _ => write!(f, "({})", id)?,
+                };
+                write!(f, "\\{{{}}}", action)?;
+            }
+            TransitionID::Renaming(new, old, id) => {
+                match **id {
+                    TransitionID::Simple(_) => write!(f, "{}", id)?,
+                    _ => write!(f, "({})", id)?,
+                };
+                write!(f, "[{}/{}]", new, old)?;
+            }
             TransitionID::Simple(name) => write!(f, "{}", name)?,
             TransitionID::None => write!(f, "NoID")?,
         }
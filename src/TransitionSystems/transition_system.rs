@@ -1,3 +1,4 @@
+use super::hiding::INTERNAL_PREFIX;
 use super::ComponentInfo;
 use super::{CompositionType, LocationID, LocationTree};
 use crate::parse_queries::parse_to_system_expr;
@@ -57,7 +58,7 @@ impl<'a> ComponentInfoTree<'a> {
     }
 }
 
-pub trait TransitionSystem: DynClone {
+pub trait TransitionSystem: DynClone + Send + Sync {
     fn get_local_max_bounds(&self, loc: &LocationTree) -> Bounds;
     fn get_dim(&self) -> ClockIndex;
 
@@ -97,6 +98,14 @@ pub trait TransitionSystem: DynClone {
         self.get_output_actions().contains(action)
     }
 
+    /// The outputs which are internal moves of hidden outputs, see [Hiding](super::Hiding)
+    fn get_internal_actions(&self) -> HashSet<String> {
+        self.get_output_actions()
+            .into_iter()
+            .filter(|output| output.starts_with(INTERNAL_PREFIX))
+            .collect()
+    }
+
     fn get_actions(&self) -> HashSet<String>;
 
     fn actions_contain(&self, action: &str) -> bool {
@@ -258,6 +267,7 @@ pub fn component_loader_to_transition_system(
     let mut dimension = 0;
     let sys_expr = parse_to_system_expr(composition).unwrap();
     get_system_recipe(&sys_expr, loader, &mut dimension, &mut None)
+        .unwrap()
         .compile(dimension)
         .unwrap()
 }
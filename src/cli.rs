@@ -2,7 +2,7 @@ use clap::Parser;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about="Reveaal is a model checking engine for ECDAR (Environment for Compositional Design and Analysis of Real Time Systems)\nFor more information about ECDAR see https://www.ecdar.net/", long_about = Some("With Reveaal you can either run a single query with the 'query' command or run it as a server with the 'serve' command"))]
+#[command(author, version, about="Reveaal is a model checking engine for ECDAR (Environment for Compositional Design and Analysis of Real Time Systems)\nFor more information about ECDAR see https://www.ecdar.net/", long_about = Some("With Reveaal you can either run a single query with the 'query' command, simulate a system with the 'simulate' command, test an implementation with the 'test-run' command or run it as a server with the 'serve' command"))]
 pub enum Args {
     /// Start a gRPC server with the protocol defined in the protobuf file
     ///
@@ -33,6 +33,22 @@ pub enum Args {
     /// Reveaal query "consistency: Machine" -i samples/json/EcdarUniversity
     ///
     /// Reveaal query "determinism: Researcher" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "concrete-trace: Machine: 2.5 tea, 0 coin, 4.25 cof" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query 'trace-check: Machine: "samples/traces/Machine.csv"' -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "simulate: Machine {runs=10, steps=50, seed=1} -> Machine.L4" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "test-gen: Machine {depth=4, tests=3, coverage}" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "strategy: Machine <= Spec" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "consistency: Machine[c/coin] \ {tea}" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "refinement: UniversityExample <= Spec" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal query "refinement: Researcher || Machine || Administration <= Spec" -i samples/json/EcdarUniversity -t 4 --parallel-refinement
     Query {
         /// The query to execute
         #[clap(value_name = "QUERY_TYPE: refinement|consistency|reachability|save-component", value_parser = query_check)]
@@ -49,10 +65,74 @@ pub enum Args {
         /// Save file for refinement relations
         #[arg(short, long, value_name = "FILE")]
         save_refinement_relations: Option<PathBuf>,
-        // TODO: Maybe add this later
-        // /// The number of threads to use when running the query
-        // #[arg(short, long, default_value_t = num_cpus::get())]
-        // thread_count: usize,
+
+        /// The number of threads to use when running the query
+        #[arg(short, long, default_value_t = num_cpus::get())]
+        thread_count: usize,
+
+        /// Whether to run reachability queries on all threads
+        #[arg(long, default_value_t = false)]
+        parallel_reachability: bool,
+
+        /// Whether to run refinement queries on all threads
+        #[arg(long, default_value_t = false)]
+        parallel_refinement: bool,
+    },
+    /// Simulate a system interactively, choosing the decisions to take one at a time
+    ///
+    /// Examples of usage:
+    ///
+    /// Reveaal simulate "Machine" -i samples/json/EcdarUniversity
+    ///
+    /// Reveaal simulate "Administration || Machine || Researcher" -i samples/json/EcdarUniversity
+    Simulate {
+        /// The composition of components to simulate
+        #[clap(value_name = "SYSTEM", value_parser = system_check)]
+        system: String,
+
+        /// File (XML) or folder (JSON) with component definitions
+        #[arg(short, long, value_name = "XML|JSON")]
+        input_folder: PathBuf,
+
+        /// Whether to enable clock reduction
+        #[arg(short, long, default_value_t = false)]
+        enable_clock_reduction: bool,
+    },
+    /// Test a running implementation against a specification, talking to it over its standard input and output
+    ///
+    /// Examples of usage:
+    ///
+    /// Reveaal test-run "Machine" -i samples/json/EcdarUniversity -- sh samples/sut/machine.sh
+    ///
+    /// Reveaal test-run "Machine" -i samples/json/EcdarUniversity -n 50 --seed 3 -w 5 -- ./machine-adapter
+    TestRun {
+        /// The specification to test against
+        #[clap(value_name = "SYSTEM", value_parser = system_check)]
+        system: String,
+
+        /// File (XML) or folder (JSON) with component definitions
+        #[arg(short, long, value_name = "XML|JSON")]
+        input_folder: PathBuf,
+
+        /// The number of inputs to send and waits for outputs
+        #[arg(short = 'n', long, default_value_t = 20)]
+        steps: usize,
+
+        /// The seed used to choose between the inputs and waiting
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// The number of time units to wait for an output at a time
+        #[arg(short, long, default_value_t = 10)]
+        wait: u32,
+
+        /// The milliseconds to wait for an answer from the system under test before the run is inconclusive
+        #[arg(long, value_name = "MS", default_value_t = 5000)]
+        answer_timeout: u64,
+
+        /// The command starting the system under test
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
     },
 }
 
@@ -60,6 +140,10 @@ fn query_check(arg: &str) -> Result<String, String> {
     crate::parse_queries::parse_to_expression_tree(arg).map(|_| arg.to_string())
 }
 
+fn system_check(arg: &str) -> Result<String, String> {
+    crate::parse_queries::parse_to_system_expr(arg).map(|_| arg.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::Args;
@@ -85,11 +169,14 @@ mod tests {
     }
 
     #[test_case(
-    &["", "query", "-i", "/path/to/system", "-e", "-s", "saved-comp", "refinement: some <= refinement"], Args::Query {
+    &["", "query", "-i", "/path/to/system", "-e", "-s", "saved-comp", "-t", "4", "--parallel-reachability", "--parallel-refinement", "refinement: some <= refinement"], Args::Query {
     query: "refinement: some <= refinement".to_string(),
     input_folder: PathBuf::from("/path/to/system"),
     enable_clock_reduction: true,
     save_refinement_relations: Some(PathBuf::from("saved-comp")),
+    thread_count: 4,
+    parallel_reachability: true,
+    parallel_refinement: true,
     } ; "All fields"
     )]
     #[test_case(
@@ -98,6 +185,9 @@ mod tests {
     input_folder: PathBuf::from("/path/to/system"),
     enable_clock_reduction: Default::default(),
     save_refinement_relations: Some(PathBuf::from("saved-comp")),
+    thread_count: num_cpus::get(),
+    parallel_reachability: false,
+    parallel_refinement: false,
     } ; "Default clock-reduction"
     )]
     #[test_case(
@@ -106,15 +196,65 @@ mod tests {
     input_folder: PathBuf::from("/path/to/system"),
     enable_clock_reduction: Default::default(),
     save_refinement_relations: None,
+    thread_count: num_cpus::get(),
+    parallel_reachability: false,
+    parallel_refinement: false,
     } ; "No saved path"
     )]
     fn query_command_tests(input_args: &[&str], expected: Args) {
         check_args(Args::parse_from(input_args), expected);
     }
 
+    #[test_case(
+    &["", "simulate", "-i", "/path/to/system", "-e", "Machine || Researcher"], Args::Simulate {
+    system: "Machine || Researcher".to_string(),
+    input_folder: PathBuf::from("/path/to/system"),
+    enable_clock_reduction: true,
+    } ; "All fields"
+    )]
+    #[test_case(
+    &["", "simulate", "-i", "/path/to/system", "Machine"], Args::Simulate {
+    system: "Machine".to_string(),
+    input_folder: PathBuf::from("/path/to/system"),
+    enable_clock_reduction: false,
+    } ; "Default clock-reduction"
+    )]
+    fn simulate_command_tests(input_args: &[&str], expected: Args) {
+        check_args(Args::parse_from(input_args), expected);
+    }
+
+    #[test_case(
+    &["", "test-run", "-i", "/path/to/system", "-n", "50", "--seed", "3", "-w", "5", "--answer-timeout", "100", "Machine", "--", "sh", "machine.sh", "7"], Args::TestRun {
+    system: "Machine".to_string(),
+    input_folder: PathBuf::from("/path/to/system"),
+    steps: 50,
+    seed: 3,
+    wait: 5,
+    answer_timeout: 100,
+    command: vec!["sh".to_string(), "machine.sh".to_string(), "7".to_string()],
+    } ; "All fields"
+    )]
+    #[test_case(
+    &["", "test-run", "-i", "/path/to/system", "Machine", "--", "./adapter"], Args::TestRun {
+    system: "Machine".to_string(),
+    input_folder: PathBuf::from("/path/to/system"),
+    steps: 20,
+    seed: 0,
+    wait: 10,
+    answer_timeout: 5000,
+    command: vec!["./adapter".to_string()],
+    } ; "Default options"
+    )]
+    fn test_run_command_tests(input_args: &[&str], expected: Args) {
+        check_args(Args::parse_from(input_args), expected);
+    }
+
     #[test_case(&["", "query", "-i", "/path/to/system", "-s", "refinement: some <= refinement"] ; "Not supplying needed argument")]
     #[test_case(&["", "query", "-i", "/path/to/system", "refinement: some  refinement"] ; "Bad query")]
     #[test_case(&["", "serve", "-i", "/path/to/system", "refinement: some <= refinement"] ; "Wrong command")]
+    #[test_case(&["", "simulate", "-i", "/path/to/system", "Machine ||"] ; "Bad system")]
+    #[test_case(&["", "simulate", "Machine"] ; "Missing input folder")]
+    #[test_case(&["", "test-run", "-i", "/path/to/system", "Machine"] ; "Missing command")]
     #[should_panic]
     fn query_command_tests_panics(input_args: &[&str]) {
         Args::try_parse_from(input_args).unwrap();
@@ -128,18 +268,27 @@ mod tests {
                     input_folder: ia,
                     enable_clock_reduction: da,
                     save_refinement_relations: sa,
+                    thread_count: ta,
+                    parallel_reachability: pa,
+                    parallel_refinement: ra,
                 },
                 Args::Query {
                     query: qe,
                     input_folder: ie,
                     enable_clock_reduction: de,
                     save_refinement_relations: se,
+                    thread_count: te,
+                    parallel_reachability: pe,
+                    parallel_refinement: re,
                 },
             ) => {
                 assert_eq!(qa, qe);
                 assert_eq!(ia, ie);
                 assert_eq!(da, de);
                 assert_eq!(sa, se);
+                assert_eq!(ta, te);
+                assert_eq!(pa, pe);
+                assert_eq!(ra, re);
             }
             (
                 Args::Serve {
@@ -157,6 +306,50 @@ mod tests {
                 assert_eq!(ta, te);
                 assert_eq!(ca, ce);
             }
+            (
+                Args::Simulate {
+                    system: sa,
+                    input_folder: ia,
+                    enable_clock_reduction: ea,
+                },
+                Args::Simulate {
+                    system: se,
+                    input_folder: ie,
+                    enable_clock_reduction: ee,
+                },
+            ) => {
+                assert_eq!(sa, se);
+                assert_eq!(ia, ie);
+                assert_eq!(ea, ee);
+            }
+            (
+                Args::TestRun {
+                    system: sa,
+                    input_folder: ia,
+                    steps: na,
+                    seed: da,
+                    wait: wa,
+                    answer_timeout: aa,
+                    command: ca,
+                },
+                Args::TestRun {
+                    system: se,
+                    input_folder: ie,
+                    steps: ne,
+                    seed: de,
+                    wait: we,
+                    answer_timeout: ae,
+                    command: ce,
+                },
+            ) => {
+                assert_eq!(sa, se);
+                assert_eq!(ia, ie);
+                assert_eq!(na, ne);
+                assert_eq!(da, de);
+                assert_eq!(wa, we);
+                assert_eq!(aa, ae);
+                assert_eq!(ca, ce);
+            }
             (a, e) => panic!("Not same, expected {:?}, got {:?}", e, a),
         }
     }
@@ -14,7 +14,7 @@ pub mod tests;
 pub use crate::DataReader::component_loader::{
     ComponentLoader, JsonProjectLoader, ProjectLoader, XmlProjectLoader,
 };
-pub use crate::DataReader::{parse_queries, xml_parser};
+pub use crate::DataReader::{json_writer, parse_queries, xml_parser};
 use crate::ProtobufServer::services::query_request::Settings;
 pub use crate::System::extract_system_rep;
 pub use ProtobufServer::start_grpc_server_with_tokio;
@@ -22,6 +22,13 @@ pub use ProtobufServer::start_grpc_server_with_tokio;
 /// The default settings
 pub const DEFAULT_SETTINGS: Settings = Settings {
     disable_clock_reduction: true,
+    parallel_reachability: false,
+    parallel_refinement: false,
+    save_refinement_relations: false,
+    timeout_ms: 0,
+    max_states: 0,
+    max_zones_per_location: 0,
+    max_memory_bytes: 0,
 };
 
 #[macro_use]
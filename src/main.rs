@@ -5,13 +5,19 @@ use reveaal::ModelObjects::Query;
 use reveaal::System::query_failures::QueryResult;
 
 use clap::Parser;
+use rayon::ThreadPoolBuilder;
 use reveaal::ProtobufServer::services::query_request::Settings;
+use reveaal::Simulation::repl;
+use reveaal::Simulation::test_run::{self, TestRunOptions, TestVerdict};
+use reveaal::System::extract_system_rep::ExecutableQueryError;
+use reveaal::TransitionSystems::TransitionSystemPtr;
 use reveaal::{
-    extract_system_rep, parse_queries, start_grpc_server_with_tokio, xml_parser, ComponentLoader,
-    JsonProjectLoader, ProjectLoader, XmlProjectLoader,
+    extract_system_rep, json_writer, parse_queries, start_grpc_server_with_tokio, xml_parser,
+    ComponentLoader, JsonProjectLoader, ProjectLoader, XmlProjectLoader,
 };
 use std::env;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
@@ -25,52 +31,134 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             thread_count,
             cache_size,
         } => start_grpc_server_with_tokio(&endpoint, cache_size, thread_count)?,
-        Args::Query { .. } => start_using_cli(args),
+        Args::Query { thread_count, .. } => start_using_cli(args, thread_count),
+        Args::Simulate {
+            system,
+            input_folder,
+            enable_clock_reduction,
+        } => {
+            let settings = cli_settings(enable_clock_reduction, false, false);
+            let mut comp_loader = get_project_loader(input_folder, settings).to_comp_loader();
+            let system = match compile_system(&mut *comp_loader, &system) {
+                Ok(system) => system,
+                Err(err) => {
+                    eprintln!("Could not simulate {}: {}", system, err);
+                    std::process::exit(1);
+                }
+            };
+            repl::run_repl(system, io::stdin().lock(), io::stdout())?
+        }
+        Args::TestRun {
+            system,
+            input_folder,
+            steps,
+            seed,
+            wait,
+            answer_timeout,
+            command,
+        } => {
+            let settings = cli_settings(false, false, false);
+            let mut comp_loader = get_project_loader(input_folder, settings).to_comp_loader();
+            let (system, clock) = test_run::specification_with_clock(&mut *comp_loader, &system)?;
+            let options = TestRunOptions {
+                steps,
+                seed,
+                wait,
+                answer_timeout_ms: answer_timeout,
+            };
+            let report = test_run::run_process(&system, clock, &options, &command)?;
+            println!("{}", report);
+            // Lets scripts running the tests tell a failing implementation apart
+            if let TestVerdict::Fail(_) = report.verdict {
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
 }
 
-fn start_using_cli(args: Args) {
-    let (mut comp_loader, queries) = parse_args(args);
+/// Compiles the system `composition`, failing instead of panicking if it is missing components or inconsistent
+fn compile_system(
+    loader: &mut dyn ComponentLoader,
+    composition: &str,
+) -> Result<TransitionSystemPtr, String> {
+    let mut dim = 0;
+    let system = parse_queries::parse_to_system_expr(composition)?;
+    extract_system_rep::get_system_recipe(&system, loader, &mut dim, &mut None)
+        .map_err(|err| err.to_string())?
+        .compile(dim)
+        .map_err(|err| err.to_string())
+}
 
-    let mut results = vec![];
-    for query in &queries {
-        let executable_query = Box::new(
-            extract_system_rep::create_executable_query(query, &mut *comp_loader).unwrap(),
-        );
+fn start_using_cli(args: Args, thread_count: usize) {
+    // Queries run inside the pool, so the parallel searches can use all of its threads
+    let thread_pool = ThreadPoolBuilder::new()
+        .num_threads(thread_count)
+        .build()
+        .unwrap();
+    let (queries, results, relations_path) = thread_pool.install(|| run_queries(args));
 
-        let result = executable_query.execute();
+    println!("\nQuery results:");
+    print_results(&queries, &results, relations_path);
+}
 
-        if let QueryResult::CustomError(err) = result {
-            panic!("{}", err);
-        }
+fn run_queries(args: Args) -> (Vec<Query>, Vec<QueryResult>, Option<PathBuf>) {
+    let (mut comp_loader, queries, relations_path) = parse_args(args);
+
+    let mut results = vec![];
+    for query in &queries {
+        let result = match extract_system_rep::create_executable_query(query, &mut *comp_loader) {
+            Ok(executable_query) => executable_query.execute(),
+            Err(ExecutableQueryError::SystemRecipeFailure(failure)) => {
+                QueryResult::RecipeFailure(failure)
+            }
+            Err(ExecutableQueryError::Custom(err)) => QueryResult::CustomError(err),
+        };
 
         results.push(result);
     }
 
-    println!("\nQuery results:");
+    (queries, results, relations_path)
+}
+
+fn print_results(queries: &[Query], results: &[QueryResult], relations_path: Option<PathBuf>) {
     for index in 0..queries.len() {
         results[index].print_result(&queries[index].query.as_ref().unwrap().to_string())
     }
+
+    if let Some(path) = relations_path {
+        let relations: Vec<_> = queries
+            .iter()
+            .zip(results)
+            .filter_map(|(query, result)| match result {
+                QueryResult::Refinement(Ok(Some(relation))) => {
+                    Some((query.query.as_ref().unwrap().to_string(), relation))
+                }
+                _ => None,
+            })
+            .collect();
+        json_writer::refinement_relations_to_json_file(path, &relations);
+    }
 }
 
-fn parse_args(args: Args) -> (Box<dyn ComponentLoader>, Vec<Query>) {
+fn parse_args(args: Args) -> (Box<dyn ComponentLoader>, Vec<Query>, Option<PathBuf>) {
     match args {
         Args::Query {
             query,
             input_folder,
             enable_clock_reduction,
             save_refinement_relations,
-            //thread_count,
+            parallel_reachability,
+            parallel_refinement,
+            ..
         } => {
-            if save_refinement_relations.is_some() {
-                unimplemented!("Saving refinement relations is not yet implemented");
-            }
-
-            let settings = Settings {
-                disable_clock_reduction: !enable_clock_reduction,
-            };
+            let settings = cli_settings(
+                enable_clock_reduction,
+                parallel_reachability,
+                parallel_refinement,
+                save_refinement_relations.is_some(),
+            );
 
             let project_loader = get_project_loader(input_folder, settings);
 
@@ -80,12 +168,35 @@ fn parse_args(args: Args) -> (Box<dyn ComponentLoader>, Vec<Query>) {
                 parse_queries::parse_to_query(&query)
             };
 
-            (project_loader.to_comp_loader(), queries)
+            (
+                project_loader.to_comp_loader(),
+                queries,
+                save_refinement_relations,
+            )
         }
         _ => unreachable!("This function should only be called when the args are a query"),
     }
 }
 
+/// The settings of the CLI, which has no timeout or limits
+fn cli_settings(
+    enable_clock_reduction: bool,
+    parallel_reachability: bool,
+    parallel_refinement: bool,
+    save_refinement_relations: bool,
+) -> Settings {
+    Settings {
+        disable_clock_reduction: !enable_clock_reduction,
+        parallel_reachability,
+        parallel_refinement,
+        save_refinement_relations,
+        timeout_ms: 0,
+        max_states: 0,
+        max_zones_per_location: 0,
+        max_memory_bytes: 0,
+    }
+}
+
 fn get_project_loader<P: AsRef<Path>>(
     project_path: P,
     settings: Settings,
@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod test {
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::tests::refinement::Helper::{json_refinement_check, json_run_query};
+    use crate::DataReader::parse_declarations::parse_declarations;
+    use crate::DataReader::xml_parser::parse_xml_from_str;
+    use crate::System::query_failures::SystemRecipeFailure;
+    use crate::TransitionSystems::MAX_VALUED_LOCATIONS;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/IntVariables";
+
+    #[test]
+    fn parse_clocks_test() {
+        let decls = parse_declarations("clock x, y;\nclock z;").unwrap();
+        assert_eq!(decls.clocks["x"], 1);
+        assert_eq!(decls.clocks["y"], 2);
+        assert_eq!(decls.clocks["z"], 3);
+        assert!(decls.ints.is_empty());
+    }
+
+    #[test]
+    fn parse_ints_and_consts_test() {
+        let decls = parse_declarations(
+            "// Constants\nconst int MAX = 10, MIN = -2;\nint[MIN,MAX] n = 2, m;\nint k = MAX; /* unbounded */",
+        )
+        .unwrap();
+        assert_eq!(decls.consts["MAX"], 10);
+        assert_eq!(decls.consts["MIN"], -2);
+        assert_eq!(decls.ints["n"], 2);
+        assert_eq!(decls.ints["m"], 0);
+        assert_eq!(decls.ints["k"], 10);
+        assert_eq!(decls.get_int_range("n"), (-2, 10));
+        assert_eq!(decls.get_int_range("m"), (-2, 10));
+        assert!(!decls.int_ranges.contains_key("k"));
+    }
+
+    #[test]
+    fn initial_value_defaults_to_lower_bound_test() {
+        let decls = parse_declarations("int[1,5] n;").unwrap();
+        assert_eq!(decls.ints["n"], 1);
+    }
+
+    #[test]
+    fn empty_declarations_test() {
+        let decls = parse_declarations("").unwrap();
+        assert!(decls.clocks.is_empty());
+        assert!(decls.ints.is_empty());
+    }
+
+    #[test_case("clock x;\nbool_t free;", "2:1"; "Unknown type")]
+    #[test_case("clock x;\nint x;", "2:5"; "Duplicate name")]
+    #[test_case("int[0,5] n = 7;", "1:10"; "Initial value outside range")]
+    #[test_case("int[5,0] n;", "1:4"; "Empty range")]
+    #[test_case("const int A = 1;\nint[0,MAX] n;", "2:7"; "Undeclared constant")]
+    #[test_case("clock x", "1:8"; "Missing semicolon")]
+    fn declaration_error_test(input: &str, position: &str) {
+        let error = parse_declarations(input).unwrap_err();
+        assert!(
+            error.contains(&format!("--> {}", position)),
+            "Expected an error at {}, got: {}",
+            position,
+            error
+        );
+    }
+
+    #[test]
+    fn xml_declaration_error_test() {
+        let xml = "<nta><template><name>A</name><declaration>clock x</declaration>\
+                   <location id=\"L0\"/><init ref=\"L0\"/></template>\
+                   <system>system A;</system></nta>";
+        let error = parse_xml_from_str(xml).unwrap_err();
+        assert!(
+            error.contains("--> 1:8"),
+            "Expected an error at 1:8, got: {}",
+            error
+        );
+    }
+
+    #[test_case("refinement: ConstCounter <= Unrolled"; "Constants are substituted")]
+    #[test_case("refinement: Unrolled <= ConstCounter"; "Constants are substituted the other way")]
+    fn constants_refinement_test(query: &str) {
+        assert!(json_refinement_check(PATH, query));
+    }
+
+    #[test]
+    fn int_range_violation_test() {
+        match json_run_query(PATH, "consistency: Overflow") {
+            Err(ExecutableQueryError::SystemRecipeFailure(SystemRecipeFailure::Invalid(
+                reason,
+                _,
+            ))) => assert!(
+                reason.contains("\"n\""),
+                "Expected an error naming n, got: {}",
+                reason
+            ),
+            Err(error) => panic!("Expected an invalid component, got {:?}", error),
+            Ok(result) => panic!("Expected an error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn unbounded_int_limit_test() {
+        // Without a declared range the counter would be unrolled over the whole default range
+        match json_run_query(PATH, "consistency: Unbounded") {
+            Err(ExecutableQueryError::SystemRecipeFailure(SystemRecipeFailure::Invalid(
+                reason,
+                _,
+            ))) => assert!(
+                reason.contains(&MAX_VALUED_LOCATIONS.to_string()),
+                "Expected an error naming the limit, got: {}",
+                reason
+            ),
+            Err(error) => panic!("Expected an invalid component, got {:?}", error),
+            Ok(result) => panic!("Expected an error, got {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::{json_refinement_check, json_run_query};
+    use crate::DataReader::parse_edge::Update;
+    use crate::ModelObjects::Expressions::ArithExpression as AE;
+    use crate::ModelObjects::{Declarations, Valuation};
+    use crate::System::query_failures::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/IntVariables";
+
+    fn valuation(n: i32) -> Valuation {
+        let mut decls = Declarations::empty();
+        decls.ints.insert("n".to_string(), n);
+        Valuation::from_declarations(&decls)
+    }
+
+    #[test]
+    fn valuation_update_test() {
+        let mut val = valuation(1);
+        let update = Update {
+            variable: "n".to_string(),
+            expression: AE::AAdd(AE::VarName("n".to_string()), AE::Int(2)),
+        };
+        val.apply_update(&update, &Declarations::empty()).unwrap();
+        assert_eq!(val, valuation(3));
+        assert_eq!(val.to_string(), "{n=3}");
+    }
+
+    #[test]
+    fn valuation_update_out_of_range_test() {
+        let mut val = valuation(32767);
+        let update = Update {
+            variable: "n".to_string(),
+            expression: AE::AAdd(AE::VarName("n".to_string()), AE::Int(1)),
+        };
+        assert!(val.apply_update(&update, &Declarations::empty()).is_err());
+    }
+
+    #[test_case(AE::Addition, i32::MAX, 1; "Addition")]
+    #[test_case(AE::Difference, i32::MIN, 1; "Difference")]
+    #[test_case(AE::Multiplication, i32::MAX, 2; "Multiplication")]
+    #[test_case(AE::Division, i32::MIN, -1; "Division")]
+    fn valuation_overflow_test(op: fn(Box<AE>, Box<AE>) -> AE, left: i32, right: i32) {
+        // The operators are built directly, as their constructors fold constants
+        let expr = op(Box::new(AE::Int(left)), Box::new(AE::Int(right)));
+        assert!(valuation(0).evaluate(&expr).is_err());
+    }
+
+    #[test]
+    fn valuation_division_by_zero_test() {
+        let val = valuation(0);
+        let expr = AE::ADiv(AE::Int(1), AE::VarName("n".to_string()));
+        assert!(val.evaluate(&expr).is_err());
+    }
+
+    #[test_case("refinement: Counter <= Unrolled", true; "Counter refines its unrolled version")]
+    #[test_case("refinement: Unrolled <= Counter", true; "Unrolled version refines the counter")]
+    #[test_case("refinement: Early <= Unrolled", false; "Stopping after two ticks does not refine three ticks")]
+    #[test_case("refinement: Counter <= Counter", true; "Counter refines itself")]
+    #[test_case("refinement: Buffer <= Buffer", true; "Buffer refines itself")]
+    fn int_variables_refinement_test(query: &str, expected: bool) {
+        assert_eq!(json_refinement_check(PATH, query), expected);
+    }
+
+    #[test_case("reachability: Counter @ init -> Counter.L1", true; "Done is reached after counting")]
+    #[test_case("reachability: Early @ init -> Early.L1", true; "Done is reached after counting to two")]
+    #[test_case("reachability: Counter @ init -> Counter.L0 && Counter.x > 2", false; "The invariant holds for every valuation")]
+    #[test_case("reachability: Counter || Buffer @ init -> Counter.L1 && Buffer.L0", true; "Partial valuations in a composition")]
+    fn int_variables_reachability_test(query: &str, expected: bool) {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Reachability(path) => assert_eq!(path.is_ok(), expected),
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test_case("consistency: Counter"; "Counter")]
+    #[test_case("consistency: Buffer"; "Buffer")]
+    #[test_case("determinism: Counter"; "Counter determinism")]
+    #[test_case("determinism: Buffer"; "Buffer determinism")]
+    fn int_variables_consistency_test(query: &str) {
+        let result = json_run_query(PATH, query).unwrap();
+        assert!(matches!(
+            result,
+            QueryResult::Consistency(Ok(())) | QueryResult::Determinism(Ok(()))
+        ));
+    }
+}
@@ -1,2 +1,5 @@
 pub mod arith_expression;
 pub mod bool_expression;
+pub mod declarations;
+pub mod int_variables;
+pub mod system_diagram;
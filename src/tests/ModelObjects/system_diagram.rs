@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod test {
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::tests::refinement::Helper::{json_refinement_check, json_run_query};
+    use crate::DataReader::json_reader::read_systems;
+    use crate::ModelObjects::{resolve_systems, SystemDiagram};
+    use crate::System::query_failures::QueryResult;
+    use test_case::test_case;
+
+    const UNIVERSITY_PATH: &str = "samples/json/EcdarUniversity";
+    const INVALID_SYSTEMS_PATH: &str = "samples/json/InvalidSystems";
+
+    const COMPONENTS: [&str; 4] = ["Machine", "Researcher", "HalfAdm1", "HalfAdm2"];
+
+    /// A system named `name` with the `instances` as `(id, component, x)`, `operators` as `(id, type, x)` and `edges` as `(child, parent)`
+    fn diagram(
+        name: &str,
+        instances: &[(u32, &str, f64)],
+        operators: &[(u32, &str, f64)],
+        edges: &[(u32, u32)],
+    ) -> SystemDiagram {
+        let instances: Vec<_> = instances
+            .iter()
+            .map(|(id, comp, x)| {
+                format!(
+                    r#"{{"id": {}, "componentName": "{}", "x": {}}}"#,
+                    id, comp, x
+                )
+            })
+            .collect();
+        let operators: Vec<_> = operators
+            .iter()
+            .map(|(id, op, x)| format!(r#"{{"id": {}, "type": "{}", "x": {}}}"#, id, op, x))
+            .collect();
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|(child, parent)| format!(r#"{{"child": {}, "parent": {}}}"#, child, parent))
+            .collect();
+
+        serde_json::from_str(&format!(
+            r#"{{"name": "{}", "componentInstances": [{}], "operators": [{}], "edges": [{}]}}"#,
+            name,
+            instances.join(", "),
+            operators.join(", "),
+            edges.join(", ")
+        ))
+        .unwrap()
+    }
+
+    fn is_component(name: &str) -> bool {
+        COMPONENTS.contains(&name)
+    }
+
+    #[test]
+    fn university_example_is_read() {
+        let systems = read_systems(UNIVERSITY_PATH);
+
+        assert_eq!(systems.len(), 1);
+        let (name, system) = &systems[0];
+        assert_eq!(name, "UniversityExample");
+        assert_eq!(
+            system
+                .as_ref()
+                .unwrap()
+                .to_expression()
+                .unwrap()
+                .to_string(),
+            "((Machine || (HalfAdm1 && HalfAdm2)) || Researcher)"
+        );
+    }
+
+    #[test]
+    fn children_are_ordered_from_left_to_right() {
+        let system = diagram(
+            "S",
+            &[(1, "Machine", 300.0), (2, "Researcher", 10.0)],
+            &[(3, "quotient", 100.0)],
+            &[(3, 0), (1, 3), (2, 3)],
+        );
+
+        assert_eq!(
+            system.to_expression().unwrap().to_string(),
+            "(Researcher \\\\ Machine)"
+        );
+    }
+
+    #[test]
+    fn repeated_components_are_told_apart() {
+        let system = diagram(
+            "S",
+            &[(1, "Machine", 10.0), (2, "Machine", 20.0)],
+            &[(3, "composition", 10.0)],
+            &[(3, 0), (1, 3), (2, 3)],
+        );
+
+        assert_eq!(
+            system.to_expression().unwrap().to_string(),
+            "(Machine[1] || Machine[2])"
+        );
+    }
+
+    #[test]
+    fn systems_are_used_in_other_systems() {
+        let inner = diagram(
+            "Inner",
+            &[(1, "HalfAdm1", 10.0), (2, "HalfAdm2", 20.0)],
+            &[(3, "conjunction", 10.0)],
+            &[(3, 0), (1, 3), (2, 3)],
+        );
+        let outer = diagram(
+            "Outer",
+            &[(1, "Machine", 10.0), (2, "Inner", 20.0)],
+            &[(3, "composition", 10.0)],
+            &[(3, 0), (1, 3), (2, 3)],
+        );
+
+        let systems = resolve_systems(&[outer, inner], is_component);
+
+        assert_eq!(
+            systems["Outer"].as_ref().unwrap().to_string(),
+            "(Machine || (HalfAdm1 && HalfAdm2))"
+        );
+    }
+
+    #[test]
+    fn cyclic_systems_fail() {
+        let first = diagram("First", &[(1, "Second", 10.0)], &[], &[(1, 0)]);
+        let second = diagram("Second", &[(1, "First", 10.0)], &[], &[(1, 0)]);
+        let valid = diagram("Valid", &[(1, "Machine", 10.0)], &[], &[(1, 0)]);
+
+        let systems = resolve_systems(&[first, second, valid], is_component);
+
+        assert_eq!(
+            systems["First"].as_ref().unwrap_err(),
+            "Systems form a cycle: First -> Second -> First"
+        );
+        assert!(systems["Second"].is_err());
+        assert!(systems["Valid"].is_ok());
+    }
+
+    #[test_case(&[(1, "Unknown", 10.0)], &[], &[(1, 0)], "neither a component nor a system"; "unknown component")]
+    #[test_case(&[(1, "Machine", 10.0)], &[(3, "composition", 10.0)], &[(3, 0), (1, 3), (2, 3)], "no component instance or operator with id 2"; "missing instance")]
+    #[test_case(&[(1, "Machine", 10.0)], &[(3, "composition", 10.0)], &[(3, 0), (1, 3)], "must have at least two children"; "single operand")]
+    #[test_case(&[(1, "Machine", 10.0)], &[], &[], "nothing connected to its root"; "empty system")]
+    fn invalid_systems_fail(
+        instances: &[(u32, &str, f64)],
+        operators: &[(u32, &str, f64)],
+        edges: &[(u32, u32)],
+        error: &str,
+    ) {
+        let systems = resolve_systems(&[diagram("S", instances, operators, edges)], is_component);
+
+        match &systems["S"] {
+            Err(err) => assert!(err.contains(error), "Unexpected error: {}", err),
+            Ok(system) => panic!("Expected an error, got {}", system),
+        }
+    }
+
+    #[test]
+    fn systems_with_component_names_are_left_out() {
+        let system = diagram("Machine", &[(1, "Researcher", 10.0)], &[], &[(1, 0)]);
+
+        let systems = resolve_systems(&[system], is_component);
+
+        assert!(systems.is_empty());
+    }
+
+    #[test_case("UniversityExample <= (HalfAdm1 && HalfAdm2) || Researcher || Machine", true; "system refines its definition")]
+    #[test_case("(HalfAdm1 && HalfAdm2) || Researcher || Machine <= UniversityExample", true; "definition refines the system")]
+    #[test_case("UniversityExample <= Spec", false; "system does not refine spec")]
+    fn refinement_with_system(query: &str, refines: bool) {
+        assert_eq!(
+            json_refinement_check(UNIVERSITY_PATH, &format!("refinement: {}", query)),
+            refines
+        );
+    }
+    #[test]
+    fn malformed_system_file_is_read_as_error() {
+        let systems = read_systems(INVALID_SYSTEMS_PATH);
+
+        let (_, malformed) = systems
+            .iter()
+            .find(|(name, _)| name == "Malformed")
+            .unwrap();
+        assert!(malformed.is_err());
+        assert_eq!(
+            systems.iter().filter(|(_, system)| system.is_ok()).count(),
+            4
+        );
+    }
+
+    #[test_case("Malformed", "Could not parse json file"; "malformed system")]
+    #[test_case("First", "Systems form a cycle"; "cyclic system")]
+    #[test_case("Missing", "neither a component nor a system"; "missing instance")]
+    fn invalid_systems_of_project_are_reported(system: &str, error: &str) {
+        match json_run_query(INVALID_SYSTEMS_PATH, &format!("consistency: {}", system)) {
+            Err(ExecutableQueryError::Custom(err)) => {
+                assert!(err.contains(error), "Unexpected error: {}", err)
+            }
+            result => panic!("Expected an error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn valid_system_is_used_next_to_invalid_systems() {
+        let result = json_run_query(INVALID_SYSTEMS_PATH, "consistency: Valid").unwrap();
+
+        assert!(matches!(result, QueryResult::Consistency(Ok(_))));
+    }
+}
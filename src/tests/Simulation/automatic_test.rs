@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::DataReader::parse_queries::parse_to_expression_tree;
+    use crate::Simulation::automatic::SimulationReport;
+    use crate::System::query_failures::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn simulate(query: &str) -> SimulationReport {
+        match json_run_query(PATH, query) {
+            Ok(QueryResult::Simulation(report)) => report,
+            result => panic!("Expected a simulation report, got {:?}", result),
+        }
+    }
+
+    #[test_case("simulate: Machine {runs=5, steps=20, seed=42}"; "machine")]
+    #[test_case("simulate: Administration || Machine || Researcher {runs=3, steps=30, seed=7}"; "composition")]
+    #[test_case("simulate: Spec // Machine {runs=3, steps=30, seed=7}"; "quotient")]
+    fn same_seed_gives_same_report(query: &str) {
+        assert_eq!(simulate(query), simulate(query));
+    }
+
+    #[test]
+    fn runs_are_bounded_by_steps() {
+        let report = simulate("simulate: Machine {runs=4, steps=10}");
+
+        assert_eq!(report.runs.len(), 4);
+        assert!(report
+            .runs
+            .iter()
+            .all(|run| run.steps == 10 && !run.deadlocked && !run.reached_target));
+    }
+
+    #[test]
+    fn coverage_of_machine() {
+        let report = simulate("simulate: Machine {runs=10, steps=20, seed=1}");
+
+        let coverage = &report.coverage["Machine"];
+        assert_eq!(coverage.total_locations, 2);
+        assert_eq!(coverage.total_edges, 5);
+        assert_eq!(coverage.visited_locations.len(), 2);
+        assert!(coverage.visited_edges.len() <= coverage.total_edges);
+    }
+
+    #[test]
+    fn coverage_of_every_component() {
+        let report = simulate("simulate: Administration || Machine || Researcher");
+
+        assert_eq!(
+            report.coverage.keys().collect::<Vec<_>>(),
+            vec!["Administration", "Machine", "Researcher"]
+        );
+    }
+
+    #[test]
+    fn guided_runs_reach_target() {
+        let report = simulate("simulate: Machine {runs=3} -> Machine.L4");
+
+        assert!(report
+            .runs
+            .iter()
+            .all(|run| run.reached_target && run.steps == 1));
+    }
+
+    #[test]
+    fn zero_weights_deadlock() {
+        let report =
+            simulate("simulate: Machine {runs=2, weight(coin)=0, weight(tea)=0, weight(cof)=0}");
+
+        assert!(report
+            .runs
+            .iter()
+            .all(|run| run.deadlocked && run.steps == 0));
+    }
+
+    #[test]
+    fn no_runs_without_initial_state() {
+        let report = match json_run_query("samples/json/RefinementTests", "simulate: D") {
+            Ok(QueryResult::Simulation(report)) => report,
+            result => panic!("Expected a simulation report, got {:?}", result),
+        };
+
+        assert!(report.no_initial_state);
+        assert!(report.runs.is_empty());
+    }
+
+    #[test]
+    fn too_many_runs_is_a_parse_error() {
+        let result = parse_to_expression_tree("simulate: Machine {runs=99999999999999999999}");
+
+        assert!(result
+            .unwrap_err()
+            .contains("Invalid number 99999999999999999999"));
+    }
+
+    #[test]
+    fn simulate_query_display() {
+        let query =
+            parse_to_expression_tree("simulate: Machine {runs=3, weight(tea)=0.5} -> Machine.L4")
+                .unwrap();
+
+        assert_eq!(
+            query[0].to_string(),
+            "simulate: Machine {runs=3, steps=100, seed=0, weight(tea)=0.5} -> Machine.L4"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::{json_get_system, json_run_query};
+    use crate::DataReader::parse_queries::parse_to_expression_tree;
+    use crate::Simulation::concrete::{
+        parse_delay, ConcreteSimulationError, ConcreteSimulator, DelayBound, DelayInterval,
+        Rational,
+    };
+    use crate::System::query_failures::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn rational(numerator: i64, denominator: i64) -> Rational {
+        Rational::new(numerator, denominator)
+    }
+
+    fn interval(lower: i64, upper: i64) -> DelayInterval {
+        DelayInterval {
+            lower: DelayBound {
+                value: rational(lower, 1),
+                strict: false,
+            },
+            upper: Some(DelayBound {
+                value: rational(upper, 1),
+                strict: false,
+            }),
+        }
+    }
+
+    fn machine() -> ConcreteSimulator {
+        ConcreteSimulator::new(json_get_system(PATH, "Machine")).unwrap()
+    }
+
+    #[test_case("2", rational(2, 1); "integer")]
+    #[test_case("2.5", rational(5, 2); "decimal")]
+    #[test_case("0.125", rational(1, 8); "long decimal")]
+    #[test_case("5/2", rational(5, 2); "fraction")]
+    fn parse_valid_delay(delay: &str, expected: Rational) {
+        assert_eq!(parse_delay(delay), Ok(expected));
+    }
+
+    #[test_case("-1"; "negative")]
+    #[test_case("1/0"; "zero denominator")]
+    #[test_case("2."; "missing decimals")]
+    #[test_case("two"; "not a number")]
+    fn parse_invalid_delay(delay: &str) {
+        assert!(parse_delay(delay).is_err());
+    }
+
+    #[test]
+    fn step_with_rational_delay() {
+        let mut simulator = machine();
+
+        let step = simulator.step(rational(5, 2), "tea").unwrap();
+
+        assert_eq!(step.state.valuation.get(1), rational(5, 2));
+        assert_eq!(simulator.steps().len(), 1);
+    }
+
+    #[test]
+    fn step_updates_clocks() {
+        let mut simulator = machine();
+        simulator.step(rational(5, 2), "tea").unwrap();
+
+        let step = simulator.step(rational(1, 3), "coin").unwrap();
+
+        assert_eq!(step.state.valuation.get(1), rational(0, 1));
+    }
+
+    #[test]
+    fn step_violating_guard() {
+        let mut simulator = machine();
+        simulator.step(rational(0, 1), "coin").unwrap();
+
+        let error = simulator.step(rational(7, 2), "cof").unwrap_err();
+
+        assert_eq!(
+            error,
+            ConcreteSimulationError::NotEnabled {
+                action: "cof".to_string(),
+                delay: rational(7, 2),
+                allowed: vec![interval(4, 6)],
+            }
+        );
+        assert_eq!(simulator.steps().len(), 1);
+    }
+
+    #[test]
+    fn step_violating_invariant() {
+        let mut simulator = machine();
+        simulator.step(rational(0, 1), "coin").unwrap();
+
+        let error = simulator.step(rational(13, 2), "cof").unwrap_err();
+
+        assert_eq!(
+            error,
+            ConcreteSimulationError::InvalidDelay {
+                delay: rational(13, 2),
+                allowed: Some(interval(0, 6)),
+            }
+        );
+    }
+
+    #[test]
+    fn decisions_have_delay_intervals() {
+        let mut simulator = machine();
+        simulator.step(rational(0, 1), "coin").unwrap();
+        simulator.step(rational(1, 2), "coin").unwrap();
+
+        let cof = simulator
+            .decisions()
+            .into_iter()
+            .find(|decision| decision.decision.action == "cof")
+            .unwrap();
+
+        // The clock y is reset by the first coin, so cof is enabled when 4 <= y <= 6, which is after 7/2 to 11/2 time units
+        let expected = DelayInterval {
+            lower: DelayBound {
+                value: rational(7, 2),
+                strict: false,
+            },
+            upper: Some(DelayBound {
+                value: rational(11, 2),
+                strict: false,
+            }),
+        };
+        assert_eq!(cof.delays, vec![expected]);
+        assert_eq!(cof.pick_delay(), rational(7, 2));
+    }
+
+    #[test]
+    fn take_decision_picks_delay() {
+        let mut simulator = machine();
+        let tea = simulator
+            .decisions()
+            .iter()
+            .position(|decision| decision.decision.action == "tea")
+            .unwrap();
+
+        let step = simulator.take_decision(tea, None).unwrap();
+
+        assert_eq!(step.delay, rational(2, 1));
+    }
+
+    #[test]
+    fn take_decision_validates_delay() {
+        let mut simulator = machine();
+        let tea = simulator
+            .decisions()
+            .iter()
+            .position(|decision| decision.decision.action == "tea")
+            .unwrap();
+
+        let error = simulator.take_decision(tea, Some(rational(3, 2)));
+
+        assert!(matches!(
+            error,
+            Err(ConcreteSimulationError::NotEnabled { .. })
+        ));
+    }
+
+    #[test]
+    fn concrete_trace_query() {
+        let result = json_run_query(PATH, "concrete-trace: Machine: 2.5 tea, 0 coin, 4.25 cof");
+
+        match result {
+            Ok(QueryResult::ConcreteTrace(Ok(trace))) => {
+                assert_eq!(trace.steps.len(), 3);
+                assert_eq!(trace.steps[2].state.clocks[0].1, rational(17, 4));
+                assert_eq!(
+                    trace.to_string(),
+                    "(Machine.L5) Machine.y=0\n  delay 5/2, tea -> (Machine.L5) Machine.y=5/2\n  delay 0, coin -> (Machine.L4) Machine.y=0\n  delay 17/4, cof -> (Machine.L5) Machine.y=17/4"
+                );
+            }
+            result => panic!("Expected a concrete trace, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn infeasible_concrete_trace_query() {
+        let result = json_run_query(PATH, "concrete-trace: Machine: 1 coin, 3 cof");
+
+        match result {
+            Ok(QueryResult::ConcreteTrace(Err(failure))) => {
+                assert_eq!(failure.trace.unwrap().steps.len(), 1);
+            }
+            result => panic!("Expected an infeasible trace, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn concrete_trace_query_display() {
+        let query = parse_to_expression_tree("concrete-trace: Machine: 2.5 tea, 1/3 coin").unwrap();
+
+        assert_eq!(
+            query[0].to_string(),
+            "concrete-trace: Machine: 5/2 tea, 1/3 coin"
+        );
+    }
+}
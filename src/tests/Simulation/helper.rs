@@ -4,7 +4,7 @@ use tonic::{Response, Status};
 
 use crate::ProtobufServer::services::component::Rep;
 use crate::ProtobufServer::services::{
-    Component as ProtoComponent, ComponentsInfo as ProtoComponentsInfo,
+    Component as ProtoComponent, ComponentsInfo as ProtoComponentsInfo, SimulationBatchRequest,
     SimulationInfo as ProtoSimulationInfo, SimulationStartRequest, SimulationStepRequest,
     SimulationStepResponse,
 };
@@ -19,6 +19,28 @@ impl SimulationStartRequest {
     }
 }
 
+impl SimulationBatchRequest {
+    pub fn new(
+        component_names: &[&str],
+        components_path: &str,
+        composition: &str,
+        runs: u32,
+        target: &str,
+    ) -> Self {
+        let simulation_info =
+            ProtoSimulationInfo::new(component_names, components_path, composition);
+        SimulationBatchRequest {
+            simulation_info: Some(simulation_info),
+            runs,
+            max_steps: 0,
+            seed: 0,
+            action_weights: Default::default(),
+            target: target.to_string(),
+            settings: None,
+        }
+    }
+}
+
 pub fn construct_step_requests(
     component_names: &[&str],
     components_path: &str,
@@ -1 +1,8 @@
+pub mod automatic_test;
+pub mod concrete_test;
 pub mod helper;
+pub mod repl_test;
+pub mod session_test;
+pub mod test_generation_test;
+pub mod test_run_test;
+pub mod trace_check_test;
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::json_get_system;
+    use crate::Simulation::repl::{run_repl, ReplCommand};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn run_machine(input: &str) -> String {
+        let mut output = vec![];
+        run_repl(
+            json_get_system(PATH, "Machine"),
+            input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test_case("2", ReplCommand::Take(2); "index")]
+    #[test_case("take 2", ReplCommand::Take(2); "take")]
+    #[test_case(" delay  5 ", ReplCommand::Delay(5); "delay")]
+    #[test_case("back", ReplCommand::Back; "back")]
+    #[test_case("zone", ReplCommand::Zone; "zone")]
+    #[test_case("exit", ReplCommand::Quit; "exit")]
+    fn parse_command(line: &str, expected: ReplCommand) {
+        assert_eq!(line.parse::<ReplCommand>(), Ok(expected));
+    }
+
+    #[test_case("delay -1"; "negative delay")]
+    #[test_case("take"; "missing index")]
+    #[test_case("fly"; "unknown")]
+    fn parse_invalid_command(line: &str) {
+        assert!(line.parse::<ReplCommand>().is_err());
+    }
+
+    #[test]
+    fn lists_decisions_with_edges() {
+        let output = run_machine("quit\n");
+
+        assert!(output.starts_with(
+            "Step 0: (Machine.L5)\nDecisions:\n  0: coin [Machine.E27] -> (Machine.L4)\n> "
+        ));
+    }
+
+    #[test]
+    fn delay_take_and_back() {
+        let output = run_machine("delay 3\nzone\n1\nback\n");
+
+        assert!(output.contains("Step 1: (Machine.L5)\nDecisions:\n  0: coin [Machine.E27] -> (Machine.L4)\n  1: tea [Machine.E29] -> (Machine.L5)\n"));
+        assert!(output.contains("Zone: Machine.y >= 3\n"));
+        assert!(output.contains("Step 2: (Machine.L5)"));
+        assert!(output.ends_with("Step 1: (Machine.L5)\nDecisions:\n  0: coin [Machine.E27] -> (Machine.L4)\n  1: tea [Machine.E29] -> (Machine.L5)\n> \n"));
+    }
+
+    #[test]
+    fn reports_errors_and_continues() {
+        let output = run_machine("7\nback\nfly\n0\n");
+
+        assert!(output.contains("There is no decision 7 in the current state\n"));
+        assert!(output.contains("There is no step to undo\n"));
+        assert!(output.contains("Unknown command 'fly'"));
+        assert!(output.contains("Step 1: (Machine.L4)"));
+    }
+}
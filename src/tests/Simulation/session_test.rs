@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::json_get_system;
+    use crate::Simulation::session::{SimulationError, SimulationSession};
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn machine_session() -> SimulationSession {
+        SimulationSession::new(json_get_system(PATH, "Machine")).unwrap()
+    }
+
+    #[test]
+    fn new_session_starts_in_initial_state() {
+        let session = machine_session();
+
+        assert_eq!(session.position(), 0);
+        assert_eq!(session.trace().len(), 1);
+        assert!(session.current().decision.is_none());
+        assert!(!session.current().decisions.is_empty());
+    }
+
+    #[test]
+    fn take_decision_extends_trace() {
+        let mut session = machine_session();
+        let decision = session.current().decisions[0].clone();
+
+        let step = session.take_decision(0).unwrap();
+
+        assert_eq!(step.decision.as_ref().unwrap().action, decision.action);
+        assert_eq!(
+            step.state.decorated_locations.id,
+            decision.next_state.decorated_locations.id
+        );
+        assert_eq!(session.position(), 1);
+        assert_eq!(session.trace().len(), 2);
+    }
+
+    #[test]
+    fn undo_and_redo_keep_trace() {
+        let mut session = machine_session();
+        session.take_decision(0).unwrap();
+        session.take_decision(0).unwrap();
+
+        session.undo().unwrap();
+        session.undo().unwrap();
+        assert_eq!(session.position(), 0);
+        assert_eq!(session.undo().unwrap_err(), SimulationError::NothingToUndo);
+
+        session.redo().unwrap();
+        assert_eq!(session.position(), 1);
+        assert_eq!(session.trace().len(), 3);
+    }
+
+    #[test]
+    fn decision_after_undo_discards_undone_steps() {
+        let mut session = machine_session();
+        session.take_decision(0).unwrap();
+        session.take_decision(0).unwrap();
+        session.undo().unwrap();
+
+        session.take_decision(0).unwrap();
+
+        assert_eq!(session.position(), 2);
+        assert_eq!(session.trace().len(), 3);
+        assert_eq!(session.redo().unwrap_err(), SimulationError::NothingToRedo);
+    }
+
+    #[test]
+    fn jump_to_earlier_step() {
+        let mut session = machine_session();
+        session.take_decision(0).unwrap();
+        session.take_decision(0).unwrap();
+
+        session.jump_to(0).unwrap();
+        assert_eq!(session.position(), 0);
+        session.jump_to(2).unwrap();
+        assert_eq!(session.position(), 2);
+        assert_eq!(
+            session.jump_to(3).unwrap_err(),
+            SimulationError::UnknownStep(3)
+        );
+    }
+
+    #[test]
+    fn unknown_decision() {
+        let mut session = machine_session();
+        let decisions = session.current().decisions.len();
+
+        assert_eq!(
+            session.take_decision(decisions).unwrap_err(),
+            SimulationError::UnknownDecision(decisions)
+        );
+        assert_eq!(session.trace().len(), 1);
+    }
+
+    fn actions(session: &SimulationSession) -> Vec<&str> {
+        session
+            .current()
+            .decisions
+            .iter()
+            .map(|decision| decision.action.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn delay_enables_guarded_decisions() {
+        let mut session = machine_session();
+        assert_eq!(actions(&session), vec!["coin"]);
+
+        let step = session.delay(3).unwrap();
+
+        assert!(step.decision.is_none());
+        assert_eq!(session.position(), 1);
+        assert_eq!(actions(&session), vec!["coin", "tea"]);
+    }
+
+    #[test]
+    fn delay_violating_invariant_fails() {
+        let mut session = machine_session();
+        session.take_decision(0).unwrap();
+
+        assert_eq!(
+            session.delay(7).unwrap_err(),
+            SimulationError::InvalidDelay(7)
+        );
+        assert_eq!(session.position(), 1);
+        session.delay(6).unwrap();
+    }
+
+    #[test]
+    fn delay_too_large_for_a_zone_fails() {
+        let mut session = machine_session();
+
+        assert_eq!(
+            session.delay(u32::MAX).unwrap_err(),
+            SimulationError::InvalidDelay(u32::MAX)
+        );
+        assert_eq!(
+            session.delay(1 << 30).unwrap_err(),
+            SimulationError::InvalidDelay(1 << 30)
+        );
+        assert_eq!(session.position(), 0);
+    }
+}
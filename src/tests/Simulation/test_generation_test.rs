@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::DataReader::parse_queries::parse_to_expression_tree;
+    use crate::Simulation::test_generation::{
+        ExpectedOutput, TestCase, TestSuite, TestTimeout, TimeWindow,
+    };
+    use crate::System::query_failures::QueryResult;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn generate(query: &str) -> TestSuite {
+        match json_run_query(PATH, query) {
+            Ok(QueryResult::TestCases(suite)) => suite,
+            result => panic!("Expected test cases, got {:?}", result),
+        }
+    }
+
+    fn window(lower: i32, upper: Option<i32>) -> TimeWindow {
+        TimeWindow {
+            lower,
+            lower_strict: false,
+            upper,
+            upper_strict: false,
+        }
+    }
+
+    fn input(action: &str, next: TestCase) -> TestCase {
+        TestCase::Input {
+            action: action.to_string(),
+            next: Box::new(next),
+        }
+    }
+
+    #[test]
+    fn machine_waits_for_tea_or_quiescence() {
+        let suite = generate("test-gen: Machine {depth=2}");
+
+        // Tea may be served 2 time units after the start, but the machine may also do nothing
+        assert_eq!(
+            suite.tests,
+            vec![TestCase::Observe {
+                outputs: vec![ExpectedOutput {
+                    action: "tea".to_string(),
+                    windows: vec![window(2, None)],
+                    next: input("coin", TestCase::Pass),
+                }],
+                timeout: TestTimeout::Continue {
+                    after: 10,
+                    next: Box::new(input("coin", TestCase::Pass)),
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn machine_must_serve_after_coin() {
+        let suite = generate("test-gen: Machine {depth=2, tests=2}");
+
+        // The invariant y <= 6 forces the machine to serve within 6 time units of the coin
+        assert_eq!(
+            suite.tests[1],
+            input(
+                "coin",
+                TestCase::Observe {
+                    outputs: vec![
+                        ExpectedOutput {
+                            action: "cof".to_string(),
+                            windows: vec![window(4, Some(6))],
+                            next: TestCase::Pass,
+                        },
+                        ExpectedOutput {
+                            action: "tea".to_string(),
+                            windows: vec![window(0, Some(6))],
+                            next: TestCase::Pass,
+                        },
+                    ],
+                    timeout: TestTimeout::Fail { after: 6 },
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn coverage_covers_machine() {
+        let suite = generate("test-gen: Machine {depth=4, tests=5, coverage}");
+
+        let coverage = &suite.coverage["Machine"];
+        assert_eq!(coverage.visited_locations.len(), coverage.total_locations);
+        assert_eq!(coverage.visited_edges.len(), coverage.total_edges);
+        // The generation stops once a test case covers nothing new
+        assert!(suite.tests.len() < 5);
+    }
+
+    #[test_case("test-gen: Machine {depth=4, tests=3}"; "machine")]
+    #[test_case("test-gen: Administration || Machine || Researcher {depth=3, tests=2, coverage}"; "composition")]
+    #[test_case("test-gen: Spec {depth=3, wait=5}"; "spec")]
+    fn generation_is_deterministic(query: &str) {
+        assert_eq!(generate(query), generate(query));
+    }
+
+    #[test]
+    fn test_cases_export_as_json() {
+        let suite = generate("test-gen: Machine {depth=2}");
+
+        let json = serde_json::to_value(&suite.tests).unwrap();
+        assert_eq!(json[0]["kind"], "observe");
+        assert_eq!(json[0]["outputs"][0]["action"], "tea");
+        assert_eq!(json[0]["timeout"]["verdict"], "continue");
+        assert_eq!(json[0]["timeout"]["next"]["kind"], "input");
+    }
+
+    #[test]
+    fn too_deep_test_is_a_parse_error() {
+        let result = parse_to_expression_tree("test-gen: Machine {depth=99999999999999999999}");
+
+        assert!(result
+            .unwrap_err()
+            .contains("Invalid number 99999999999999999999"));
+    }
+
+    #[test]
+    fn test_generation_query_display() {
+        let query = parse_to_expression_tree("test-gen: Machine {tests=3, coverage}").unwrap();
+
+        assert_eq!(
+            query[0].to_string(),
+            "test-gen: Machine {depth=5, tests=3, wait=10, coverage}"
+        );
+    }
+}
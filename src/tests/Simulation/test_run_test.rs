@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::DataReader::component_loader::JsonProjectLoader;
+    use crate::Simulation::test_run::{
+        self, TestEvent, TestRunOptions, TestRunReport, TestVerdict,
+    };
+    use crate::TransitionSystems::TransitionSystemPtr;
+    use edbm::util::constraints::ClockIndex;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const SUT: &str = "samples/sut/machine.sh";
+
+    fn machine() -> (TransitionSystemPtr, ClockIndex) {
+        let project_loader =
+            JsonProjectLoader::new_loader(String::from(PATH), crate::tests::TEST_SETTINGS);
+        let mut loader = project_loader.to_comp_loader();
+        test_run::specification_with_clock(&mut *loader, "Machine").unwrap()
+    }
+
+    fn run(options: &TestRunOptions, command: &[&str]) -> TestRunReport {
+        let (system, clock) = machine();
+        let command: Vec<String> = command.iter().map(|word| word.to_string()).collect();
+        test_run::run_process(&system, clock, options, &command).unwrap()
+    }
+
+    #[test_case(0; "seed 0")]
+    #[test_case(1; "seed 1")]
+    #[test_case(2; "seed 2")]
+    fn conforming_machine_passes(seed: u64) {
+        let options = TestRunOptions {
+            steps: 30,
+            seed,
+            ..Default::default()
+        };
+        let report = run(&options, &["sh", SUT]);
+
+        assert_eq!(report.verdict, TestVerdict::Pass);
+        assert_eq!(report.trace.len(), 30);
+    }
+
+    #[test]
+    fn late_coffee_fails() {
+        let options = TestRunOptions {
+            steps: 50,
+            ..Default::default()
+        };
+        // The invariant of the specification requires the coffee within 6 time units of the coin
+        let report = run(&options, &["sh", SUT, "7"]);
+
+        assert!(matches!(report.verdict, TestVerdict::Fail(_)));
+        let coin = report
+            .trace
+            .iter()
+            .rev()
+            .find_map(|event| match event {
+                TestEvent::Input(event) => Some(event.timestamp),
+                _ => None,
+            })
+            .unwrap();
+        match report.trace.last() {
+            Some(TestEvent::Output(event)) => {
+                assert_eq!(event.action, "cof");
+                assert_eq!(event.timestamp, coin + 7);
+            }
+            event => panic!("Expected the late coffee to fail, got {:?}", event),
+        }
+    }
+
+    #[test_case(""; "no answer")]
+    #[test_case("coffee please\n"; "invalid answer")]
+    #[test_case("ok -1\n"; "negative time")]
+    fn protocol_errors(answers: &str) {
+        let (system, clock) = machine();
+        let result = test_run::run_test(
+            &system,
+            clock,
+            &TestRunOptions::default(),
+            Vec::new(),
+            Cursor::new(answers.to_string()),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn silent_sut_is_inconclusive() {
+        let options = TestRunOptions {
+            answer_timeout_ms: 100,
+            ..Default::default()
+        };
+        let report = run(&options, &["sleep", "10"]);
+
+        assert!(matches!(report.verdict, TestVerdict::Inconclusive(_)));
+        assert!(report.trace.is_empty());
+    }
+
+    #[test]
+    fn missing_sut_is_an_error() {
+        let (system, clock) = machine();
+        let command = vec!["./does-not-exist".to_string()];
+
+        assert!(
+            test_run::run_process(&system, clock, &TestRunOptions::default(), &command).is_err()
+        );
+    }
+}
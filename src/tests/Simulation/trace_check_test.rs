@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::refinement::Helper::{json_get_system, json_run_query};
+    use crate::DataReader::parse_queries::parse_to_expression_tree;
+    use crate::Simulation::concrete::Rational;
+    use crate::Simulation::trace_check::{check_trace, parse_csv_trace, parse_json_trace};
+    use crate::System::query_failures::{QueryResult, TraceDeviation};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn check_machine(trace: &str) -> crate::System::query_failures::TraceCheckResult {
+        let events = parse_csv_trace(trace).unwrap();
+        check_trace(&json_get_system(PATH, "Machine"), &events).unwrap()
+    }
+
+    #[test]
+    fn parse_csv_and_json_alike() {
+        let csv = parse_csv_trace("action,timestamp\n\n# comment\ncoin, 0\ntea,5/2\ncof,2.75\n");
+        let json = parse_json_trace(
+            r#"[{"action": "coin", "timestamp": 0}, {"action": "tea", "timestamp": "5/2"}, {"action": "cof", "timestamp": 2.75}]"#,
+        );
+
+        assert_eq!(csv, json);
+        let events = csv.unwrap();
+        assert_eq!(events[1].timestamp, Rational::new(5, 2));
+        assert_eq!(events[2].timestamp, Rational::new(11, 4));
+    }
+
+    #[test_case("coin"; "missing timestamp")]
+    #[test_case("coin,-1"; "negative timestamp")]
+    #[test_case("coin,soon"; "invalid timestamp")]
+    fn parse_invalid_csv(trace: &str) {
+        assert!(parse_csv_trace(trace)
+            .unwrap_err()
+            .starts_with("Invalid line 1"));
+    }
+
+    #[test_case(r#"{"action": "coin"}"#; "not an array")]
+    #[test_case(r#"[{"action": "coin"}]"#; "missing timestamp")]
+    #[test_case(r#"[{"timestamp": 1}]"#; "missing action")]
+    fn parse_invalid_json(trace: &str) {
+        assert!(parse_json_trace(trace).is_err());
+    }
+
+    #[test]
+    fn conforming_trace() {
+        let states = check_machine("coin,0\ntea,2.5\ncoin,3\ncof,7").unwrap();
+
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].to_string(), "(Machine.L5) Machine.y=4");
+    }
+
+    #[test_case("coin,1\ncof,3", 1, TraceDeviation::NotEnabled, "(Machine.L4) Machine.y=2"; "guard")]
+    #[test_case("coin,1\ncof,8", 1, TraceDeviation::InvariantViolated, "(Machine.L4) Machine.y=0"; "invariant")]
+    #[test_case("coin,1\nfly,2", 1, TraceDeviation::UnknownAction, "(Machine.L4) Machine.y=0"; "unknown action")]
+    #[test_case("coin,2\ntea,1", 1, TraceDeviation::TimeDecreasing { previous: Rational::from_integer(2) }, "(Machine.L4) Machine.y=0"; "time decreasing")]
+    fn first_deviating_event(trace: &str, index: usize, deviation: TraceDeviation, state: &str) {
+        let failure = check_machine(trace).unwrap_err();
+
+        assert_eq!(failure.event.unwrap().0, index);
+        assert_eq!(failure.deviation, deviation);
+        assert_eq!(failure.states.len(), 1);
+        assert_eq!(failure.states[0].to_string(), state);
+    }
+
+    #[test_case("samples/traces/Machine.csv"; "csv")]
+    #[test_case("samples/traces/Machine.json"; "json")]
+    fn trace_check_query(file: &str) {
+        let result = json_run_query(PATH, &format!("trace-check: Machine: \"{}\"", file));
+
+        assert!(matches!(result, Ok(QueryResult::TraceCheck(Ok(_)))));
+    }
+
+    #[test]
+    fn trace_check_query_failure() {
+        let result = json_run_query(
+            PATH,
+            "trace-check: Machine: \"samples/traces/MachineTooLate.csv\"",
+        );
+
+        match result {
+            Ok(QueryResult::TraceCheck(Err(failure))) => assert_eq!(
+                failure.to_string(),
+                "Event 2 (cof at 7) leaves the behaviour of the system: the invariants do not allow waiting until it happened"
+            ),
+            result => panic!("Expected a failed trace check, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn trace_check_query_missing_file() {
+        let result = json_run_query(PATH, "trace-check: Machine: \"samples/traces/Missing.csv\"");
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn trace_check_query_display() {
+        let query =
+            parse_to_expression_tree("trace-check: Machine || Researcher : \"runs/run 1.csv\"")
+                .unwrap();
+
+        assert_eq!(
+            query[0].to_string(),
+            "trace-check: (Machine || Researcher): \"runs/run 1.csv\""
+        );
+    }
+}
@@ -0,0 +1,114 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::ProtobufServer::ecdar_requests::cancel_query::RunningQueries;
+    use crate::System::cancellation::{self, CancellationToken, Interrupted};
+    use crate::System::query_failures::QueryResult;
+    use rayon::ThreadPoolBuilder;
+    use std::time::Duration;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn run_with_token(token: &CancellationToken, query: &str) -> Result<QueryResult, Interrupted> {
+        cancellation::run_cancellable(token, || json_run_query(PATH, query).unwrap())
+    }
+
+    #[test]
+    fn uninterrupted_query_finishes_test() {
+        let token = CancellationToken::with_timeout(Duration::from_secs(600));
+        let result = run_with_token(&token, "refinement: Machine <= Machine");
+        assert!(matches!(result, Ok(QueryResult::Refinement(Ok(_)))));
+    }
+
+    #[test]
+    fn cancelled_refinement_test() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = run_with_token(&token, "refinement: Machine <= Machine");
+        assert_eq!(result.unwrap_err(), Interrupted::Cancelled);
+    }
+
+    #[test]
+    fn timed_out_reachability_test() {
+        let token = CancellationToken::with_timeout(Duration::ZERO);
+        let result = run_with_token(&token, "reachability: Machine @ Machine.L5 -> Machine.L4");
+        assert_eq!(result.unwrap_err(), Interrupted::Timeout);
+    }
+
+    #[test]
+    fn cancelled_consistency_test() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = run_with_token(&token, "consistency: Administration || Researcher");
+        assert_eq!(result.unwrap_err(), Interrupted::Cancelled);
+    }
+
+    #[test]
+    fn cancelled_parallel_refinement_test() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let thread_pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let result = thread_pool.install(|| {
+            run_with_token(
+                &token,
+                "refinement: Administration || Researcher || Machine <= Spec",
+            )
+        });
+        assert_eq!(result.unwrap_err(), Interrupted::Cancelled);
+    }
+
+    #[test]
+    fn token_is_restored_after_query_test() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let _ = run_with_token(&token, "refinement: Machine <= Machine");
+        assert!(cancellation::current_token().is_none());
+        assert!(json_run_query(PATH, "refinement: Machine <= Machine").is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not an interruption")]
+    fn other_panics_are_passed_on_test() {
+        let _ = cancellation::run_cancellable(&CancellationToken::new(), || {
+            panic!("Not an interruption")
+        });
+    }
+
+    #[test]
+    fn interruption_is_kept_after_query_test() {
+        let token = CancellationToken::new();
+        let _ = run_with_token(&token, "refinement: Machine <= Machine");
+        assert!(token.interrupted().is_none());
+        token.cancel();
+        assert_eq!(token.checkpoint(), Err(Interrupted::Cancelled));
+        assert_eq!(token.interrupted(), Some(Interrupted::Cancelled));
+    }
+
+    #[test]
+    fn finished_query_keeps_replacing_query_test() {
+        let running_queries = RunningQueries::default();
+        let (first, second) = (CancellationToken::new(), CancellationToken::new());
+        let first_query = running_queries.register(0, 0, first.clone());
+        let second_query = running_queries.register(0, 0, second.clone());
+
+        drop(first_query);
+        assert!(running_queries.cancel(0, 0));
+        assert_eq!(second.interruption(), Some(Interrupted::Cancelled));
+        assert!(first.interruption().is_none());
+
+        drop(second_query);
+        assert!(!running_queries.cancel(0, 0));
+    }
+
+    #[test]
+    fn interrupted_query_results_test() {
+        assert!(matches!(
+            QueryResult::from(Interrupted::Timeout),
+            QueryResult::Timeout
+        ));
+        assert!(matches!(
+            QueryResult::from(Interrupted::Cancelled),
+            QueryResult::Cancelled
+        ));
+    }
+}
@@ -7,7 +7,7 @@ mod test {
         ConsistencyFailure, DeterminismFailure, DeterminismResult, QueryResult, RefinementFailure,
         RefinementPrecondition,
     };
-    use crate::System::specifics::SpecificLocation;
+    use crate::System::specifics::{SpecificEdge, SpecificLocation};
     const PATH: &str = "samples/json/Actions";
 
     #[test]
@@ -18,6 +18,7 @@ mod test {
             state: actual_state,
             action: actual_action,
             system: actual_system,
+            path: actual_path,
         })) = json_run_query(PATH, "determinism: NonDeterministic1").unwrap()
         {
             let actual_location = actual_state.locations;
@@ -26,6 +27,7 @@ mod test {
                 (actual_location, actual_action.name)
             );
             assert_eq!(actual_system, "NonDeterministic1");
+            assert!(actual_path.path.is_empty());
         } else {
             panic!("Models in samples/action have been changed, REVERT!");
         }
@@ -37,8 +39,17 @@ mod test {
         if let QueryResult::Consistency(Err(ConsistencyFailure::InconsistentFrom {
             state: actual_state,
             system: actual_system,
+            path: actual_path,
         })) = json_run_query(PATH, "consistency: NonConsistent").unwrap()
         {
+            assert_eq!(actual_path.path.len(), 1);
+            let decision = &actual_path.path[0];
+            assert_eq!(decision.action, "coffee");
+            assert_eq!(
+                decision.edges,
+                vec![SpecificEdge::new("NonConsistent", "E0", 0)]
+            );
+            assert_eq!(decision.destination_state, actual_state);
             let actual_location = actual_state.locations;
             assert_eq!((expected_location), (actual_location));
             assert_eq!(actual_system, "NonConsistent");
@@ -57,6 +68,7 @@ mod test {
                     state: actual_state,
                     action: actual_action,
                     system: actual_system,
+                    ..
                 }),
                 _,
             ),
@@ -81,6 +93,7 @@ mod test {
                 ConsistencyFailure::InconsistentFrom {
                     state: actual_state,
                     system: actual_system,
+                    ..
                 },
                 _,
             ),
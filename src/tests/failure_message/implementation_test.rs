@@ -0,0 +1,38 @@
+#[cfg(test)]
+
+mod test {
+    use crate::{
+        tests::refinement::Helper::json_run_query,
+        System::query_failures::{ImplementationFailure, ImplementationResult, QueryResult},
+    };
+
+    const PATH: &str = "samples/json/Implementation";
+
+    #[test]
+    fn implementation_test() {
+        let actual = json_run_query(PATH, "implementation: Impl").unwrap();
+        assert!(matches!(actual, QueryResult::Implementation(Ok(()))));
+    }
+
+    #[test]
+    fn not_output_urgent_test() {
+        let actual = json_run_query(PATH, "implementation: NotUrgent").unwrap();
+        assert!(matches!(
+            actual,
+            QueryResult::Implementation(ImplementationResult::Err(
+                ImplementationFailure::NotOutputUrgent { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn no_independent_progress_test() {
+        let actual = json_run_query(PATH, "implementation: NoProgress").unwrap();
+        assert!(matches!(
+            actual,
+            QueryResult::Implementation(ImplementationResult::Err(
+                ImplementationFailure::NoIndependentProgress { .. }
+            ))
+        ));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod actions_test;
 pub mod consistency_test;
 pub mod determinism_test;
+pub mod implementation_test;
 pub mod refinement_test;
+pub mod specification_test;
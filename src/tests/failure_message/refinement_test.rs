@@ -1,6 +1,8 @@
 #[cfg(test)]
 
 mod test {
+    use test_case::test_case;
+
     use crate::{
         tests::refinement::Helper::json_run_query,
         System::query_failures::{
@@ -86,4 +88,38 @@ mod test {
             ))),
         ));
     }
+
+    #[test]
+    fn cannot_match_after_step_test() {
+        let actual = json_run_query(PATH, "refinement: A2 <= B").unwrap();
+        let (state, path) = match actual {
+            QueryResult::Refinement(Err(RefinementFailure::CannotMatch {
+                state, path, ..
+            })) => (state, path),
+            actual => panic!("Expected a CannotMatch failure, got {:?}", actual),
+        };
+        assert_eq!(path.path.len(), 1);
+        assert_eq!(path.path[0].action, "in");
+        assert_eq!(path.path[0].edges.len(), 2);
+        assert_eq!(path.path[0].destination_state, state);
+    }
+
+    #[test_case("refinement: A <= B"; "cannot match in initial state")]
+    #[test_case("refinement: A2 <= B2"; "cuts delay solutions in initial state")]
+    #[test_case("refinement: A2 <= B"; "cannot match after step")]
+    fn failure_path_leads_to_state_test(query: &str) {
+        let (state, path) = match json_run_query(PATH, query).unwrap() {
+            QueryResult::Refinement(Err(
+                RefinementFailure::CannotMatch { state, path, .. }
+                | RefinementFailure::CutsDelaySolutions { state, path, .. },
+            )) => (state, path),
+            actual => panic!("Expected a refinement failure in a state, got {:?}", actual),
+        };
+        for (decision, next) in path.path.iter().zip(path.path.iter().skip(1)) {
+            assert_eq!(decision.destination_state, next.source_state);
+        }
+        if let Some(last) = path.path.last() {
+            assert_eq!(last.destination_state, state);
+        }
+    }
 }
@@ -0,0 +1,64 @@
+#[cfg(test)]
+
+mod test {
+    use crate::{
+        tests::refinement::Helper::json_run_query,
+        System::query_failures::{
+            ConsistencyFailure, QueryResult, SpecificationFailure, SpecificationResult,
+        },
+    };
+
+    #[test]
+    fn specification_test() {
+        let actual = json_run_query("samples/json/Implementation", "specification: Impl").unwrap();
+        assert!(matches!(actual, QueryResult::Specification(Ok(()))));
+    }
+
+    #[test]
+    fn not_consistent_specification_test() {
+        let actual = json_run_query(
+            "samples/json/ConsistencyTest",
+            "specification: notConsistent",
+        )
+        .unwrap();
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Err(
+                SpecificationFailure::NotConsistent(ConsistencyFailure::InconsistentFrom { .. })
+            ))
+        ));
+    }
+
+    #[test]
+    fn not_deterministic_specification_test() {
+        let actual = json_run_query(
+            "samples/json/Determinism",
+            "specification: NonDeterminismCom",
+        )
+        .unwrap();
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Err(
+                SpecificationFailure::NotDeterministic(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn not_consistent_nor_deterministic_specification_test() {
+        let actual = json_run_query(
+            "samples/json/ConsistencyTest",
+            "specification: notConsistentNorDeterministic",
+        )
+        .unwrap();
+        assert!(matches!(
+            actual,
+            QueryResult::Specification(SpecificationResult::Err(
+                SpecificationFailure::NotConsistentNorDeterministic(
+                    ConsistencyFailure::InconsistentFrom { .. },
+                    _
+                )
+            ))
+        ));
+    }
+}
@@ -3,6 +3,7 @@ mod refinements {
     use crate::ProtobufServer::services::component::Rep;
     use crate::ProtobufServer::services::ecdar_backend_server::EcdarBackend;
     use crate::ProtobufServer::services::query_response;
+    use crate::ProtobufServer::services::CancelQueryRequest;
     use crate::ProtobufServer::services::Component;
     use crate::ProtobufServer::services::ComponentsInfo;
     use crate::ProtobufServer::services::QueryRequest;
@@ -11,6 +12,9 @@ mod refinements {
 
     //const CONJUN: &str = "samples/xml/conjun.xml";
     const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+    const CONSISTENCY_TEST: &str = "samples/json/ConsistencyTest";
+    const TEMPORAL: &str = "samples/json/Temporal";
+    const IMPLEMENTATION: &str = "samples/json/Implementation";
 
     #[tokio::test]
     async fn send_self_refinement_query() {
@@ -28,6 +32,29 @@ mod refinements {
         }
     }
 
+    #[tokio::test]
+    async fn send_self_refinement_query_saving_relation() {
+        let backend = ConcreteEcdarBackend::default();
+        let mut query_request = construct_query_request("refinement: Machine <= Machine");
+        query_request
+            .get_mut()
+            .settings
+            .as_mut()
+            .unwrap()
+            .save_refinement_relations = true;
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        match result {
+            query_response::Result::RefinementRelation(relation) => {
+                assert!(!relation.states.is_empty())
+            }
+            _ => panic!("Expected a refinement relation, got {:?}", result),
+        }
+    }
+
     #[tokio::test]
     async fn send_consistency_query() {
         let backend = ConcreteEcdarBackend::default();
@@ -58,9 +85,158 @@ mod refinements {
         }
     }
 
+    #[tokio::test]
+    async fn cancel_unknown_query() {
+        let backend = ConcreteEcdarBackend::default();
+        let cancel_request = Request::new(CancelQueryRequest {
+            user_id: 0,
+            query_id: 42,
+        });
+
+        let cancel_response = backend.cancel_query(cancel_request).await;
+        assert_eq!(cancel_response.unwrap_err().code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn cancel_finished_query() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_query_request("refinement: Machine <= Machine");
+        assert!(backend.send_query(query_request).await.is_ok());
+
+        // The query is no longer running, so it cannot be cancelled
+        let cancel_request = Request::new(CancelQueryRequest {
+            user_id: 0,
+            query_id: 0,
+        });
+        let cancel_response = backend.cancel_query(cancel_request).await;
+        assert_eq!(cancel_response.unwrap_err().code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn send_specification_query_with_both_failures() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_component_query_request(
+            "specification: notConsistentNorDeterministic",
+            CONSISTENCY_TEST,
+            "notConsistentNorDeterministic",
+        );
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        match result {
+            query_response::Result::Specification(failure) => {
+                assert!(failure.consistency.is_some());
+                assert!(failure.determinism.is_some());
+            }
+            _ => panic!("Expected specification failure, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_implementation_query_not_output_urgent() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_component_query_request(
+            "implementation: NotUrgent",
+            IMPLEMENTATION,
+            "NotUrgent",
+        );
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        use query_response::implementation_failure::Failure;
+        match result {
+            query_response::Result::Implementation(query_response::ImplementationFailure {
+                failure: Some(Failure::NotOutputUrgent(state_action)),
+                ..
+            }) => {
+                assert!(state_action.state.is_some());
+                assert!(!state_action.action.is_empty());
+            }
+            _ => panic!("Expected an implementation failure, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_temporal_query_with_witness() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request =
+            construct_component_query_request("E<> Lamp: Lamp.On", TEMPORAL, "Lamp");
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        match result {
+            query_response::Result::TemporalPath(lasso) => {
+                assert_eq!(lasso.prefix.unwrap().decisions.len(), 1);
+                assert!(lasso.cycle.unwrap().decisions.is_empty());
+            }
+            _ => panic!("Expected a temporal path, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_leads_to_query_with_counterexample() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request = construct_component_query_request(
+            "leadsto: Lamp @ Lamp.Off --> Lamp.On",
+            TEMPORAL,
+            "Lamp",
+        );
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        match result {
+            query_response::Result::TemporalFailure(failure) => {
+                assert_eq!(failure.operator, "-->");
+                assert!(failure.counterexample.is_some());
+            }
+            _ => panic!("Expected a temporal failure, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn send_query_reaching_state_limit() {
+        let backend = ConcreteEcdarBackend::default();
+        let mut query_request = construct_query_request("refinement: Machine <= Machine");
+        query_request
+            .get_mut()
+            .settings
+            .as_mut()
+            .unwrap()
+            .max_states = 1;
+
+        let query_response = backend.send_query(query_request).await;
+
+        let query_result = query_response.unwrap().into_inner();
+        let result = query_result.result.unwrap();
+        match result {
+            query_response::Result::Inconclusive(inconclusive) => {
+                assert_eq!(inconclusive.limit, "at most 1 states");
+                assert!(inconclusive.states > 1);
+                assert!(inconclusive.approximate_bytes > 0);
+            }
+            _ => panic!("Expected an inconclusive result, got {:?}", result),
+        }
+    }
+
     fn construct_query_request(query: &str) -> Request<QueryRequest> {
+        construct_component_query_request(query, ECDAR_UNI, "Machine")
+    }
+
+    fn construct_component_query_request(
+        query: &str,
+        project: &str,
+        component: &str,
+    ) -> Request<QueryRequest> {
         let json =
-            std::fs::read_to_string(format!("{}/Components/Machine.json", ECDAR_UNI)).unwrap();
+            std::fs::read_to_string(format!("{}/Components/{}.json", project, component)).unwrap();
 
         Request::new(QueryRequest {
             user_id: 0,
@@ -73,9 +249,41 @@ mod refinements {
                 components_hash: 0,
             }),
             settings: Some(crate::tests::TEST_SETTINGS),
+            trace: String::new(),
         })
     }
 
+    #[tokio::test]
+    async fn send_trace_check_query_with_trace() {
+        let backend = ConcreteEcdarBackend::default();
+        let mut query_request =
+            construct_query_request("trace-check: Machine: \"Machine.csv\"").into_inner();
+        query_request.trace = std::fs::read_to_string("samples/traces/Machine.csv").unwrap();
+
+        let query_response = backend.send_query(Request::new(query_request)).await;
+
+        let result = query_response.unwrap().into_inner().result.unwrap();
+        match result {
+            query_response::Result::Success(_) => {}
+            _ => panic!("Expected success, got {:?}", result),
+        }
+    }
+
+    /// Ensure that the server does not read trace files on its own machine
+    #[tokio::test]
+    async fn send_trace_check_query_without_trace() {
+        let backend = ConcreteEcdarBackend::default();
+        let query_request =
+            construct_query_request("trace-check: Machine: \"samples/traces/Machine.csv\"");
+
+        let query_response = backend.send_query(query_request).await;
+
+        assert_eq!(
+            query_response.unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
     /// Ensure that the backend does not crash when a query panics
     #[tokio::test]
     async fn send_panic_query() {
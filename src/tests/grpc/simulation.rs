@@ -1,10 +1,17 @@
 #[cfg(test)]
 mod tests {
-    use crate::ProtobufServer::services::SimulationStartRequest;
+    use crate::DataReader::component_loader::ModelCache;
+    use crate::ProtobufServer::ecdar_requests::simulation_sessions::SimulationSessions;
+    use crate::ProtobufServer::services::query_request::Settings;
+    use crate::ProtobufServer::services::{
+        SessionDelayRequest, SessionJumpRequest, SessionStepRequest, SimulationBatchRequest,
+        SimulationSessionRequest, SimulationStartRequest,
+    };
     use crate::{
         tests::Simulation::helper::construct_step_requests,
         ProtobufServer::{self, services::ecdar_backend_server::EcdarBackend},
     };
+    use std::time::Duration;
     use test_case::test_case;
     use tonic::Request;
 
@@ -80,4 +87,271 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn simulation_session_steps_undo_redo_and_end() {
+        // Arrange
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationStartRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+        ));
+
+        // Act
+        let start = backend
+            .start_simulation_session(request)
+            .await
+            .unwrap()
+            .into_inner();
+        let session_id = start.session_id;
+        let step = backend
+            .take_session_step(Request::new(SessionStepRequest {
+                session_id,
+                user_id: 0,
+                decision_index: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let undo = backend
+            .undo_simulation_step(Request::new(SimulationSessionRequest {
+                session_id,
+                user_id: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let redo = backend
+            .redo_simulation_step(Request::new(SimulationSessionRequest {
+                session_id,
+                user_id: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let jump = backend
+            .jump_to_simulation_step(Request::new(SessionJumpRequest {
+                session_id,
+                user_id: 0,
+                step: 0,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let end = backend
+            .end_simulation(Request::new(SimulationSessionRequest {
+                session_id,
+                user_id: 0,
+            }))
+            .await;
+        let after_end = backend
+            .take_session_step(Request::new(SessionStepRequest {
+                session_id,
+                user_id: 0,
+                decision_index: 0,
+            }))
+            .await;
+
+        // Assert
+        assert_eq!((start.step, start.trace_length), (0, 1));
+        assert_eq!((step.step, step.trace_length), (1, 2));
+        assert_eq!(undo.current, start.current);
+        assert_eq!(redo.current, step.current);
+        assert_eq!((jump.step, jump.trace_length), (0, 2));
+        assert!(end.is_ok());
+        assert_eq!(after_end.unwrap_err().code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn simulation_session_undo_initial_step_fails() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationStartRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+        ));
+        let session_id = backend
+            .start_simulation_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+
+        let undo = backend
+            .undo_simulation_step(Request::new(SimulationSessionRequest {
+                session_id,
+                user_id: 0,
+            }))
+            .await;
+
+        assert_eq!(undo.unwrap_err().code(), tonic::Code::FailedPrecondition);
+    }
+
+    #[tokio::test]
+    async fn simulation_session_delays() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationStartRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+        ));
+        let session_id = backend
+            .start_simulation_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+
+        let delay = backend
+            .delay_simulation_step(Request::new(SessionDelayRequest {
+                session_id,
+                user_id: 0,
+                delay: 5,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        let invalid_delay = backend
+            .delay_simulation_step(Request::new(SessionDelayRequest {
+                session_id,
+                user_id: 0,
+                delay: u32::MAX,
+            }))
+            .await;
+
+        assert_eq!((delay.step, delay.trace_length), (1, 2));
+        assert_eq!(
+            invalid_delay.unwrap_err().code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+
+    #[tokio::test]
+    async fn start_simulation_session_without_info_fails() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationStartRequest {
+            simulation_info: None,
+        });
+
+        let start = backend.start_simulation_session(request).await;
+
+        assert_eq!(start.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn simulation_session_of_another_user_is_denied() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationStartRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+        ));
+        let session_id = backend
+            .start_simulation_session(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_id;
+
+        let step = backend
+            .take_session_step(Request::new(SessionStepRequest {
+                session_id,
+                user_id: 1,
+                decision_index: 0,
+            }))
+            .await;
+        let end = backend
+            .end_simulation(Request::new(SimulationSessionRequest {
+                session_id,
+                user_id: 1,
+            }))
+            .await;
+
+        assert_eq!(step.unwrap_err().code(), tonic::Code::PermissionDenied);
+        assert_eq!(end.unwrap_err().code(), tonic::Code::PermissionDenied);
+    }
+
+    #[test]
+    fn idle_simulation_session_is_closed() {
+        let sessions = SimulationSessions::with_idle_timeout(Duration::ZERO);
+        let request =
+            SimulationStartRequest::new(&["Machine"], "samples/json/EcdarUniversity", "(Machine)");
+        let session_id = ProtobufServer::ConcreteEcdarBackend::handle_start_simulation_session(
+            request,
+            ModelCache::default(),
+            &sessions,
+        )
+        .unwrap()
+        .session_id;
+
+        let step = ProtobufServer::ConcreteEcdarBackend::handle_take_session_step(
+            SessionStepRequest {
+                session_id,
+                user_id: 0,
+                decision_index: 0,
+            },
+            &sessions,
+        );
+
+        assert_eq!(step.unwrap_err().code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn simulate_batch_reports_runs_and_coverage() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationBatchRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+            3,
+            "Machine.L4",
+        ));
+
+        let report = backend.simulate_batch(request).await.unwrap().into_inner();
+
+        assert_eq!(report.runs.len(), 3);
+        assert!(report.runs.iter().all(|run| run.reached_target));
+        assert_eq!(report.coverage.len(), 1);
+        assert_eq!(report.coverage[0].component_name, "Machine");
+        assert_eq!(report.coverage[0].total_locations, 2);
+    }
+
+    #[tokio::test]
+    async fn simulate_batch_with_invalid_target_fails() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationBatchRequest::new(
+            &["Machine"],
+            "samples/json/EcdarUniversity",
+            "(Machine)",
+            1,
+            "Machine.L42",
+        ));
+
+        let response = backend.simulate_batch(request).await;
+
+        assert_eq!(response.unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn simulate_batch_times_out() {
+        let backend = ProtobufServer::ConcreteEcdarBackend::default();
+        let request = Request::new(SimulationBatchRequest {
+            settings: Some(Settings {
+                timeout_ms: 1,
+                ..crate::tests::TEST_SETTINGS
+            }),
+            ..SimulationBatchRequest::new(
+                &["Machine"],
+                "samples/json/EcdarUniversity",
+                "(Machine)",
+                1_000_000,
+                "",
+            )
+        });
+
+        let response = backend.simulate_batch(request).await;
+
+        assert_eq!(response.unwrap_err().code(), tonic::Code::DeadlineExceeded);
+    }
 }
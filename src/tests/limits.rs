@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::{json_get_system, json_run_query};
+    use crate::DataTypes::{PassedStateListExt, WaitingStateList};
+    use crate::ModelObjects::StatePair;
+    use crate::System::cancellation::{self, CancellationToken, Interrupted};
+    use crate::System::limits::{self, ExplorationLimits, Limit, LimitReached};
+    use crate::System::query_failures::QueryResult;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const REFINEMENT: &str = "refinement: Administration || Researcher || Machine <= Spec";
+
+    fn run_with_limits(limits: ExplorationLimits, query: &str) -> Result<QueryResult, Interrupted> {
+        let token = CancellationToken::new().with_limits(limits);
+        cancellation::run_cancellable(&token, || json_run_query(PATH, query).unwrap())
+    }
+
+    fn limit_reached<T: std::fmt::Debug>(result: Result<T, Interrupted>) -> LimitReached {
+        match result {
+            Err(Interrupted::LimitReached(limit)) => limit,
+            result => panic!("Expected the limit to be reached, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn state_limit_test() {
+        let limits = ExplorationLimits {
+            max_states: 2,
+            ..Default::default()
+        };
+        let limit = limit_reached(run_with_limits(limits, REFINEMENT));
+        assert_eq!(limit.limit, Limit::States(2));
+        assert_eq!(limit.statistics.states, 3);
+        assert!(limit.statistics.approximate_bytes > 0);
+    }
+
+    #[test]
+    fn byte_limit_test() {
+        let limits = ExplorationLimits {
+            max_bytes: 1,
+            ..Default::default()
+        };
+        let limit = limit_reached(run_with_limits(limits, REFINEMENT));
+        assert_eq!(limit.limit, Limit::Bytes(1));
+        assert_eq!(limit.statistics.states, 1);
+    }
+
+    #[test]
+    fn zones_per_location_limit_test() {
+        let token = CancellationToken::new().with_limits(ExplorationLimits {
+            max_zones_per_location: 1,
+            ..Default::default()
+        });
+        cancellation::with_token(Some(token.clone()), || {
+            assert!(limits::check_zones_per_location(1).is_ok());
+            let limit = limit_reached(limits::check_zones_per_location(2));
+            assert_eq!(limit.limit, Limit::ZonesPerLocation(1));
+        });
+        assert!(token.interrupted().is_some());
+    }
+
+    #[test]
+    fn waiting_list_counts_against_the_limits_test() {
+        let token = CancellationToken::new().with_limits(ExplorationLimits {
+            max_states: 1,
+            ..Default::default()
+        });
+        let system = json_get_system(PATH, "Machine");
+        let pair = StatePair::from_locations(
+            system.get_dim(),
+            system.get_initial_location().unwrap(),
+            system.get_initial_location().unwrap(),
+        );
+        cancellation::with_token(Some(token), || {
+            let mut waiting_list = WaitingStateList::new();
+            assert!(waiting_list.put(pair.clone()).is_ok());
+            // A popped state is no longer counted, so another one fits
+            waiting_list.pop();
+            assert!(waiting_list.put(pair.clone()).is_ok());
+            let limit = limit_reached(waiting_list.put(pair));
+            assert_eq!(limit.limit, Limit::States(1));
+            assert_eq!(limit.statistics.states, 2);
+        });
+    }
+
+    #[test]
+    fn generous_limits_do_not_interrupt_test() {
+        let limits = ExplorationLimits {
+            max_states: 1_000_000,
+            max_zones_per_location: 1_000_000,
+            max_bytes: 1 << 40,
+        };
+        let result = run_with_limits(limits, REFINEMENT);
+        assert!(matches!(result, Ok(QueryResult::Refinement(Ok(_)))));
+    }
+
+    #[test]
+    fn unlimited_query_records_statistics_test() {
+        let token = CancellationToken::new();
+        let result = cancellation::run_cancellable(&token, || json_run_query(PATH, REFINEMENT));
+        assert!(matches!(result, Ok(Ok(QueryResult::Refinement(Ok(_))))));
+        let statistics = token.statistics();
+        assert!(statistics.states > 0);
+        assert!(statistics.approximate_bytes > 0);
+    }
+
+    #[test]
+    fn limits_only_apply_within_a_query_test() {
+        let limits = ExplorationLimits {
+            max_states: 1,
+            ..Default::default()
+        };
+        let _ = run_with_limits(limits, REFINEMENT);
+        assert!(json_run_query(PATH, REFINEMENT).is_ok());
+    }
+
+    #[test]
+    fn inconclusive_result_test() {
+        let limits = ExplorationLimits {
+            max_states: 2,
+            ..Default::default()
+        };
+        let result = QueryResult::from(run_with_limits(limits, REFINEMENT).unwrap_err());
+        match result {
+            QueryResult::Inconclusive(limit) => {
+                assert!(limit.to_string().starts_with("inconclusive: limit reached"))
+            }
+            result => panic!("Expected an inconclusive result, got {:?}", result),
+        }
+    }
+}
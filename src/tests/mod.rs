@@ -3,9 +3,11 @@ use crate::ProtobufServer::services::query_request::Settings;
 pub mod ClockReduction;
 pub mod ModelObjects;
 pub mod Simulation;
+pub mod cancellation;
 pub mod edge_ids;
 pub mod failure_message;
 pub mod grpc;
+pub mod limits;
 pub mod reachability;
 pub mod refinement;
 pub mod sample;
@@ -15,4 +17,11 @@ pub mod system_recipe;
 /// The default settings for Testing
 pub const TEST_SETTINGS: Settings = Settings {
     disable_clock_reduction: false,
+    parallel_reachability: false,
+    parallel_refinement: false,
+    save_refinement_relations: false,
+    timeout_ms: 0,
+    max_states: 0,
+    max_zones_per_location: 0,
+    max_memory_bytes: 0,
 };
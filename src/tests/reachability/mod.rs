@@ -3,7 +3,9 @@ pub mod get_leaves_tests;
 pub mod grammar;
 pub mod helper_functions;
 pub mod location_validation;
+pub mod parallel_search_test;
 pub mod parse_partial_state;
 pub mod partial_state;
 pub mod search_algorithm_test;
 pub mod split_component_tests;
+pub mod temporal_test;
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod parallel_reachability_search_test {
+    use crate::tests::refinement::Helper::json_run_query_with_settings;
+    use crate::tests::TEST_SETTINGS;
+    use crate::ProtobufServer::services::query_request::Settings;
+    use crate::System::query_failures::{PathResult, QueryResult};
+    use rayon::ThreadPoolBuilder;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+    const PATH2: &str = "samples/json/AutomatonTestReachability";
+
+    fn run_reachability(path: &str, query: &str, parallel_reachability: bool) -> PathResult {
+        let settings = Settings {
+            parallel_reachability,
+            ..TEST_SETTINGS
+        };
+        let thread_pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        match thread_pool.install(|| json_run_query_with_settings(path, query, settings).unwrap()) {
+            QueryResult::Reachability(result) => result,
+            _ => panic!("Inconsistent query result, expected Reachability"),
+        }
+    }
+
+    #[test_case(PATH, "reachability: Machine @ Machine.L5 && Machine.y<6 -> Machine.L4 && Machine.y<=6"; "Existing states and with right clocks")]
+    #[test_case(PATH, "reachability: Machine @ Machine.L5 -> Machine.L4 && Machine.y>7"; "Exisiting locations but not possible with the clocks")]
+    #[test_case(PATH, "reachability: Machine || Researcher @ Machine.L5 && Researcher.L6 -> Machine.L4 && Researcher.L9"; "Composition between Machine & Researcher")]
+    #[test_case(PATH, "reachability: Machine || Researcher @ Machine.L5 && Researcher.U0 -> Researcher.L7"; "Machine || Researcher not reachable with partial end state")]
+    #[test_case(PATH, "reachability: Administration || Researcher || Machine @ init -> Researcher.L7"; "Composition of the university from the initial state")]
+    #[test_case(PATH2, "reachability: Component1 @ Component1.L1 -> Component1.L3"; "False due to invariants")]
+    #[test_case(PATH2, "reachability: Component6 @ Component6.L13 -> Component6.L15"; "Path in Component6 from L13 to L15")]
+    #[test_case(PATH2, "reachability: Component7 @ Component7.L16 -> Component7.L19"; "Overwrite state of location once to reach end state")]
+    #[test_case(PATH2, "reachability: Component9 @ Component9.L23 && Component9.x<5 -> Component9.L26"; "Path in Component9 from L23 x lt 5 to L26")]
+    fn parallel_search_matches_sequential_search(path: &str, query: &str) {
+        let sequential = run_reachability(path, query, false);
+        let parallel = run_reachability(path, query, true);
+        assert_eq!(parallel.is_ok(), sequential.is_ok(), "{}", query);
+        if let (Ok(parallel), Ok(sequential)) = (parallel, sequential) {
+            // The paths may differ, but both are shortest paths
+            assert_eq!(parallel.path.len(), sequential.path.len(), "{}", query);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod temporal_test {
+    use crate::parse_queries;
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::System::query_failures::{QueryResult, TemporalFailure, TemporalResult};
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/Temporal";
+
+    fn run_temporal(query: &str) -> TemporalResult {
+        match json_run_query(PATH, query).unwrap() {
+            QueryResult::Temporal(result) => result,
+            _ => panic!("Inconsistent query result, expected Temporal"),
+        }
+    }
+
+    #[test_case("E<> Lamp: Lamp.On"; "Possibly")]
+    #[test_case("A[] Lamp || Blinker: Lamp.x <= 5 && Blinker.A"; "Invariantly in a composition")]
+    #[test_case("E[] Blinker[1] && Blinker[2]: Blinker[1].x <= 3"; "Eventually always in a conjunction")]
+    #[test_case("A<> Lamp: true"; "Potentially")]
+    #[test_case("leadsto: Lamp || Blinker @ Lamp.On && Lamp.x < 1 --> Lamp.Off"; "Leads to")]
+    fn temporal_grammar_test(query: &str) {
+        let parsed = parse_queries::parse_to_expression_tree(query).unwrap();
+        assert_eq!(parsed.len(), 1);
+    }
+
+    #[test_case("E<> Lamp: Lamp.On && Lamp.x > 4", true; "Lamp can stay on")]
+    #[test_case("E<> Lamp: Lamp.On && Lamp.x > 5", false; "The invariant prevents staying on")]
+    #[test_case("A[] Lamp: Lamp.x >= 0", true; "Clocks are never negative")]
+    #[test_case("A[] Lamp: Lamp.x <= 5", false; "Lamp can stay off forever")]
+    #[test_case("A[] Blinker: Blinker.x <= 3", true; "Blinker never exceeds its invariants")]
+    #[test_case("E[] Lamp: Lamp.Off", true; "Lamp can stay off by delaying forever")]
+    #[test_case("E[] Lamp: Lamp.On", false; "Lamp starts off")]
+    #[test_case("E[] Blinker: Blinker.x <= 3", true; "Blinker cycles forever")]
+    #[test_case("E[] Blinker: Blinker.A", false; "Blinker must leave A")]
+    #[test_case("A<> Lamp: Lamp.Off", true; "Lamp starts off")]
+    #[test_case("A<> Lamp: Lamp.On", false; "Lamp is never forced on")]
+    #[test_case("A<> Blinker: Blinker.B", true; "Blinker is forced to B by the invariant of A")]
+    #[test_case("A<> Blinker: Blinker.x > 3", false; "Blinker never exceeds its invariants")]
+    #[test_case("E[] Urgent: Urgent.L0", false; "Only a Zeno cycle stays in L0")]
+    #[test_case("A<> Urgent: Urgent.L1", true; "Time must diverge, so L0 is left")]
+    #[test_case("A<> Partial: Partial.L1", false; "Waiting past the guard deadlocks in L0")]
+    fn temporal_result_test(query: &str, expected: bool) {
+        assert_eq!(run_temporal(query).is_ok(), expected, "{}", query);
+    }
+
+    #[test]
+    fn possibly_witness_test() {
+        let witness = run_temporal("E<> Lamp: Lamp.On").unwrap().unwrap();
+        let edges: Vec<_> = witness.prefix.path[0]
+            .edges
+            .iter()
+            .map(|e| e.edge_id.clone())
+            .collect();
+        assert_eq!(witness.prefix.path.len(), 1);
+        assert_eq!(edges, vec!["E0"]);
+        assert!(witness.cycle.path.is_empty());
+    }
+
+    #[test]
+    fn eventually_always_witness_is_a_cycle_test() {
+        let witness = run_temporal("E[] Blinker: Blinker.x <= 3")
+            .unwrap()
+            .unwrap();
+        assert!(witness.prefix.path.is_empty());
+        let actions: Vec<_> = witness
+            .cycle
+            .path
+            .iter()
+            .map(|d| d.action.clone())
+            .collect();
+        assert_eq!(actions, vec!["go", "back"]);
+        assert_eq!(
+            witness.cycle.path[0].source_state,
+            witness.cycle.path[1].destination_state
+        );
+    }
+
+    #[test]
+    fn potentially_counterexample_test() {
+        match run_temporal("A<> Blinker: Blinker.x > 3") {
+            Err(TemporalFailure::Counterexample { counterexample, .. }) => {
+                assert_eq!(counterexample.cycle.path.len(), 2)
+            }
+            result => panic!("Expected a counterexample, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn universal_operators_have_no_witness_test() {
+        assert_eq!(run_temporal("A[] Blinker: Blinker.x <= 3"), Ok(None));
+        assert_eq!(run_temporal("A<> Blinker: Blinker.B"), Ok(None));
+    }
+
+    #[test_case("leadsto: Blinker @ Blinker.A --> Blinker.B", true; "The invariant of A forces a move to B")]
+    #[test_case("leadsto: Blinker @ Blinker.A && Blinker.x > 2 --> Blinker.B && Blinker.x < 1", true; "B is entered with the clock reset")]
+    #[test_case("leadsto: Blinker @ Blinker.A --> Blinker.B && Blinker.x > 2", false; "B can be left before the clock exceeds 2")]
+    #[test_case("leadsto: Lamp @ Lamp.Off --> Lamp.On", false; "Lamp can stay off forever")]
+    #[test_case("leadsto: Lamp @ false --> Lamp.On", true; "The premise is never satisfied")]
+    #[test_case("leadsto: Urgent @ Urgent.L0 --> Urgent.L1", true; "The Zeno self-loop cannot avoid L1")]
+    #[test_case("leadsto: Partial @ Partial.L0 --> Partial.L1", false; "Waiting past the guard deadlocks in L0")]
+    fn leads_to_result_test(query: &str, expected: bool) {
+        assert_eq!(run_temporal(query).is_ok(), expected, "{}", query);
+    }
+
+    #[test]
+    fn leads_to_counterexample_is_a_lasso_test() {
+        match run_temporal("leadsto: Blinker @ Blinker.A --> Blinker.B && Blinker.x > 2") {
+            Err(TemporalFailure::NotLeadsTo { counterexample }) => {
+                let actions: Vec<_> = counterexample
+                    .cycle
+                    .path
+                    .iter()
+                    .map(|d| d.action.clone())
+                    .collect();
+                assert_eq!(actions, vec!["go", "back"]);
+                assert!(counterexample.prefix.path.is_empty());
+            }
+            result => panic!("Expected a leads-to counterexample, got {:?}", result),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::logging::setup_logger;
 use crate::DataReader::component_loader::{JsonProjectLoader, XmlProjectLoader};
 use crate::DataReader::parse_queries;
 use crate::ModelObjects::Query;
+use crate::ProtobufServer::services::query_request::Settings;
 use crate::System::extract_system_rep::create_executable_query;
 use crate::System::query_failures::QueryResult;
 use crate::TransitionSystems::transition_system::component_loader_to_transition_system;
@@ -16,7 +17,7 @@ fn try_setup_logging() {
 pub fn xml_refinement_check(PATH: &str, QUERY: &str) -> bool {
     try_setup_logging();
     match xml_run_query(PATH, QUERY) {
-        QueryResult::Refinement(Ok(())) => true,
+        QueryResult::Refinement(Ok(_)) => true,
         QueryResult::Refinement(Err(_)) => false,
         QueryResult::CustomError(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -27,7 +28,7 @@ pub fn json_refinement_check(PATH: &str, QUERY: &str) -> bool {
     try_setup_logging();
 
     match json_run_query(PATH, QUERY).unwrap() {
-        QueryResult::Refinement(Ok(())) => true,
+        QueryResult::Refinement(Ok(_)) => true,
         QueryResult::Refinement(Err(_)) => false,
         QueryResult::CustomError(err) => panic!("{}", err),
         _ => panic!("Not a refinement check"),
@@ -43,6 +44,7 @@ pub fn xml_run_query(PATH: &str, QUERY: &str) -> QueryResult {
     let q = Query {
         query: Option::from(query),
         comment: "".to_string(),
+        ..Default::default()
     };
 
     let mut comp_loader = project_loader.to_comp_loader();
@@ -52,14 +54,22 @@ pub fn xml_run_query(PATH: &str, QUERY: &str) -> QueryResult {
 }
 
 pub fn json_run_query(PATH: &str, QUERY: &str) -> Result<QueryResult, ExecutableQueryError> {
-    let project_loader =
-        JsonProjectLoader::new_loader(String::from(PATH), crate::tests::TEST_SETTINGS);
+    json_run_query_with_settings(PATH, QUERY, crate::tests::TEST_SETTINGS)
+}
+
+pub fn json_run_query_with_settings(
+    PATH: &str,
+    QUERY: &str,
+    settings: Settings,
+) -> Result<QueryResult, ExecutableQueryError> {
+    let project_loader = JsonProjectLoader::new_loader(String::from(PATH), settings);
     let query = parse_queries::parse_to_expression_tree(QUERY)
         .unwrap()
         .remove(0);
     let q = Query {
         query: Option::from(query),
         comment: "".to_string(),
+        ..Default::default()
     };
 
     let mut comp_loader = project_loader.to_comp_loader();
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_query_with_settings;
+    use crate::tests::TEST_SETTINGS;
+    use crate::ProtobufServer::services::query_request::Settings;
+    use crate::System::query_failures::{QueryResult, RefinementResult};
+    use rayon::ThreadPoolBuilder;
+    use test_case::test_case;
+
+    const UNIVERSITY: &str = "samples/json/EcdarUniversity";
+    const BIG: &str = "samples/json/BigRefinement";
+    const FAILURES: &str = "samples/json/RefinementTests";
+
+    fn run_refinement(
+        path: &str,
+        query: &str,
+        parallel_refinement: bool,
+        threads: usize,
+    ) -> RefinementResult {
+        let settings = Settings {
+            parallel_refinement,
+            save_refinement_relations: true,
+            ..TEST_SETTINGS
+        };
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        match thread_pool.install(|| json_run_query_with_settings(path, query, settings).unwrap()) {
+            QueryResult::Refinement(result) => result,
+            _ => panic!("Inconsistent query result, expected Refinement"),
+        }
+    }
+
+    fn relation_locations(result: &RefinementResult) -> Vec<String> {
+        result
+            .as_ref()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .pairs
+            .iter()
+            .map(|pair| pair.locations.to_string())
+            .collect()
+    }
+
+    #[test_case(UNIVERSITY, "refinement: Administration || Researcher || Machine <= Spec"; "University composition refines spec")]
+    #[test_case(UNIVERSITY, "refinement: Machine3 <= Machine3"; "Machine3 refines self")]
+    #[test_case(BIG, "refinement: Comp1 <= Comp1"; "Big component refines self")]
+    fn parallel_refinement_relation_test(path: &str, query: &str) {
+        let sequential = run_refinement(path, query, false, 4);
+        let parallel = run_refinement(path, query, true, 4);
+        assert!(parallel.is_ok(), "{}: {:?}", query, parallel);
+        assert_eq!(
+            relation_locations(&parallel),
+            relation_locations(&sequential)
+        );
+    }
+
+    #[test_case(UNIVERSITY, "refinement: Researcher <= Spec"; "Researcher does not refine spec")]
+    #[test_case(UNIVERSITY, "refinement: (HalfAdm1 && HalfAdm2) || Researcher || Machine <= Spec"; "Half composition does not refine spec")]
+    #[test_case(BIG, "refinement: Comp1 <= Ref1"; "Big component does not refine reference")]
+    #[test_case(FAILURES, "refinement: A <= B"; "Cannot match")]
+    #[test_case(FAILURES, "refinement: A2 <= B2"; "Cuts delay solutions")]
+    #[test_case(FAILURES, "refinement: C <= D"; "Empty initial state")]
+    fn parallel_refinement_failure_test(path: &str, query: &str) {
+        assert!(run_refinement(path, query, false, 4).is_err(), "{}", query);
+        // The failure found in parallel does not depend on the number of threads
+        let single_thread = run_refinement(path, query, true, 1).unwrap_err();
+        let parallel = run_refinement(path, query, true, 4).unwrap_err();
+        assert_eq!(parallel, single_thread, "{}", query);
+    }
+}
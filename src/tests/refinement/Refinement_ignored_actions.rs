@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use crate::DataReader::component_loader::JsonProjectLoader;
+    use crate::DataReader::json_reader::read_queries;
+    use crate::ModelObjects::Query;
+    use crate::System::extract_system_rep::create_executable_query;
+    use crate::System::query_failures::{
+        ConsistencyFailure, QueryResult, RefinementFailure, RefinementPrecondition,
+    };
+
+    const PATH: &str = "samples/json/RefinementTests";
+
+    /// Runs a query given as an entry of Queries.json
+    fn run_json_query(entry: &str) -> QueryResult {
+        let query: Query = serde_json::from_str(entry).unwrap();
+        let project_loader =
+            JsonProjectLoader::new_loader(String::from(PATH), crate::tests::TEST_SETTINGS);
+        let mut comp_loader = project_loader.to_comp_loader();
+        let query = create_executable_query(&query, &mut *comp_loader).unwrap();
+
+        query.execute()
+    }
+
+    #[test_case(r#"{}"#, false; "no ignored outputs")]
+    #[test_case(r#"{"out": false}"#, false; "output not ignored")]
+    #[test_case(r#"{"out": true}"#, true; "output ignored")]
+    fn refinement_with_ignored_outputs(ignored_outputs: &str, refines: bool) {
+        let entry = format!(
+            r#"{{"query": "refinement: A <= B", "comment": "", "ignoredInputs": {{}}, "ignoredOutputs": {}}}"#,
+            ignored_outputs
+        );
+
+        match run_json_query(&entry) {
+            QueryResult::Refinement(result) => assert_eq!(result.is_ok(), refines),
+            result => panic!("Expected a refinement result, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn ignored_input_removes_failure_after_input() {
+        let entry = r#"{"query": "refinement: A2 <= B", "comment": "", "ignoredInputs": {"in": true}, "ignoredOutputs": {}}"#;
+
+        assert!(matches!(
+            run_json_query(entry),
+            QueryResult::Refinement(Ok(_))
+        ));
+    }
+
+    #[test_case(r#"{"in": true}"#, true; "ignored input")]
+    #[test_case(r#"{}"#, false; "no ignored inputs")]
+    fn non_determinism_only_after_ignored_input(ignored_inputs: &str, refines: bool) {
+        let entry = format!(
+            r#"{{"query": "refinement: A3 <= B", "comment": "", "ignoredInputs": {}, "ignoredOutputs": {{}}}}"#,
+            ignored_inputs
+        );
+
+        match run_json_query(&entry) {
+            QueryResult::Refinement(Ok(_)) => assert!(refines),
+            QueryResult::Refinement(Err(RefinementFailure::Precondition(
+                RefinementPrecondition::InconsistentChild(
+                    ConsistencyFailure::NotDeterministic(_),
+                    _,
+                ),
+            ))) => assert!(!refines),
+            result => panic!("Expected a refinement result, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn ignored_actions_default_to_empty() {
+        let query: Query =
+            serde_json::from_str(r#"{"query": "refinement: A <= B", "comment": ""}"#).unwrap();
+
+        assert!(query.get_ignored_inputs().is_empty());
+        assert!(query.get_ignored_outputs().is_empty());
+    }
+
+    #[test]
+    fn queries_file_ignored_actions_are_read() {
+        let queries = read_queries("samples/json/EcdarUniversity").unwrap();
+
+        assert!(!queries.is_empty());
+        for query in queries {
+            assert!(query.get_ignored_inputs().is_empty());
+            assert!(query.get_ignored_outputs().is_empty());
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::{json_run_query, json_run_query_with_settings};
+    use crate::tests::TEST_SETTINGS;
+    use crate::DataReader::json_writer::refinement_relations_to_json_file;
+    use crate::ProtobufServer::services::query_request::Settings;
+    use crate::System::query_failures::QueryResult;
+    use crate::System::specifics::{SpecificLocation, SpecificRelation};
+
+    const PATH: &str = "samples/json/EcdarUniversity";
+
+    fn get_relation(query: &str) -> SpecificRelation {
+        let settings = Settings {
+            save_refinement_relations: true,
+            ..TEST_SETTINGS
+        };
+        match json_run_query_with_settings(PATH, query, settings).unwrap() {
+            QueryResult::Refinement(Ok(Some(relation))) => relation,
+            result => panic!("Expected a refinement relation, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn self_refinement_relates_equal_locations() {
+        let relation = get_relation("refinement: Machine <= Machine");
+
+        assert!(!relation.pairs.is_empty());
+        for pair in relation.pairs {
+            let (left, right) = pair.locations.split();
+            match (left, right) {
+                (
+                    SpecificLocation::ComponentLocation {
+                        location_id: left, ..
+                    },
+                    SpecificLocation::ComponentLocation {
+                        location_id: right, ..
+                    },
+                ) => assert_eq!(left, right),
+                pair => panic!("Expected component locations, got {:?}", pair),
+            }
+        }
+    }
+
+    #[test]
+    fn composition_refinement_has_relation() {
+        let relation = get_relation("refinement: Administration || Researcher || Machine <= Spec");
+
+        assert!(!relation.pairs.is_empty());
+    }
+
+    #[test]
+    fn relation_is_only_built_when_asked_for() {
+        let result = json_run_query(PATH, "refinement: Machine <= Machine").unwrap();
+        assert!(matches!(result, QueryResult::Refinement(Ok(None))));
+    }
+
+    #[test]
+    fn relations_are_saved_as_json() {
+        let query = "refinement: Machine <= Machine";
+        let relation = get_relation(query);
+        let path = std::env::temp_dir().join("reveaal_relations_are_saved_as_json.json");
+
+        refinement_relations_to_json_file(&path, &[(query.to_string(), &relation)]);
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let saved = saved.as_array().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0]["query"], query);
+        assert_eq!(
+            saved[0]["relation"],
+            serde_json::to_value(&relation).unwrap()
+        );
+        assert_eq!(
+            saved[0]["relation"]["pairs"].as_array().unwrap().len(),
+            relation.pairs.len()
+        );
+    }
+}
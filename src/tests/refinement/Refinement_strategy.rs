@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::refinement::Helper::json_run_query;
+    use crate::System::query_failures::{
+        ActionFailure, QueryResult, RefinementPrecondition, StrategyResult,
+    };
+    use crate::System::refinement_strategy::{RefinementStrategy, StrategyNode};
+    use crate::System::specifics::SpecificEdge;
+
+    const PATH: &str = "samples/json/RefinementTests";
+
+    fn get_strategy_result(path: &str, query: &str) -> StrategyResult {
+        match json_run_query(path, query).unwrap() {
+            QueryResult::Strategy(result) => result,
+            result => panic!("Expected a strategy, got {:?}", result),
+        }
+    }
+
+    fn get_strategy(query: &str) -> RefinementStrategy {
+        get_strategy_result(PATH, query)
+            .unwrap()
+            .expect("Expected the refinement to fail")
+    }
+
+    #[test]
+    fn strategy_in_initial_state() {
+        let strategy = get_strategy("strategy: A <= B");
+
+        assert!(strategy.complete);
+        match strategy.root {
+            StrategyNode::CannotMatch { action, .. } => {
+                assert_eq!(action.name, "out");
+                assert!(!action.is_input);
+            }
+            root => panic!("Expected the output to be unmatched, got {:?}", root),
+        }
+    }
+
+    #[test]
+    fn strategy_after_input() {
+        let strategy = get_strategy("strategy: A2 <= B");
+
+        assert!(strategy.complete);
+        let (action, edges, responses) = match strategy.root {
+            StrategyNode::Move {
+                action,
+                edges,
+                responses,
+                ..
+            } => (action, edges, responses),
+            root => panic!("Expected a move from the initial state, got {:?}", root),
+        };
+        assert_eq!(action.name, "in");
+        assert!(action.is_input);
+        assert_eq!(edges, vec![SpecificEdge::new("B", "E6", 1)]);
+
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].edges, vec![SpecificEdge::new("A2", "E7", 0)]);
+        assert!(matches!(
+            &responses[0].next,
+            StrategyNode::CannotMatch { action, .. } if action.name == "out"
+        ));
+    }
+
+    #[test]
+    fn strategy_exports_to_json() {
+        let strategy = get_strategy("strategy: A2 <= B");
+        let json = serde_json::to_value(&strategy).unwrap();
+
+        assert_eq!(json["root"]["kind"], "move");
+        assert_eq!(json["root"]["responses"][0]["next"]["kind"], "cannot_match");
+    }
+
+    #[test]
+    fn no_strategy_when_refinement_holds() {
+        let result = get_strategy_result(
+            "samples/json/EcdarUniversity",
+            "strategy: Machine <= Machine",
+        );
+
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn strategy_precondition_failure() {
+        let result = get_strategy_result(PATH, "strategy: notSubset1 <= notSubset2");
+
+        assert!(matches!(
+            result,
+            Err(RefinementPrecondition::ActionMismatch(
+                ActionFailure::NotSubset(_, _),
+                _
+            ))
+        ));
+    }
+}
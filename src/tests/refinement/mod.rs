@@ -2,7 +2,11 @@ mod AG_Tests;
 mod Big_Refinement;
 mod Conjunction_refinement;
 pub mod Helper;
+mod Parallel_refinement;
 mod Refinement_delay_add;
+mod Refinement_ignored_actions;
+mod Refinement_relation;
+mod Refinement_strategy;
 mod Refinement_university;
 mod Refinement_unspec;
 pub mod xml;
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod test {
+    use crate::tests::save_component::save_comp_helper::util::json_minimised_component_refines_base_self;
+
+    const ECDAR_UNI: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn AdministrationMinimisedRefinesSelf() {
+        json_minimised_component_refines_base_self(ECDAR_UNI, "Administration");
+    }
+
+    #[test]
+    fn ResearcherMinimisedRefinesSelf() {
+        json_minimised_component_refines_base_self(ECDAR_UNI, "Researcher");
+    }
+
+    #[test]
+    fn Adm2MachineMinimisedRefinesSelf() {
+        json_minimised_component_refines_base_self(ECDAR_UNI, "Adm2 || Machine");
+    }
+
+    #[test]
+    fn AdministrationResearcherMachineMinimisedRefinesSelf() {
+        json_minimised_component_refines_base_self(
+            ECDAR_UNI,
+            "Administration || Researcher || Machine",
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bisimulation_tests;
 pub mod composition_tests;
 pub mod conjunction_tests;
 pub mod no_operation_tests;
@@ -3,6 +3,7 @@ pub mod util {
     use crate::DataReader::component_loader::JsonProjectLoader;
     use crate::DataReader::parse_queries;
     use crate::ModelObjects::Expressions::QueryExpression;
+    use crate::System::bisimulation;
     use crate::System::extract_system_rep;
     use crate::System::extract_system_rep::SystemRecipe;
     use crate::System::query_failures::ConsistencyResult;
@@ -30,13 +31,15 @@ pub mod util {
                     &mut *comp_loader,
                     &mut dim,
                     &mut None,
-                ),
+                )
+                .unwrap(),
                 extract_system_rep::get_system_recipe(
                     &expr.system,
                     &mut *comp_loader,
                     &mut dim,
                     &mut None,
-                ),
+                )
+                .unwrap(),
             )
         } else {
             panic!("Failed to create system")
@@ -62,16 +65,60 @@ pub mod util {
         //Only do refinement check if both pass precheck
         if helper(&base_precheck) && helper(&new_precheck) {
             assert!(matches!(
-                refine::check_refinement(new_comp.clone(), base_system.clone()),
-                Ok(())
+                refine::check_refinement(new_comp.clone(), base_system.clone(), false),
+                Ok(Ok(_))
             ));
             assert!(matches!(
-                refine::check_refinement(base_system.clone(), new_comp.clone()),
-                Ok(())
+                refine::check_refinement(base_system.clone(), new_comp.clone(), false),
+                Ok(Ok(_))
             ));
         }
     }
 
+    pub fn json_minimised_component_refines_base_self(input_path: &str, system: &str) {
+        let project_loader =
+            JsonProjectLoader::new_loader(String::from(input_path), crate::tests::TEST_SETTINGS);
+
+        //This query is not executed but simply used to extract the system
+        let str_query = format!("bisim-minim: {} save-as test", system);
+        let query = parse_queries::parse_to_expression_tree(str_query.as_str())
+            .unwrap()
+            .remove(0);
+
+        let mut dim: ClockIndex = 0;
+        let recipe = if let QueryExpression::BisimMinim(expr) = &query {
+            let mut comp_loader = project_loader.to_comp_loader();
+            extract_system_rep::get_system_recipe(
+                &expr.system,
+                &mut *comp_loader,
+                &mut dim,
+                &mut None,
+            )
+            .unwrap()
+        } else {
+            panic!("Failed to create system")
+        };
+
+        let base_system = recipe.compile(dim).unwrap();
+        let minimised = bisimulation::minimise_system_locations(base_system.clone(), dim).unwrap();
+
+        let base_comp = combine_components(&base_system, PruningStrategy::Reachable);
+        let minimised_comp = combine_components(&minimised, PruningStrategy::Reachable);
+        assert!(minimised_comp.locations.len() <= base_comp.locations.len());
+
+        // The minimised system must refine, and be refined by, the base system
+        assert!(helper(&base_system.precheck_sys_rep()));
+        assert!(helper(&minimised.precheck_sys_rep()));
+        assert!(matches!(
+            refine::check_refinement(minimised.clone(), base_system.clone(), false),
+            Ok(Ok(_))
+        ));
+        assert!(matches!(
+            refine::check_refinement(base_system, minimised, false),
+            Ok(Ok(_))
+        ));
+    }
+
     fn helper(a: &ConsistencyResult) -> bool {
         a.is_ok()
     }
@@ -0,0 +1,149 @@
+#[cfg(test)]
+
+mod test {
+    use std::collections::HashSet;
+
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::tests::refinement::Helper::{json_get_system, json_run_query};
+    use crate::DataReader::parse_queries::parse_to_system_expr;
+    use crate::ModelObjects::Expressions::SystemExpression;
+    use crate::System::query_failures::{ActionFailure, QueryResult, SystemRecipeFailure};
+    use crate::System::specifics::SpecificEdge;
+    use crate::TransitionSystems::TransitionID;
+
+    const PATH: &str = "samples/json/RefinementTests";
+    const UNIVERSITY_PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn hiding_is_parsed_as_postfix_operator() {
+        let expr = parse_to_system_expr(r"A \ {out, in} || B").unwrap();
+
+        match expr {
+            SystemExpression::Composition(left, _) => match *left {
+                SystemExpression::Hiding(system, actions) => {
+                    assert!(
+                        matches!(*system, SystemExpression::Component(name, None) if name == "A")
+                    );
+                    assert_eq!(actions, vec!["out".to_string(), "in".to_string()]);
+                }
+                left => panic!("Expected a hiding, got {}", left),
+            },
+            expr => panic!("Expected a composition, got {}", expr),
+        }
+    }
+
+    #[test]
+    fn quotient_is_not_parsed_as_hiding() {
+        let expr = parse_to_system_expr(r"A \\ B").unwrap();
+
+        assert!(matches!(expr, SystemExpression::Quotient(_, _)));
+    }
+
+    #[test]
+    fn composition_with_common_output_fails() {
+        let actual = json_run_query(PATH, "consistency: A || B").unwrap_err();
+        assert!(matches!(
+            actual,
+            ExecutableQueryError::SystemRecipeFailure(SystemRecipeFailure::Action(
+                ActionFailure::NotDisjoint(_, _),
+                _
+            ))
+        ));
+    }
+
+    #[test]
+    fn composition_with_hidden_common_output_succeeds() {
+        let actual = json_run_query(PATH, r"consistency: A \ {out} || B").unwrap();
+        assert!(matches!(actual, QueryResult::Consistency(Ok(_))));
+    }
+
+    #[test]
+    fn hidden_output_becomes_internal_output() {
+        let system = json_get_system(UNIVERSITY_PATH, r"Machine \ {tea}");
+
+        assert_eq!(
+            system.get_output_actions(),
+            HashSet::from(["cof".to_string(), "τtea".to_string()])
+        );
+        assert_eq!(
+            system.get_input_actions(),
+            HashSet::from(["coin".to_string()])
+        );
+    }
+
+    #[test]
+    fn hiding_inputs_leaves_them_as_they_are() {
+        let system = json_get_system(UNIVERSITY_PATH, r"Machine \ {coin}");
+
+        assert_eq!(
+            system.get_input_actions(),
+            HashSet::from(["coin".to_string()])
+        );
+        assert_eq!(
+            system.get_output_actions(),
+            HashSet::from(["cof".to_string(), "tea".to_string()])
+        );
+    }
+
+    #[test]
+    fn hidden_transitions_track_the_hidden_edges() {
+        let system = json_get_system(UNIVERSITY_PATH, r"Machine \ {tea}");
+        let initial = system.get_initial_location().unwrap();
+
+        assert!(system.next_transitions(&initial, "tea").is_empty());
+
+        let transitions = system.next_transitions(&initial, "τtea");
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(
+            transitions[0].id,
+            TransitionID::Hiding(
+                "tea".to_string(),
+                Box::new(TransitionID::Simple("E29".to_string()))
+            )
+        );
+        assert_eq!(
+            SpecificEdge::from_transition_id(transitions[0].id.clone(), system.as_ref()),
+            vec![SpecificEdge::new("Machine", "E29", 0)]
+        );
+    }
+
+    #[test]
+    fn hidden_outputs_are_internal_actions() {
+        let system = json_get_system(UNIVERSITY_PATH, r"Machine \ {tea}");
+
+        assert_eq!(
+            system.get_internal_actions(),
+            HashSet::from(["τtea".to_string()])
+        );
+        assert!(json_get_system(UNIVERSITY_PATH, "Machine")
+            .get_internal_actions()
+            .is_empty());
+    }
+
+    #[test]
+    fn hidden_output_refines_specification_without_it() {
+        let actual = json_run_query(
+            UNIVERSITY_PATH,
+            r"refinement: Machine \ {tea} <= CofMachine",
+        )
+        .unwrap();
+        assert!(matches!(actual, QueryResult::Refinement(Ok(_))));
+    }
+
+    #[test]
+    fn internal_moves_of_specification_need_not_be_outputs() {
+        let actual =
+            json_run_query(UNIVERSITY_PATH, r"refinement: Machine3 <= Machine \ {tea}").unwrap();
+        assert!(matches!(actual, QueryResult::Refinement(Ok(_))));
+    }
+
+    #[test]
+    fn hidden_output_is_matched_by_staying() {
+        let actual = json_run_query(
+            UNIVERSITY_PATH,
+            r"refinement: Machine \ {tea} <= Machine \ {tea}",
+        )
+        .unwrap();
+        assert!(matches!(actual, QueryResult::Refinement(Ok(_))));
+    }
+}
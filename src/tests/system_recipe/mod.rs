@@ -1,4 +1,6 @@
 pub mod compiled_component;
 pub mod composition;
 pub mod conjunction;
+pub mod hiding;
 pub mod quotient;
+pub mod renaming;
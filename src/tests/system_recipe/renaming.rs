@@ -0,0 +1,106 @@
+#[cfg(test)]
+
+mod test {
+    use std::collections::HashSet;
+
+    use crate::extract_system_rep::ExecutableQueryError;
+    use crate::tests::refinement::Helper::{json_get_system, json_run_query};
+    use crate::DataReader::parse_queries::parse_to_system_expr;
+    use crate::ModelObjects::Expressions::SystemExpression;
+    use crate::System::query_failures::{ActionFailure, QueryResult, SystemRecipeFailure};
+    use crate::System::specifics::SpecificEdge;
+    use crate::TransitionSystems::TransitionID;
+    use test_case::test_case;
+
+    const PATH: &str = "samples/json/RefinementTests";
+    const UNIVERSITY_PATH: &str = "samples/json/EcdarUniversity";
+
+    #[test]
+    fn renaming_is_parsed_as_postfix_operator() {
+        let expr = parse_to_system_expr("A[o/out, i/in]").unwrap();
+
+        match expr {
+            SystemExpression::Renaming(system, renamings) => {
+                assert!(matches!(*system, SystemExpression::Component(name, None) if name == "A"));
+                assert_eq!(
+                    renamings,
+                    vec![
+                        ("o".to_string(), "out".to_string()),
+                        ("i".to_string(), "in".to_string())
+                    ]
+                );
+            }
+            expr => panic!("Expected a renaming, got {}", expr),
+        }
+    }
+
+    #[test]
+    fn component_id_is_not_parsed_as_renaming() {
+        let expr = parse_to_system_expr("A[Temp]").unwrap();
+
+        assert!(
+            matches!(expr, SystemExpression::Component(name, Some(id)) if name == "A" && id == "Temp")
+        );
+    }
+
+    #[test]
+    fn composition_with_renamed_common_output_succeeds() {
+        let actual = json_run_query(PATH, "consistency: A || B[o/out]").unwrap();
+        assert!(matches!(actual, QueryResult::Consistency(Ok(_))));
+    }
+
+    #[test]
+    fn renaming_input_to_output_fails() {
+        let actual = json_run_query(PATH, "consistency: A[out/in]").unwrap_err();
+        assert!(matches!(
+            actual,
+            ExecutableQueryError::SystemRecipeFailure(SystemRecipeFailure::Action(
+                ActionFailure::NotDisjoint(_, _),
+                _
+            ))
+        ));
+    }
+
+    #[test]
+    fn renamed_actions_replace_the_old_actions() {
+        let system = json_get_system(UNIVERSITY_PATH, "Machine[c/coin, t/tea, x/unknown]");
+
+        assert_eq!(system.get_input_actions(), HashSet::from(["c".to_string()]));
+        assert_eq!(
+            system.get_output_actions(),
+            HashSet::from(["cof".to_string(), "t".to_string()])
+        );
+    }
+
+    #[test]
+    fn renamed_transitions_track_the_renamed_edges() {
+        let system = json_get_system(UNIVERSITY_PATH, "Machine[c/coin]");
+        let initial = system.get_initial_location().unwrap();
+
+        assert!(system.next_transitions(&initial, "coin").is_empty());
+
+        let transitions = system.next_transitions(&initial, "c");
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(
+            transitions[0].id,
+            TransitionID::Renaming(
+                "c".to_string(),
+                "coin".to_string(),
+                Box::new(TransitionID::Simple("E27".to_string()))
+            )
+        );
+        assert_eq!(
+            SpecificEdge::from_transition_id(transitions[0].id.clone(), system.as_ref()),
+            vec![SpecificEdge::new("Machine", "E27", 0)]
+        );
+    }
+
+    #[test_case("Machine[c/coin] <= Machine[c/coin]", true; "same renaming")]
+    #[test_case("Machine[c/coin] <= Machine", false; "renamed input")]
+    fn refinement_with_renaming(query: &str, refines: bool) {
+        match json_run_query(UNIVERSITY_PATH, &format!("refinement: {}", query)).unwrap() {
+            QueryResult::Refinement(result) => assert_eq!(result.is_ok(), refines),
+            result => panic!("Expected a refinement result, got {:?}", result),
+        }
+    }
+}